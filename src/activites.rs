@@ -1,29 +1,64 @@
 #![allow(dead_code)]
-#[cfg(not(debug_assertions))]
-use directories::ProjectDirs;
+use chrono::{Datelike, Days, Weekday};
 use rand::Rng;
-use std::{collections::BTreeMap, path::PathBuf};
+use ratatui::style::Color;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
     activity_selector::ActivitySelectorValue,
-    file::File,
+    file::{data_dir, File},
     heatmap::{CalendarDate, HeatMapValue},
 };
 
 #[derive(Serialize, Deserialize, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
 pub struct ActivityId(u32);
 
+/**
+ * Identifies a single completion of an activity, apart from other
+ * completions of the same activity on the same date.
+ */
+#[derive(Serialize, Deserialize, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Default)]
+pub struct InstanceId(u64);
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Activity {
     activity_id: ActivityId,
     date: CalendarDate,
+    // Assigned by the store when the activity is added. Zero until then, and
+    // in data saved before activities had instance ids.
+    #[serde(default)]
+    instance_id: InstanceId,
+    // Quantity entered for the completion (e.g. kilometres run), if any.
+    #[serde(default)]
+    value: Option<f32>,
 }
 
 impl Activity {
     pub fn new(activity_id: ActivityId, date: CalendarDate) -> Self {
-        Self { activity_id, date }
+        Self {
+            activity_id,
+            date,
+            instance_id: InstanceId::default(),
+            value: None,
+        }
+    }
+
+    pub fn value(mut self, value: f32) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn activity_id(&self) -> ActivityId {
+        self.activity_id
+    }
+
+    pub fn instance_id(&self) -> InstanceId {
+        self.instance_id
     }
 }
 
@@ -32,8 +67,11 @@ impl HeatMapValue for Activity {
         self.date
     }
 
+    /**
+     * The entered quantity, or one for completions without one.
+     */
     fn heat_map_value(&self) -> f32 {
-        1.0
+        self.value.unwrap_or(1.0)
     }
 }
 
@@ -41,11 +79,141 @@ impl HeatMapValue for Activity {
 pub struct ActivityType {
     pub id: ActivityId,
     pub name: String,
+    // Freeform labels used to filter activity types.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // Heat displayed as the hottest color, or None to scale to the data.
+    #[serde(default)]
+    pub heat_max: Option<f32>,
+    // Color the activity is displayed in, or None for the default.
+    #[serde(default)]
+    pub color: Option<ActivityColor>,
+    // Days of the week the activity is meant to be done on, or empty for
+    // every day.
+    #[serde(default)]
+    pub schedule: Vec<Weekday>,
+    // Number of times a week the activity is meant to be done, if any.
+    #[serde(default)]
+    pub weekly_target: Option<u32>,
+    // Value each completion of the activity is given, unless another is
+    // entered, if any.
+    #[serde(default)]
+    pub default_value: Option<f32>,
 }
 
 impl ActivityType {
     fn new(id: ActivityId, name: String) -> Self {
-        Self { id, name }
+        Self {
+            id,
+            name,
+            tags: vec![],
+            heat_max: None,
+            color: None,
+            schedule: vec![],
+            weekly_target: None,
+            default_value: None,
+        }
+    }
+
+    /**
+     * Whether the activity is meant to be done on the date's day of the week.
+     */
+    pub fn scheduled_on(&self, date: CalendarDate) -> bool {
+        self.schedule.is_empty() || self.schedule.contains(&date.weekday())
+    }
+
+    /**
+     * A completion of the activity on a date, given the activity's default
+     * value if it has one.
+     */
+    pub fn completion(&self, date: CalendarDate) -> Activity {
+        let activity = Activity::new(self.id, date);
+        match self.default_value {
+            Some(default_value) => activity.value(default_value),
+            None => activity,
+        }
+    }
+
+    /**
+     * Number of days between start and end (inclusive) the activity is
+     * scheduled on.
+     */
+    pub fn scheduled_days_between(&self, start: CalendarDate, end: CalendarDate) -> usize {
+        start
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .filter(|date| self.scheduled_on(*date))
+            .count()
+    }
+}
+
+/**
+ * The colors an activity can be displayed in.
+ */
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum ActivityColor {
+    Green,
+    Cyan,
+    Blue,
+    Magenta,
+    Red,
+    Yellow,
+}
+
+impl ActivityColor {
+    const ALL: [ActivityColor; 6] = [
+        ActivityColor::Green,
+        ActivityColor::Cyan,
+        ActivityColor::Blue,
+        ActivityColor::Magenta,
+        ActivityColor::Red,
+        ActivityColor::Yellow,
+    ];
+
+    /**
+     * The color after `color`, cycling back to no color after the last one.
+     */
+    pub fn next(color: Option<Self>) -> Option<Self> {
+        match color {
+            None => Self::ALL.first().copied(),
+            Some(color) => Self::ALL.into_iter().skip_while(|c| *c != color).nth(1),
+        }
+    }
+
+    /**
+     * The color before `color`, cycling back to no color before the first one.
+     */
+    pub fn previous(color: Option<Self>) -> Option<Self> {
+        match color {
+            None => Self::ALL.last().copied(),
+            Some(color) => Self::ALL
+                .into_iter()
+                .rev()
+                .skip_while(|c| *c != color)
+                .nth(1),
+        }
+    }
+
+    pub fn to_color(self) -> Color {
+        match self {
+            ActivityColor::Green => Color::Green,
+            ActivityColor::Cyan => Color::Cyan,
+            ActivityColor::Blue => Color::Blue,
+            ActivityColor::Magenta => Color::Magenta,
+            ActivityColor::Red => Color::Red,
+            ActivityColor::Yellow => Color::Yellow,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ActivityColor::Green => "green",
+            ActivityColor::Cyan => "cyan",
+            ActivityColor::Blue => "blue",
+            ActivityColor::Magenta => "magenta",
+            ActivityColor::Red => "red",
+            ActivityColor::Yellow => "yellow",
+        }
     }
 }
 
@@ -57,21 +225,77 @@ pub struct ActivityTypesStore {
 impl ActivityTypesStore {
     /**
      * Create a new activity type. A random new ActivityId is given to the
-     * activity type.
+     * activity type. Surrounding whitespace is trimmed from the name.
      */
     pub fn create_new_activity(&mut self, name: String) -> ActivityId {
         let id = self.next_unused_id();
-        let activity_type = ActivityType::new(id, name);
+        let activity_type = ActivityType::new(id, name.trim().to_owned());
         self.types.insert(activity_type.id, activity_type);
         id
     }
 
     /**
-     * Update the name of an activity type.
+     * Update the name of an activity type. Surrounding whitespace is trimmed
+     * from the name.
      */
     pub fn update_activity(&mut self, name: String, activity_id: ActivityId) {
         let activity_type = self.types.get_mut(&activity_id).unwrap();
-        activity_type.name = name;
+        activity_type.name = name.trim().to_owned();
+    }
+
+    /**
+     * Replace the tags of an activity type. Tags are trimmed, and empty or
+     * repeated tags are dropped.
+     */
+    pub fn update_tags(&mut self, activity_id: ActivityId, tags: Vec<String>) {
+        let activity_type = self.types.get_mut(&activity_id).unwrap();
+        activity_type.tags.clear();
+        for tag in tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !activity_type.tags.iter().any(|t| t == tag) {
+                activity_type.tags.push(tag.to_owned());
+            }
+        }
+    }
+
+    pub fn update_heat_max(&mut self, activity_id: ActivityId, heat_max: Option<f32>) {
+        self.types.get_mut(&activity_id).unwrap().heat_max = heat_max;
+    }
+
+    pub fn update_color(&mut self, activity_id: ActivityId, color: Option<ActivityColor>) {
+        self.types.get_mut(&activity_id).unwrap().color = color;
+    }
+
+    pub fn update_weekly_target(&mut self, activity_id: ActivityId, weekly_target: Option<u32>) {
+        self.types.get_mut(&activity_id).unwrap().weekly_target = weekly_target;
+    }
+
+    pub fn update_default_value(&mut self, activity_id: ActivityId, default_value: Option<f32>) {
+        self.types.get_mut(&activity_id).unwrap().default_value = default_value;
+    }
+
+    /**
+     * Replace the days of the week an activity type is scheduled on. The days
+     * are ordered from Monday, without repeats.
+     */
+    pub fn update_schedule(&mut self, activity_id: ActivityId, mut schedule: Vec<Weekday>) {
+        schedule.sort_by_key(Weekday::num_days_from_monday);
+        schedule.dedup();
+        self.types.get_mut(&activity_id).unwrap().schedule = schedule;
+    }
+
+    /**
+     * All tags used by any activity type, sorted and without repeats.
+     */
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .types
+            .values()
+            .flat_map(|activity_type| activity_type.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
     }
 
     /**
@@ -85,6 +309,10 @@ impl ActivityTypesStore {
         self.types.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
     fn next_unused_id(&self) -> ActivityId {
         let mut rng = rand::thread_rng();
         let mut id = rng.gen::<u32>();
@@ -105,46 +333,154 @@ impl ActivityTypesStore {
 }
 
 impl File for ActivityTypesStore {
-    #[cfg(not(debug_assertions))]
-    fn path() -> PathBuf {
-        let mut base = ProjectDirs::from("com", "dleamy", "daila")
-            .unwrap()
-            .data_dir()
-            .to_path_buf();
-        base.push("activity_types.json");
-        base
-    }
-    #[cfg(debug_assertions)]
     fn path() -> PathBuf {
-        let mut crate_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        crate_root.push("data/activity_types.json");
-        crate_root
+        data_dir().join("activity_types.json")
     }
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct ActivitiesStore {
     days: BTreeMap<CalendarDate, Vec<Activity>>,
+    // Instance id given to the next activity added.
+    #[serde(default)]
+    next_instance_id: u64,
+    // Activities done before this date are kept in the archive file, apart
+    // from those done later, if they were ever archived.
+    #[serde(default)]
+    archived_before: Option<CalendarDate>,
+    // Totals of the archived activities, counted while they aren't loaded.
+    #[serde(default)]
+    archive_summary: Option<ArchiveSummary>,
+    // Note written about each day that has one, e.g. a journal entry. Notes
+    // are never archived.
+    #[serde(default)]
+    notes: BTreeMap<CalendarDate, String>,
+    // Date ranges (inclusive, sorted and apart) frozen, e.g. while on
+    // vacation, on which streaks are neither broken nor extended.
+    #[serde(default)]
+    frozen: Vec<(CalendarDate, CalendarDate)>,
+    // Whether the archived activities were merged into the store, and are
+    // to be split back out of it when it is saved.
+    #[serde(skip)]
+    archive_loaded: bool,
+    // Incremented whenever the activities change.
+    #[serde(skip)]
+    revision: u64,
 }
 
 impl ActivitiesStore {
-    pub fn add_activity(&mut self, activity: Activity) {
+    /**
+     * A number which changes whenever the activities change, used to
+     * invalidate values computed from the store.
+     */
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn add_activity(&mut self, mut activity: Activity) {
+        self.revision += 1;
+        activity.instance_id = self.take_instance_id();
         let date = activity.date;
-        let activities = self.days.entry(date).or_insert_with(Vec::new);
+        let activities = self.days.entry(date).or_default();
         activities.push(activity);
     }
 
+    /**
+     * Remove every completion of an activity on its date.
+     */
     pub fn remove_activity(&mut self, activity: Activity) {
-        let activities = self.days.get_mut(&activity.date).unwrap();
+        let Some(activities) = self.days.get_mut(&activity.date) else {
+            return;
+        };
+        self.revision += 1;
         activities.retain(|a| a.activity_id != activity.activity_id);
+        if activities.is_empty() {
+            self.days.remove(&activity.date);
+        }
     }
 
-    pub fn activities_on_date(&mut self, date: CalendarDate) -> &mut Vec<Activity> {
-        if !self.days.contains_key(&date) {
-            self.days.insert(date, Vec::new()).unwrap();
+    /**
+     * Remove exactly one completion on a date: the one with the given
+     * instance id. Returns false if there isn't one.
+     */
+    pub fn remove_activity_instance(
+        &mut self,
+        date: CalendarDate,
+        instance_id: InstanceId,
+    ) -> bool {
+        let Some(activities) = self.days.get_mut(&date) else {
+            return false;
+        };
+        let Some(index) = activities.iter().position(|a| a.instance_id == instance_id) else {
+            return false;
+        };
+
+        self.revision += 1;
+        activities.remove(index);
+        if activities.is_empty() {
+            self.days.remove(&date);
+        }
+        true
+    }
+
+    /**
+     * Remove a single completion of an activity, lowering its count on the
+     * date by one. Returns false if it wasn't completed on the date.
+     */
+    pub fn remove_one_activity(&mut self, activity: Activity) -> bool {
+        let activities = match self.days.get_mut(&activity.date) {
+            Some(activities) => activities,
+            None => return false,
+        };
+        let index = match activities
+            .iter()
+            .position(|a| a.activity_id == activity.activity_id)
+        {
+            Some(index) => index,
+            None => return false,
+        };
+
+        self.revision += 1;
+        activities.remove(index);
+        if activities.is_empty() {
+            self.days.remove(&activity.date);
         }
+        true
+    }
+
+    /**
+     * Move every completion of one activity to another, e.g. when merging
+     * two activity types. Returns the number of completions moved.
+     */
+    pub fn reassign(&mut self, from: ActivityId, to: ActivityId) -> usize {
+        // Archived completions are moved too.
+        self.load_archive();
+        self.revision += 1;
+        let mut moved = 0;
+        for activity in self.days.values_mut().flatten() {
+            if activity.activity_id == from {
+                activity.activity_id = to;
+                moved += 1;
+            }
+        }
+        moved
+    }
 
-        self.days.get_mut(&date).unwrap()
+    /**
+     * Number of times an activity was completed on a date.
+     */
+    pub fn activity_count(&self, date: CalendarDate, activity_id: ActivityId) -> u32 {
+        self.days.get(&date).map_or(0, |activities| {
+            activities
+                .iter()
+                .filter(|activity| activity.activity_id == activity_id)
+                .count() as u32
+        })
+    }
+
+    pub fn activities_on_date(&mut self, date: CalendarDate) -> &mut Vec<Activity> {
+        self.revision += 1;
+        self.days.entry(date).or_default()
     }
 
     pub fn activity_completed(&self, date: CalendarDate, activity_type: &ActivityType) -> bool {
@@ -161,29 +497,510 @@ impl ActivitiesStore {
         self.days.values().flatten().collect()
     }
 
+    /**
+     * The earliest date any activity was completed on, archived or not.
+     */
+    pub fn first_tracked_date(&self) -> Option<CalendarDate> {
+        self.unloaded_archive()
+            .and_then(|archive| archive.first_tracked_date)
+            .or_else(|| {
+                self.days
+                    .iter()
+                    .find(|(_, activities)| !activities.is_empty())
+                    .map(|(date, _)| *date)
+            })
+    }
+
+    /**
+     * Number of distinct days any activity was completed on, archived or not.
+     */
+    pub fn days_tracked(&self) -> usize {
+        let days_tracked = self
+            .days
+            .values()
+            .filter(|activities| !activities.is_empty())
+            .count();
+        days_tracked
+            + self
+                .unloaded_archive()
+                .map_or(0, |archive| archive.days_tracked)
+    }
+
+    /**
+     * Number of completions of every activity, archived or not.
+     */
+    pub fn completion_count(&self) -> usize {
+        let completions = self.days.values().map(Vec::len).sum::<usize>();
+        completions
+            + self
+                .unloaded_archive()
+                .map_or(0, |archive| archive.completions)
+    }
+
     pub fn activities_with_type(&self, activity_type: &ActivityType) -> Vec<&Activity> {
         self.activities()
             .into_iter()
             .filter(|activity| activity.activity_id == activity_type.id)
             .collect()
     }
+
+    /**
+     * The most recent date, on or before the given date, on which an activity
+     * was completed. None if it was never completed by that date.
+     */
+    pub fn last_completed_on_or_before(
+        &self,
+        activity_id: ActivityId,
+        date: CalendarDate,
+    ) -> Option<CalendarDate> {
+        self.days
+            .range(..=date)
+            .rev()
+            .find(|(_, activities)| {
+                activities
+                    .iter()
+                    .any(|activity| activity.activity_id == activity_id)
+            })
+            .map(|(date, _)| *date)
+            .or_else(|| {
+                self.archived_last_completed(activity_id)
+                    .filter(|last_completed| *last_completed <= date)
+            })
+    }
+
+    /**
+     * The last date strictly before the given date on which an activity was
+     * completed.
+     */
+    pub fn last_completed_before(
+        &self,
+        activity_id: ActivityId,
+        date: CalendarDate,
+    ) -> Option<CalendarDate> {
+        self.days
+            .range(..date)
+            .rev()
+            .find(|(_, activities)| {
+                activities
+                    .iter()
+                    .any(|activity| activity.activity_id == activity_id)
+            })
+            .map(|(date, _)| *date)
+            .or_else(|| {
+                self.archived_last_completed(activity_id)
+                    .filter(|last_completed| *last_completed < date)
+            })
+    }
+
+    /**
+     * Number of consecutive days, up to the given date, on which an activity
+     * was completed. A streak that continues through the previous day is
+     * still current if the activity hasn't been completed yet on the date.
+     * Days the activity isn't scheduled on don't break the streak, and
+     * frozen days neither break nor extend it.
+     */
+    pub fn streak(&self, activity_type: &ActivityType, date: CalendarDate) -> u32 {
+        self.streak_walk(activity_type, date).0
+    }
+
+    /**
+     * Whether the streak of an activity up to a date continues into archived
+     * activities which aren't loaded, so that it can't be counted in full.
+     */
+    pub fn streak_reaches_archive(&self, activity_type: &ActivityType, date: CalendarDate) -> bool {
+        self.streak_walk(activity_type, date).1
+    }
+
+    /**
+     * The streak of an activity up to a date, and whether counting it stopped
+     * at archived activities which aren't loaded.
+     */
+    fn streak_walk(&self, activity_type: &ActivityType, date: CalendarDate) -> (u32, bool) {
+        let completed_on = |date: CalendarDate| self.activity_completed(date, activity_type);
+        let unloaded_cutoff = self
+            .archived_before
+            .filter(|_| self.unloaded_archive().is_some());
+        let mut date = if completed_on(date) {
+            date
+        } else {
+            match date.pred_opt() {
+                Some(date) => date,
+                None => return (0, false),
+            }
+        };
+
+        let mut streak = 0;
+        loop {
+            if unloaded_cutoff.is_some_and(|cutoff| date < cutoff) {
+                return (streak, true);
+            }
+            let frozen = self.is_frozen(date);
+            if completed_on(date) && !frozen {
+                streak += 1;
+            } else if activity_type.scheduled_on(date) && !frozen {
+                break;
+            }
+            date = match date.pred_opt() {
+                Some(date) => date,
+                None => break,
+            };
+        }
+        (streak, false)
+    }
+
+    /**
+     * Activities of every type completed between start and end (inclusive).
+     */
+    pub fn activities_between(&self, start: CalendarDate, end: CalendarDate) -> Vec<&Activity> {
+        self.days
+            .range(start..=end)
+            .flat_map(|(_, activities)| activities)
+            .collect()
+    }
+
+    /**
+     * Activities with a given type completed between start and end (inclusive).
+     */
+    pub fn activities_with_type_between(
+        &self,
+        activity_id: ActivityId,
+        start: CalendarDate,
+        end: CalendarDate,
+    ) -> Vec<&Activity> {
+        self.days
+            .range(start..=end)
+            .flat_map(|(_, activities)| activities)
+            .filter(|activity| activity.activity_id == activity_id)
+            .collect()
+    }
+
+    /**
+     * For each day of the week, from Monday, the share of those days between
+     * start and end (inclusive) on which an activity was completed, so that
+     * days of the week occurring more often in the range don't stand out.
+     * None for days of the week not in the range.
+     */
+    pub fn weekday_completion_rates(
+        &self,
+        activity_type: &ActivityType,
+        start: CalendarDate,
+        end: CalendarDate,
+    ) -> [Option<f32>; 7] {
+        let completed_dates: BTreeSet<CalendarDate> = self
+            .activities_with_type(activity_type)
+            .into_iter()
+            .map(|activity| activity.date)
+            .filter(|date| (start..=end).contains(date))
+            .collect();
+        let completed = completed_dates.iter().fold([0; 7], |mut counts, date| {
+            counts[date.weekday().num_days_from_monday() as usize] += 1;
+            counts
+        });
+        let occurrences =
+            start
+                .iter_days()
+                .take_while(|date| *date <= end)
+                .fold([0; 7], |mut counts, date| {
+                    counts[date.weekday().num_days_from_monday() as usize] += 1;
+                    counts
+                });
+        std::array::from_fn(|weekday| {
+            (occurrences[weekday] > 0)
+                .then(|| completed[weekday] as f32 / occurrences[weekday] as f32)
+        })
+    }
+
+    /**
+     * Number of days between start and end (inclusive) on which an activity
+     * was completed at least once.
+     */
+    pub fn days_completed_between(
+        &self,
+        activity_id: ActivityId,
+        start: CalendarDate,
+        end: CalendarDate,
+    ) -> usize {
+        self.days
+            .range(start..=end)
+            .filter(|(_, activities)| {
+                activities
+                    .iter()
+                    .any(|activity| activity.activity_id == activity_id)
+            })
+            .count()
+    }
 }
 
-impl File for ActivitiesStore {
-    #[cfg(not(debug_assertions))]
+impl ActivitiesStore {
+    fn take_instance_id(&mut self) -> InstanceId {
+        // Zero is left for activities without an id.
+        self.next_instance_id = self.next_instance_id.max(1);
+        let instance_id = InstanceId(self.next_instance_id);
+        self.next_instance_id += 1;
+        instance_id
+    }
+
+    /**
+     * Remove the activities done before a date, returning them in a store of
+     * their own.
+     */
+    pub fn split_before(&mut self, cutoff: CalendarDate) -> ActivitiesStore {
+        let later = self.days.split_off(&cutoff);
+        let earlier = std::mem::replace(&mut self.days, later);
+        self.revision += 1;
+        ActivitiesStore {
+            days: earlier,
+            next_instance_id: self.next_instance_id,
+            ..ActivitiesStore::default()
+        }
+    }
+
+    /**
+     * Add every activity of another store, keeping their instance ids.
+     */
+    pub fn merge(&mut self, other: ActivitiesStore) {
+        self.revision += 1;
+        self.next_instance_id = self.next_instance_id.max(other.next_instance_id);
+        for (date, activities) in other.days {
+            self.days.entry(date).or_default().extend(activities);
+        }
+    }
+
+    /**
+     * The note written about a date, if any.
+     */
+    pub fn note(&self, date: CalendarDate) -> Option<&str> {
+        self.notes.get(&date).map(String::as_str)
+    }
+
+    /**
+     * Set the note about a date, removing it if it is blank.
+     */
+    pub fn set_note(&mut self, date: CalendarDate, note: String) {
+        self.revision += 1;
+        if note.trim().is_empty() {
+            self.notes.remove(&date);
+        } else {
+            self.notes.insert(date, note);
+        }
+    }
+
+    /**
+     * The frozen date ranges, sorted.
+     */
+    pub fn frozen(&self) -> &[(CalendarDate, CalendarDate)] {
+        &self.frozen
+    }
+
+    pub fn is_frozen(&self, date: CalendarDate) -> bool {
+        self.frozen
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&date))
+    }
+
+    /**
+     * Freeze the dates between start and end (inclusive), joining the range
+     * with any frozen ranges it overlaps or touches.
+     */
+    pub fn freeze(&mut self, start: CalendarDate, end: CalendarDate) {
+        self.revision += 1;
+        let (mut start, mut end) = (start, end);
+        self.frozen.retain(|(other_start, other_end)| {
+            let apart = other_end.succ_opt().is_some_and(|after| after < start)
+                || end.succ_opt().is_some_and(|after| after < *other_start);
+            if !apart {
+                start = start.min(*other_start);
+                end = end.max(*other_end);
+            }
+            apart
+        });
+        self.frozen.push((start, end));
+        self.frozen.sort();
+    }
+
+    /**
+     * Unfreeze the dates between start and end (inclusive), splitting any
+     * frozen range they are in the middle of.
+     */
+    pub fn unfreeze(&mut self, start: CalendarDate, end: CalendarDate) {
+        self.revision += 1;
+        let mut frozen = vec![];
+        for (other_start, other_end) in self.frozen.drain(..) {
+            if other_end < start || end < other_start {
+                frozen.push((other_start, other_end));
+                continue;
+            }
+            if let Some(before) = start.pred_opt().filter(|before| other_start <= *before) {
+                frozen.push((other_start, before));
+            }
+            if let Some(after) = end.succ_opt().filter(|after| *after <= other_end) {
+                frozen.push((after, other_end));
+            }
+        }
+        self.frozen = frozen;
+    }
+
+    /**
+     * The date activities done before are archived, if any are.
+     */
+    pub fn archived_before(&self) -> Option<CalendarDate> {
+        self.archived_before
+    }
+
+    /**
+     * Totals of the archived activities, if there are some and they aren't
+     * loaded.
+     */
+    fn unloaded_archive(&self) -> Option<&ArchiveSummary> {
+        self.archive_summary
+            .as_ref()
+            .filter(|_| self.archived_before.is_some() && !self.archive_loaded)
+    }
+
+    fn archived_last_completed(&self, activity_id: ActivityId) -> Option<CalendarDate> {
+        self.unloaded_archive()
+            .and_then(|archive| archive.last_completed.get(&activity_id))
+            .copied()
+    }
+
+    /**
+     * Merge the archived activities into the store, if there are any and they
+     * aren't already. They are still saved to the archive.
+     */
+    pub fn load_archive(&mut self) {
+        if self.archived_before.is_none() || self.archive_loaded {
+            return;
+        }
+        self.merge_archive(ActivitiesArchive::load().0);
+    }
+
+    fn merge_archive(&mut self, archive: ActivitiesStore) {
+        self.merge(archive);
+        self.archive_loaded = true;
+    }
+
+    /**
+     * Save the activities done before `cutoff` to the archive file instead of
+     * the activities file, so that it stays small. Activities archived
+     * earlier with a later cutoff are moved back. Returns the number of
+     * activities archived.
+     */
+    pub fn archive_before(&mut self, cutoff: CalendarDate) -> usize {
+        self.load_archive();
+        self.archived_before = Some(cutoff);
+        self.archive_loaded = true;
+        self.days
+            .range(..cutoff)
+            .map(|(_, activities)| activities.len())
+            .sum()
+    }
+
+    /**
+     * Keep every activity in the activities file again. The archive file is
+     * left as is, and is ignored until activities are next archived.
+     */
+    pub fn unarchive(&mut self) {
+        self.load_archive();
+        self.archived_before = None;
+        self.archive_summary = None;
+        self.archive_loaded = false;
+    }
+}
+
+impl ActivitiesStore {
+    /**
+     * The activities to save to the activities file and those to save to the
+     * archive file, if the archived activities are loaded.
+     */
+    fn split_archive(&self) -> Option<(ActivitiesStore, ActivitiesStore)> {
+        let cutoff = self.archived_before.filter(|_| self.archive_loaded)?;
+        let mut current = self.clone();
+        let archive = current.split_before(cutoff);
+        current.archive_summary = Some(ArchiveSummary::new(&archive));
+        Some((current, archive))
+    }
+}
+
+/**
+ * Totals of the archived activities, saved in the activities file so that
+ * they are counted without loading the archive.
+ */
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct ArchiveSummary {
+    first_tracked_date: Option<CalendarDate>,
+    days_tracked: usize,
+    completions: usize,
+    // Last date each activity was completed on.
+    last_completed: BTreeMap<ActivityId, CalendarDate>,
+}
+
+impl ArchiveSummary {
+    fn new(archive: &ActivitiesStore) -> Self {
+        let mut last_completed = BTreeMap::new();
+        for activity in archive.activities() {
+            last_completed.insert(activity.activity_id, activity.date);
+        }
+        ArchiveSummary {
+            first_tracked_date: archive.first_tracked_date(),
+            days_tracked: archive.days_tracked(),
+            completions: archive.completion_count(),
+            last_completed,
+        }
+    }
+}
+
+/**
+ * Activities split out of the activities file by `archive_before`.
+ */
+#[derive(Serialize, Deserialize, Default)]
+#[serde(transparent)]
+struct ActivitiesArchive(ActivitiesStore);
+
+impl File for ActivitiesArchive {
     fn path() -> PathBuf {
-        let mut base = ProjectDirs::from("com", "dleamy", "daila")
-            .unwrap()
-            .data_dir()
-            .to_path_buf();
-        base.push("activities.json");
-        base
-    }
-    #[cfg(debug_assertions)]
+        data_dir().join("activities-archive.json")
+    }
+}
+
+impl File for ActivitiesStore {
     fn path() -> PathBuf {
-        let mut crate_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        crate_root.push("data/activities.json");
-        crate_root
+        data_dir().join("activities.json")
+    }
+
+    /**
+     * Save the archived activities to the archive file, if they were loaded,
+     * and the rest to the activities file.
+     */
+    fn save(&self) {
+        let Some((current, archive)) = self.split_archive() else {
+            self.write();
+            return;
+        };
+        // The archive is written first, so that no activities are lost if
+        // Daila stops between the writes.
+        ActivitiesArchive(archive).write();
+        current.write();
+    }
+
+    /**
+     * Give an instance id to each activity saved before activities had them.
+     */
+    fn migrate(&mut self) {
+        let max_instance_id = self
+            .activities()
+            .iter()
+            .map(|activity| activity.instance_id.0)
+            .max()
+            .unwrap_or(0);
+        self.next_instance_id = self.next_instance_id.max(max_instance_id + 1);
+
+        let mut days = std::mem::take(&mut self.days);
+        for activity in days.values_mut().flatten() {
+            if activity.instance_id == InstanceId::default() {
+                activity.instance_id = self.take_instance_id();
+            }
+        }
+        self.days = days;
     }
 }
 
@@ -191,6 +1008,18 @@ impl File for ActivitiesStore {
 pub struct ActivityOption {
     activity_type: ActivityType,
     completed: bool,
+    // Number of times the activity was completed on the date of the option.
+    count: u32,
+    // Current streak of the activity, as of the date of the option.
+    streak: u32,
+    // Days between the date of the option and the last day before it that
+    // the activity was completed, if it ever was.
+    days_since_completed: Option<i64>,
+    // Whether the activity is scheduled on the date of the option.
+    scheduled: bool,
+    // Number of times the activity was completed in the week of the date of
+    // the option, up to the date.
+    week_count: u32,
 }
 
 impl ActivitySelectorValue for ActivityOption {
@@ -201,19 +1030,89 @@ impl ActivitySelectorValue for ActivityOption {
     fn completed(&self) -> bool {
         self.completed
     }
+
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    fn streak(&self) -> u32 {
+        self.streak
+    }
+
+    fn color(&self) -> Option<Color> {
+        self.activity_type.color.map(ActivityColor::to_color)
+    }
+
+    fn dimmed(&self) -> bool {
+        !self.scheduled
+    }
+
+    fn progress(&self) -> Option<(u32, u32)> {
+        let target = self.activity_type.weekly_target?;
+        Some((self.week_count, target))
+    }
+
+    fn hint(&self) -> Option<String> {
+        if self.completed {
+            return None;
+        }
+        if !self.scheduled {
+            return Some(String::from("(not scheduled)"));
+        }
+        Some(match self.days_since_completed {
+            Some(days) => format!("({}d ago)", days),
+            None => String::from("(never)"),
+        })
+    }
 }
 
 impl ActivityOption {
-    pub fn new(activity_type: ActivityType, completed: bool) -> Self {
+    pub fn new(
+        activity_type: ActivityType,
+        count: u32,
+        streak: u32,
+        days_since_completed: Option<i64>,
+        scheduled: bool,
+        week_count: u32,
+    ) -> Self {
         Self {
             activity_type,
-            completed,
+            completed: count > 0,
+            count,
+            streak,
+            days_since_completed,
+            scheduled,
+            week_count,
         }
     }
 
     pub fn activity_id(&self) -> ActivityId {
         self.activity_type.id
     }
+
+    pub fn tags(&self) -> &[String] {
+        &self.activity_type.tags
+    }
+
+    pub fn heat_max(&self) -> Option<f32> {
+        self.activity_type.heat_max
+    }
+
+    pub fn activity_color(&self) -> Option<ActivityColor> {
+        self.activity_type.color
+    }
+
+    pub fn schedule(&self) -> &[Weekday] {
+        &self.activity_type.schedule
+    }
+
+    pub fn scheduled(&self) -> bool {
+        self.scheduled
+    }
+
+    pub fn activity_type(&self) -> &ActivityType {
+        &self.activity_type
+    }
 }
 
 pub fn activity_options(
@@ -221,15 +1120,300 @@ pub fn activity_options(
     activities: &ActivitiesStore,
     date: CalendarDate,
 ) -> Vec<ActivityOption> {
+    // Weeks start on Monday.
+    let week_start = date - Days::new(date.weekday().num_days_from_monday().into());
     let mut options: Vec<ActivityOption> = activity_types
         .activity_types()
         .into_iter()
         .map(|activity_type| {
-            let completed = activities.activity_completed(date, activity_type);
-            ActivityOption::new(activity_type.to_owned(), completed)
+            let count = activities.activity_count(date, activity_type.id);
+            let streak = activities.streak(activity_type, date);
+            let days_since_completed = activities
+                .last_completed_before(activity_type.id, date)
+                .map(|last_completed| date.signed_duration_since(last_completed).num_days());
+            let week_count = activities
+                .activities_with_type_between(activity_type.id, week_start, date)
+                .len() as u32;
+            ActivityOption::new(
+                activity_type.to_owned(),
+                count,
+                streak,
+                days_since_completed,
+                activity_type.scheduled_on(date),
+                week_count,
+            )
         })
         .collect();
-    options.sort_by(|a, b| a.activity_id().0.cmp(&b.activity_id().0));
+    options.sort_by_key(|option| option.activity_id().0);
 
     options
 }
+
+/**
+ * The order activity options are displayed in.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+pub enum ActivitySortMode {
+    // Order of the activity types' ids: stable, but otherwise arbitrary.
+    #[default]
+    Id,
+    Name,
+    // Most recently completed first.
+    MostRecent,
+    // Lowest completion rate over the scheduled days of the last 30 days
+    // first.
+    LeastDone,
+}
+
+// Number of days, ending on the date of the options, the completion rate is computed over.
+pub const COMPLETION_RATE_DAYS: u64 = 30;
+
+impl ActivitySortMode {
+    pub fn next(self) -> Self {
+        match self {
+            ActivitySortMode::Id => ActivitySortMode::Name,
+            ActivitySortMode::Name => ActivitySortMode::MostRecent,
+            ActivitySortMode::MostRecent => ActivitySortMode::LeastDone,
+            ActivitySortMode::LeastDone => ActivitySortMode::Id,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ActivitySortMode::Id => "by id",
+            ActivitySortMode::Name => "by name",
+            ActivitySortMode::MostRecent => "most recent",
+            ActivitySortMode::LeastDone => "least done",
+        }
+    }
+}
+
+/**
+ * Order activity options, as of a date. Ties keep their order by id.
+ */
+pub fn sort_activity_options(
+    options: &mut [ActivityOption],
+    sort_mode: ActivitySortMode,
+    activities: &ActivitiesStore,
+    date: CalendarDate,
+) {
+    options.sort_by_key(|option| option.activity_id().0);
+    match sort_mode {
+        ActivitySortMode::Id => (),
+        ActivitySortMode::Name => {
+            options.sort_by_cached_key(|option| option.activity_type.name.to_lowercase())
+        }
+        ActivitySortMode::MostRecent => options.sort_by_cached_key(|option| {
+            std::cmp::Reverse(activities.last_completed_on_or_before(option.activity_id(), date))
+        }),
+        ActivitySortMode::LeastDone => {
+            let start = date
+                .checked_sub_days(Days::new(COMPLETION_RATE_DAYS - 1))
+                .unwrap_or(date);
+            options.sort_by_cached_key(|option| {
+                let days_completed =
+                    activities.days_completed_between(option.activity_id(), start, date);
+                let days_scheduled = option.activity_type.scheduled_days_between(start, date);
+                // Per mille, as rates can't be ordered by key.
+                days_completed * 1000 / days_scheduled.max(1)
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> CalendarDate {
+        CalendarDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /**
+     * A store with the given activity completed on each of the days of
+     * January 2024 listed. The 1st of January 2024 is a Monday.
+     */
+    fn store_completed_on(activity_id: ActivityId, days: &[u32]) -> ActivitiesStore {
+        let mut activities = ActivitiesStore::default();
+        for day in days {
+            activities.add_activity(Activity::new(activity_id, date(2024, 1, *day)));
+        }
+        activities
+    }
+
+    fn activity_type() -> ActivityType {
+        let mut activity_types = ActivityTypesStore::default();
+        let activity_id = activity_types.create_new_activity(String::from("Read"));
+        activity_types.activity_type(activity_id).unwrap().clone()
+    }
+
+    #[test]
+    fn has_no_streak_without_completions() {
+        let activities = ActivitiesStore::default();
+        assert_eq!(activities.streak(&activity_type(), date(2024, 1, 10)), 0);
+    }
+
+    #[test]
+    fn counts_consecutive_completions() {
+        let read = activity_type();
+        let activities = store_completed_on(read.id, &[7, 8, 9, 10]);
+        assert_eq!(activities.streak(&read, date(2024, 1, 10)), 4);
+    }
+
+    #[test]
+    fn keeps_a_streak_until_the_end_of_today() {
+        let read = activity_type();
+        let activities = store_completed_on(read.id, &[7, 8, 9]);
+        assert_eq!(activities.streak(&read, date(2024, 1, 10)), 3);
+        assert_eq!(activities.streak(&read, date(2024, 1, 11)), 0);
+    }
+
+    #[test]
+    fn ends_a_streak_at_a_gap() {
+        let read = activity_type();
+        let activities = store_completed_on(read.id, &[3, 4, 5, 7, 8]);
+        assert_eq!(activities.streak(&read, date(2024, 1, 8)), 2);
+        assert_eq!(activities.streak(&read, date(2024, 1, 5)), 3);
+    }
+
+    #[test]
+    fn ignores_other_activities() {
+        let mut activity_types = ActivityTypesStore::default();
+        let read = activity_types.create_new_activity(String::from("Read"));
+        let run = activity_types.create_new_activity(String::from("Run"));
+        let mut activities = store_completed_on(read, &[9, 10]);
+        activities.add_activity(Activity::new(run, date(2024, 1, 8)));
+        let read = activity_types.activity_type(read).unwrap();
+        let run = activity_types.activity_type(run).unwrap();
+        assert_eq!(activities.streak(read, date(2024, 1, 10)), 2);
+        assert_eq!(activities.streak(run, date(2024, 1, 10)), 0);
+    }
+
+    #[test]
+    fn continues_a_streak_through_unscheduled_days() {
+        let mut read = activity_type();
+        read.schedule = vec![Weekday::Mon, Weekday::Wed, Weekday::Fri];
+        // Monday to Monday, and on the Saturday, which isn't scheduled.
+        let activities = store_completed_on(read.id, &[1, 3, 5, 6, 8]);
+        assert_eq!(activities.streak(&read, date(2024, 1, 8)), 5);
+        // Tuesday isn't scheduled, and Wednesday isn't over.
+        assert_eq!(activities.streak(&read, date(2024, 1, 9)), 5);
+        assert_eq!(activities.streak(&read, date(2024, 1, 10)), 5);
+        // Wednesday was missed.
+        assert_eq!(activities.streak(&read, date(2024, 1, 11)), 0);
+    }
+
+    #[test]
+    fn keeps_a_streak_through_frozen_days() {
+        let read = activity_type();
+        // Also done on the 5th, while frozen.
+        let mut activities = store_completed_on(read.id, &[1, 2, 3, 5, 7, 8]);
+        activities.freeze(date(2024, 1, 4), date(2024, 1, 6));
+        assert_eq!(activities.streak(&read, date(2024, 1, 8)), 5);
+        assert_eq!(activities.streak(&read, date(2024, 1, 6)), 3);
+
+        activities.unfreeze(date(2024, 1, 4), date(2024, 1, 4));
+        assert_eq!(activities.streak(&read, date(2024, 1, 8)), 2);
+    }
+
+    #[test]
+    fn joins_and_splits_frozen_ranges() {
+        let mut activities = ActivitiesStore::default();
+        activities.freeze(date(2024, 1, 3), date(2024, 1, 5));
+        activities.freeze(date(2024, 1, 12), date(2024, 1, 12));
+        // Touches the first range.
+        activities.freeze(date(2024, 1, 6), date(2024, 1, 8));
+        assert_eq!(
+            activities.frozen(),
+            [
+                (date(2024, 1, 3), date(2024, 1, 8)),
+                (date(2024, 1, 12), date(2024, 1, 12))
+            ]
+        );
+
+        activities.unfreeze(date(2024, 1, 5), date(2024, 1, 5));
+        activities.unfreeze(date(2024, 1, 10), date(2024, 1, 20));
+        assert_eq!(
+            activities.frozen(),
+            [
+                (date(2024, 1, 3), date(2024, 1, 4)),
+                (date(2024, 1, 6), date(2024, 1, 8))
+            ]
+        );
+        assert!(activities.is_frozen(date(2024, 1, 4)));
+        assert!(!activities.is_frozen(date(2024, 1, 5)));
+    }
+
+    /**
+     * A store as saved to its file and loaded back.
+     */
+    fn reloaded(activities: &ActivitiesStore) -> ActivitiesStore {
+        serde_json::from_str(&serde_json::to_string(activities).unwrap()).unwrap()
+    }
+
+    /**
+     * The activities file and archive file saved when activities before the
+     * 5th of January 2024 are archived, as loaded back.
+     */
+    fn archived(activities: &ActivitiesStore) -> (ActivitiesStore, ActivitiesStore) {
+        let mut activities = activities.clone();
+        activities.archive_before(date(2024, 1, 5));
+        let (current, archive) = activities.split_archive().unwrap();
+        (reloaded(&current), reloaded(&archive))
+    }
+
+    #[test]
+    fn counts_archived_activities_while_they_arent_loaded() {
+        let read = activity_type();
+        let (activities, _) = archived(&store_completed_on(read.id, &[2, 3, 4, 5, 6, 9, 10]));
+        assert_eq!(activities.first_tracked_date(), Some(date(2024, 1, 2)));
+        assert_eq!(activities.days_tracked(), 7);
+        assert_eq!(activities.completion_count(), 7);
+        assert_eq!(
+            activities.last_completed_before(read.id, date(2024, 1, 9)),
+            Some(date(2024, 1, 6))
+        );
+        assert_eq!(
+            activities.last_completed_before(read.id, date(2024, 1, 5)),
+            Some(date(2024, 1, 4))
+        );
+        assert_eq!(
+            activities.last_completed_on_or_before(read.id, date(2024, 1, 4)),
+            Some(date(2024, 1, 4))
+        );
+    }
+
+    #[test]
+    fn needs_the_archive_for_streaks_reaching_into_it() {
+        let read = activity_type();
+        let (mut activities, archive) =
+            archived(&store_completed_on(read.id, &[2, 3, 4, 5, 6, 9, 10]));
+        assert!(!activities.streak_reaches_archive(&read, date(2024, 1, 10)));
+        assert!(activities.streak_reaches_archive(&read, date(2024, 1, 6)));
+
+        activities.merge_archive(archive);
+        assert!(!activities.streak_reaches_archive(&read, date(2024, 1, 6)));
+        assert_eq!(activities.streak(&read, date(2024, 1, 6)), 5);
+        assert_eq!(activities.days_tracked(), 7);
+        assert_eq!(activities.completion_count(), 7);
+    }
+
+    #[test]
+    fn restores_archived_activities() {
+        let read = activity_type();
+        let mut original = store_completed_on(read.id, &[2, 3, 4, 5, 6, 9, 10]);
+        original.set_note(date(2024, 1, 3), String::from("Finished the first book"));
+        original.set_note(date(2024, 1, 9), String::from("Started the second"));
+        original.freeze(date(2024, 1, 3), date(2024, 1, 6));
+        let (mut activities, archive) = archived(&original);
+        assert_eq!(activities.activities().len(), 4);
+        assert_eq!(archive.activities().len(), 3);
+
+        activities.merge_archive(archive);
+        activities.unarchive();
+        assert_eq!(
+            serde_json::to_value(&activities).unwrap(),
+            serde_json::to_value(&original).unwrap()
+        );
+    }
+}
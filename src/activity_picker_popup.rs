@@ -0,0 +1,183 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+};
+use std::cmp::Reverse;
+
+use crate::{
+    activites::ActivityId,
+    activity_selector::fuzzy_score,
+    popup::Popup,
+    text_input::{TextInput, TextInputState},
+};
+
+// Width of the popup.
+const ACTIVITY_PICKER_POPUP_WIDTH: u16 = 50;
+// Most activities listed at once, when there is room for them.
+const MAX_LISTED: usize = 12;
+
+#[derive(Default)]
+pub struct ActivityPickerPopup {}
+
+/**
+ * State for a popup picking one of the activity types, e.g. the one to merge
+ * the selected activity into.
+ */
+pub struct ActivityPickerPopupState {
+    title: String,
+    filter: TextInputState,
+    // Each activity that can be picked, and its name.
+    activities: Vec<(ActivityId, String)>,
+    // Indices of the activities matching the filter, best matches first.
+    matches: Vec<usize>,
+    // Index in the matches of the selected activity.
+    selected: usize,
+    // First match listed, when there are more matches than fit.
+    scroll: usize,
+}
+
+impl ActivityPickerPopupState {
+    pub fn new(title: &str, activities: Vec<(ActivityId, String)>) -> Self {
+        let mut state = Self {
+            title: format!("  {}  ", title),
+            filter: TextInputState::default().placeholder("Type to filter"),
+            activities,
+            matches: vec![],
+            selected: 0,
+            scroll: 0,
+        };
+        state.update_matches();
+        state
+    }
+
+    /**
+     * Match the activities against the filter, selecting the best match.
+     */
+    fn update_matches(&mut self) {
+        let filter = self.filter.text();
+        let mut scored: Vec<(u32, usize)> = self
+            .activities
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (_, name))| Some((fuzzy_score(filter, name)?, index)))
+            .collect();
+        // Stable, so equally good matches stay in the order they were given.
+        scored.sort_by_key(|(score, _)| Reverse(*score));
+        self.matches = scored.into_iter().map(|(_, index)| index).collect();
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    fn selected_activity(&self) -> Option<ActivityId> {
+        let index = self.matches.get(self.selected)?;
+        self.activities
+            .get(*index)
+            .map(|(activity_id, _)| *activity_id)
+    }
+}
+
+impl Popup<ActivityPickerPopupState> for ActivityPickerPopup {
+    // The picked activity, or None if the popup was cancelled.
+    type Action = Option<ActivityId>;
+
+    fn handle_event(event: &Event, state: &mut ActivityPickerPopupState) -> Option<Self::Action> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        match key_event.code {
+            KeyCode::Esc => return Some(None),
+            KeyCode::Enter => return state.selected_activity().map(Some),
+            KeyCode::Up => state.selected = state.selected.saturating_sub(1),
+            KeyCode::Down => {
+                state.selected = (state.selected + 1).min(state.matches.len().saturating_sub(1))
+            }
+            _ => {
+                if state.filter.handle_key(*key_event) {
+                    state.update_matches();
+                }
+            }
+        }
+        None
+    }
+
+    fn desired_size(&self, state: &ActivityPickerPopupState, _area: &Rect) -> (u16, u16) {
+        let listed = state.matches.len().clamp(1, MAX_LISTED) as u16;
+        // Borders, the filter with a blank line above and below it, and the
+        // activities.
+        (ACTIVITY_PICKER_POPUP_WIDTH, listed + 5)
+    }
+}
+
+impl StatefulWidget for ActivityPickerPopup {
+    type State = ActivityPickerPopupState;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title(state.title.as_str())
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title_alignment(Alignment::Center)
+            .style(Style::default());
+        block.render(area, buffer);
+        if area.width < 6 || area.height < 6 {
+            return;
+        }
+
+        // Below the top border and a blank line.
+        let input_area = Rect::new(area.x + 2, area.y + 2, area.width - 4, 1);
+        buffer.set_style(input_area, Style::default().bg(Color::Black));
+        let text_area = Rect::new(input_area.x + 1, input_area.y, input_area.width - 2, 1);
+        TextInput::default()
+            .focused(true)
+            .render(text_area, buffer, &mut state.filter);
+
+        // Below the filter and a blank line, above the bottom border.
+        let list_area = Rect::new(area.x + 2, area.y + 4, area.width - 4, area.height - 5);
+        if state.matches.is_empty() {
+            buffer.set_stringn(
+                list_area.x,
+                list_area.y,
+                "No matches",
+                list_area.width as usize,
+                Style::default().fg(Color::Gray),
+            );
+            return;
+        }
+
+        // Scroll so that the selected activity is listed.
+        let visible = list_area.height as usize;
+        if state.selected < state.scroll {
+            state.scroll = state.selected;
+        } else if state.selected >= state.scroll + visible {
+            state.scroll = state.selected + 1 - visible;
+        }
+        let listed = state.matches.iter().skip(state.scroll).take(visible);
+        for (row, &index) in listed.enumerate() {
+            let y = list_area.y + row as u16;
+            buffer.set_stringn(
+                list_area.x,
+                y,
+                &state.activities[index].1,
+                list_area.width as usize,
+                Style::default(),
+            );
+            if state.scroll + row == state.selected {
+                buffer.set_style(
+                    Rect::new(list_area.x, y, list_area.width, 1),
+                    Style::default().add_modifier(Modifier::REVERSED),
+                );
+            }
+        }
+        // Arrows beside the list where more activities match than fit.
+        let arrow_style = Style::default().fg(Color::Gray);
+        if state.scroll > 0 {
+            buffer.set_string(area.right() - 2, list_area.y, "↑", arrow_style);
+        }
+        if state.scroll + visible < state.matches.len() {
+            buffer.set_string(area.right() - 2, list_area.bottom() - 1, "↓", arrow_style);
+        }
+    }
+}
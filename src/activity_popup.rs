@@ -1,85 +1,193 @@
+use chrono::Weekday;
 use crossterm::event::{Event, KeyCode};
 use ratatui::{
     buffer::Buffer,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Margin, Rect},
     style::{Color, Style},
-    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+    widgets::{Block, Borders, StatefulWidget, Widget},
 };
+use unicode_width::UnicodeWidthStr;
 
-use crate::{activites::ActivityId, popup::Popup};
+use crate::{
+    activites::{ActivityColor, ActivityId, ActivityType},
+    button_row::{ButtonRow, ButtonRowEvent},
+    focus,
+    popup::Popup,
+    text_input::{TextInput, TextInputState},
+};
 
 #[derive(Default)]
 pub struct ActivityPopup {}
 
 pub enum ActivityPopupAction {
-    CreateActivity(String),
-    EditActivity(ActivityId, String),
+    // Create an activity type, or update it if the draft has an id.
+    Save(ActivityDraft),
+    // Create an activity type, already completed on the active date.
+    SaveCompleted(ActivityDraft),
+    EditTags(ActivityId, Vec<String>),
+    AddTags(Vec<ActivityId>, Vec<String>),
+    EditHeatMax(ActivityId, Option<f32>),
+    EditSchedule(ActivityId, Vec<Weekday>),
     Exit,
 }
 
-#[derive(Copy, Clone)]
+/**
+ * An activity type as entered in an activity editor or creator popup.
+ */
+pub struct ActivityDraft {
+    // Activity type edited, or None to create one.
+    pub id: Option<ActivityId>,
+    pub name: String,
+    pub color: Option<ActivityColor>,
+    pub tags: Vec<String>,
+    pub weekly_target: Option<u32>,
+    pub default_value: Option<f32>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
 enum CursorPosition {
     TextInput,
+    // Only in activity editor and creator popups.
+    ColorPicker,
+    TagsInput,
+    TargetInput,
+    ValueInput,
     CreateOrEditButton,
+    // Only in activity creator popups.
+    CreateAndCompleteButton,
     ExitButton,
 }
 
-impl CursorPosition {
-    fn next(&self, last_position: Option<CursorPosition>, direction: KeyCode) -> Self {
-        match &self {
-            CursorPosition::TextInput => match direction {
-                KeyCode::Down => {
-                    if last_position.is_some()
-                        && !matches!(last_position.unwrap(), CursorPosition::TextInput)
-                    {
-                        last_position.unwrap()
-                    } else {
-                        CursorPosition::ExitButton
-                    }
-                }
-                _ => CursorPosition::TextInput,
-            },
-            CursorPosition::CreateOrEditButton => match direction {
-                KeyCode::Up => CursorPosition::TextInput,
-                KeyCode::Left => CursorPosition::ExitButton,
-                _ => CursorPosition::CreateOrEditButton,
-            },
-            CursorPosition::ExitButton => match direction {
-                KeyCode::Up => CursorPosition::TextInput,
-                KeyCode::Right => CursorPosition::CreateOrEditButton,
-                _ => CursorPosition::ExitButton,
-            },
+enum PopupType {
+    Create,
+    Edit,
+    // Edit the comma separated tags of an activity.
+    EditTags,
+    // Add comma separated tags to several activities.
+    AddTags,
+    // Edit the heat of an activity displayed as the hottest color.
+    EditHeatMax,
+    // Edit the comma separated days of the week an activity is scheduled on.
+    EditSchedule,
+}
+
+impl PopupType {
+    /**
+     * Placeholder of the first field, which is the only field of most popups.
+     */
+    fn placeholder(&self) -> &'static str {
+        match self {
+            PopupType::EditTags | PopupType::AddTags => "Enter tags",
+            PopupType::EditHeatMax => "Enter max heat",
+            PopupType::EditSchedule => "Enter days, e.g. mon, wed, fri",
+            PopupType::Create | PopupType::Edit => "Enter activity name",
         }
     }
 }
 
-enum PopupType {
-    Create,
-    Edit,
+/**
+ * Input for the tags of an activity editor or creator popup.
+ */
+fn tags_input(text: String) -> TextInputState {
+    TextInputState::new(text).placeholder("Enter tags")
 }
 
+/**
+ * Input for the weekly target of an activity editor or creator popup.
+ */
+fn target_input(text: String) -> TextInputState {
+    // A week has too few days for a target of more than three digits.
+    TextInputState::new(text).placeholder("None").max_length(3)
+}
+
+/**
+ * Input for the default value of an activity editor or creator popup.
+ */
+fn value_input(text: String) -> TextInputState {
+    TextInputState::new(text).placeholder("None")
+}
+
+// Width of the popups, unless a hint or error needs more room.
+const ACTIVITY_POPUP_WIDTH: u16 = 60;
+
+// Fields of each type of popup, in the order they are focused in.
+const ACTIVITY_FIELDS: [CursorPosition; 5] = [
+    CursorPosition::TextInput,
+    CursorPosition::ColorPicker,
+    CursorPosition::TagsInput,
+    CursorPosition::TargetInput,
+    CursorPosition::ValueInput,
+];
+const TEXT_FIELDS: [CursorPosition; 1] = [CursorPosition::TextInput];
+
+// Buttons of each type of popup, from left to right.
+const CREATE_BUTTONS: [CursorPosition; 3] = [
+    CursorPosition::ExitButton,
+    CursorPosition::CreateOrEditButton,
+    CursorPosition::CreateAndCompleteButton,
+];
+const BUTTONS: [CursorPosition; 2] = [
+    CursorPosition::ExitButton,
+    CursorPosition::CreateOrEditButton,
+];
+
 /**
  * State for an activity editor or creator popup.
  */
 pub struct ActivityPopupState {
-    last_cursor_position: Option<CursorPosition>,
     cursor_position: CursorPosition,
-    text_input: String,
+    text_input: TextInputState,
+    // Only used in activity editor and creator popups.
+    color: Option<ActivityColor>,
+    tags_input: TextInputState,
+    target_input: TextInputState,
+    value_input: TextInputState,
     popup_type: PopupType,
     activity_id: Option<ActivityId>,
+    // Activities edited together.
+    activity_ids: Vec<ActivityId>,
+    // Error displayed when the input is invalid.
+    error: Option<String>,
+    // Whether anything was changed since the popup was opened.
+    modified: bool,
 }
 
 impl ActivityPopupState {
+    fn new(popup_type: PopupType, text_input: String) -> Self {
+        Self {
+            cursor_position: CursorPosition::TextInput,
+            text_input: TextInputState::new(text_input).placeholder(popup_type.placeholder()),
+            color: None,
+            tags_input: tags_input(String::new()),
+            target_input: target_input(String::new()),
+            value_input: value_input(String::new()),
+            popup_type,
+            activity_id: None,
+            activity_ids: vec![],
+            error: None,
+            modified: false,
+        }
+    }
+
     /**
      * Initialize state for an activity editor popup.
      */
-    pub fn new_editor(activity_title: String, activity_id: ActivityId) -> Self {
+    pub fn new_editor(activity_type: &ActivityType) -> Self {
         Self {
-            last_cursor_position: None,
-            cursor_position: CursorPosition::TextInput,
-            text_input: activity_title,
-            popup_type: PopupType::Edit,
-            activity_id: Some(activity_id),
+            color: activity_type.color,
+            tags_input: tags_input(activity_type.tags.join(", ")),
+            target_input: target_input(
+                activity_type
+                    .weekly_target
+                    .map_or(String::new(), |target| target.to_string()),
+            ),
+            value_input: value_input(
+                activity_type
+                    .default_value
+                    .map_or(String::new(), |value| value.to_string()),
+            ),
+            activity_id: Some(activity_type.id),
+            ..Self::new(PopupType::Edit, activity_type.name.clone())
         }
     }
 
@@ -87,56 +195,404 @@ impl ActivityPopupState {
      * Initialize state for an activity creator popup.
      */
     pub fn new_creator() -> Self {
+        Self::new(PopupType::Create, String::new())
+    }
+
+    /**
+     * Initialize state for an activity tags editor popup.
+     */
+    pub fn new_tags_editor(tags: &[String], activity_id: ActivityId) -> Self {
         Self {
-            last_cursor_position: None,
-            cursor_position: CursorPosition::TextInput,
-            text_input: String::new(),
-            popup_type: PopupType::Create,
-            activity_id: None,
+            activity_id: Some(activity_id),
+            ..Self::new(PopupType::EditTags, tags.join(", "))
+        }
+    }
+
+    /**
+     * Initialize state for a popup adding tags to several activities.
+     */
+    pub fn new_tags_adder(activity_ids: Vec<ActivityId>) -> Self {
+        Self {
+            activity_ids,
+            ..Self::new(PopupType::AddTags, String::new())
         }
     }
+
+    /**
+     * Initialize state for an activity heat maximum editor popup.
+     */
+    pub fn new_heat_max_editor(heat_max: Option<f32>, activity_id: ActivityId) -> Self {
+        let text_input = heat_max.map_or(String::new(), |heat_max| heat_max.to_string());
+        Self {
+            activity_id: Some(activity_id),
+            ..Self::new(PopupType::EditHeatMax, text_input)
+        }
+    }
+
+    /**
+     * Initialize state for an activity schedule editor popup.
+     */
+    pub fn new_schedule_editor(schedule: &[Weekday], activity_id: ActivityId) -> Self {
+        let text_input = schedule
+            .iter()
+            .map(Weekday::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        Self {
+            activity_id: Some(activity_id),
+            ..Self::new(PopupType::EditSchedule, text_input)
+        }
+    }
+
+    /**
+     * Whether anything was changed, and would be lost by exiting.
+     */
+    pub fn modified(&self) -> bool {
+        self.modified
+    }
+
+    fn fields(&self) -> &'static [CursorPosition] {
+        match self.popup_type {
+            PopupType::Create | PopupType::Edit => &ACTIVITY_FIELDS,
+            _ => &TEXT_FIELDS,
+        }
+    }
+
+    fn buttons(&self) -> &'static [CursorPosition] {
+        match self.popup_type {
+            PopupType::Create => &CREATE_BUTTONS,
+            _ => &BUTTONS,
+        }
+    }
+
+    fn text_field_mut(&mut self, position: CursorPosition) -> Option<&mut TextInputState> {
+        match position {
+            CursorPosition::TextInput => Some(&mut self.text_input),
+            CursorPosition::TagsInput => Some(&mut self.tags_input),
+            CursorPosition::TargetInput => Some(&mut self.target_input),
+            CursorPosition::ValueInput => Some(&mut self.value_input),
+            _ => None,
+        }
+    }
+
+    /**
+     * Everything that can be focused, in the order Tab moves the focus
+     * through it: the fields, the buttons that save, then the exit button.
+     */
+    fn focus_order(&self) -> Vec<CursorPosition> {
+        let mut order = self.fields().to_vec();
+        order.extend(
+            self.buttons()
+                .iter()
+                .filter(|button| **button != CursorPosition::ExitButton),
+        );
+        order.push(CursorPosition::ExitButton);
+        order
+    }
+
+    /**
+     * Move the focus forward (Tab or Down) or backward (Shift+Tab or Up)
+     * through the focus order, wrapping around, or along the buttons (Left
+     * or Right).
+     */
+    fn move_focus(&mut self, key: KeyCode) {
+        let position = self.cursor_position;
+        self.cursor_position = match key {
+            KeyCode::Tab | KeyCode::Down => focus::next(&self.focus_order(), position),
+            KeyCode::BackTab | KeyCode::Up => focus::previous(&self.focus_order(), position),
+            KeyCode::Left | KeyCode::Right => match self.button_row().handle_key(key) {
+                Some(ButtonRowEvent::Focus(index)) => self.buttons()[index],
+                _ => position,
+            },
+            _ => position,
+        };
+    }
+
+    /**
+     * The trimmed activity name, or None (setting an error) if it is empty.
+     */
+    fn validated_name(&mut self) -> Option<String> {
+        let name = self.text_input.text().trim();
+        if name.is_empty() {
+            self.error = Some(String::from("Activity name cannot be empty"));
+            None
+        } else {
+            self.error = None;
+            Some(name.to_owned())
+        }
+    }
+
+    /**
+     * The weekly target, which is None when the input is empty, or None
+     * (setting an error) if it isn't a positive whole number.
+     */
+    fn validated_weekly_target(&mut self) -> Option<Option<u32>> {
+        let input = self.target_input.text().trim();
+        if input.is_empty() {
+            return Some(None);
+        }
+        match input.parse::<u32>() {
+            Ok(target) if target > 0 => Some(Some(target)),
+            _ => {
+                self.error = Some(String::from("Weekly target must be a positive number"));
+                None
+            }
+        }
+    }
+
+    /**
+     * The default value, which is None when the input is empty, or None
+     * (setting an error) if it isn't a number.
+     */
+    fn validated_default_value(&mut self) -> Option<Option<f32>> {
+        let input = self.value_input.text().trim();
+        if input.is_empty() {
+            return Some(None);
+        }
+        match input.parse::<f32>() {
+            Ok(value) if value.is_finite() => Some(Some(value)),
+            _ => {
+                self.error = Some(format!("Default value must be a number, not {}", input));
+                None
+            }
+        }
+    }
+
+    /**
+     * The activity type entered in the fields, or None (setting an error) if
+     * any of them is invalid.
+     */
+    fn validated_draft(&mut self) -> Option<ActivityDraft> {
+        let name = self.validated_name()?;
+        let weekly_target = self.validated_weekly_target()?;
+        let default_value = self.validated_default_value()?;
+        Some(ActivityDraft {
+            id: self.activity_id,
+            name,
+            color: self.color,
+            tags: parse_tags(self.tags_input.text()),
+            weekly_target,
+            default_value,
+        })
+    }
+
+    /**
+     * The heat maximum, which is None when the input is empty, or None
+     * (setting an error) if it isn't a positive number.
+     */
+    fn validated_heat_max(&mut self) -> Option<Option<f32>> {
+        let input = self.text_input.text().trim();
+        if input.is_empty() {
+            self.error = None;
+            return Some(None);
+        }
+        match input.parse::<f32>() {
+            Ok(heat_max) if heat_max.is_finite() && heat_max > 0.0 => {
+                self.error = None;
+                Some(Some(heat_max))
+            }
+            _ => {
+                self.error = Some(String::from("Heat max must be a positive number"));
+                None
+            }
+        }
+    }
+
+    /**
+     * The days of the week (e.g. "mon, wed, fri"), or None (setting an error)
+     * if any of them isn't a day. Empty input is every day.
+     */
+    fn validated_schedule(&mut self) -> Option<Vec<Weekday>> {
+        let mut schedule = vec![];
+        for day in self.text_input.text().split(',').map(str::trim) {
+            if day.is_empty() {
+                continue;
+            }
+            match day.parse::<Weekday>() {
+                Ok(weekday) => schedule.push(weekday),
+                Err(_) => {
+                    self.error = Some(format!("Unknown day: {}", day));
+                    return None;
+                }
+            }
+        }
+        self.error = None;
+        Some(schedule)
+    }
+
+    /**
+     * The action for the popup's input, or None if the input is invalid.
+     */
+    fn submit(&mut self) -> Option<ActivityPopupAction> {
+        match self.popup_type {
+            PopupType::Create | PopupType::Edit => {
+                Some(ActivityPopupAction::Save(self.validated_draft()?))
+            }
+            PopupType::EditTags => Some(ActivityPopupAction::EditTags(
+                self.activity_id.unwrap(),
+                parse_tags(self.text_input.text()),
+            )),
+            PopupType::AddTags => Some(ActivityPopupAction::AddTags(
+                self.activity_ids.clone(),
+                parse_tags(self.text_input.text()),
+            )),
+            PopupType::EditHeatMax => Some(ActivityPopupAction::EditHeatMax(
+                self.activity_id.unwrap(),
+                self.validated_heat_max()?,
+            )),
+            PopupType::EditSchedule => Some(ActivityPopupAction::EditSchedule(
+                self.activity_id.unwrap(),
+                self.validated_schedule()?,
+            )),
+        }
+    }
+
+    fn button_label(&self, button: CursorPosition) -> &'static str {
+        match (button, &self.popup_type) {
+            (CursorPosition::ExitButton, _) => "exit",
+            (CursorPosition::CreateAndCompleteButton, _) => "create + done",
+            (_, PopupType::Create) => "create",
+            (_, PopupType::AddTags) => "add",
+            _ => "save",
+        }
+    }
+
+    /**
+     * The buttons, with the focused one selected if a button is focused.
+     */
+    fn button_row(&self) -> ButtonRow<'static> {
+        let buttons = self.buttons();
+        ButtonRow::new(
+            buttons
+                .iter()
+                .map(|button| self.button_label(*button))
+                .collect(),
+        )
+        .selected(
+            buttons
+                .iter()
+                .position(|button| *button == self.cursor_position),
+        )
+    }
+
+    fn label(&self, field: CursorPosition) -> &'static str {
+        match (field, &self.popup_type) {
+            (CursorPosition::TextInput, PopupType::EditTags | PopupType::AddTags) => "tags",
+            (CursorPosition::TextInput, PopupType::EditHeatMax) => "heat",
+            (CursorPosition::TextInput, PopupType::EditSchedule) => "days",
+            (CursorPosition::TextInput, _) => "name",
+            (CursorPosition::ColorPicker, _) => "color",
+            (CursorPosition::TagsInput, _) => "tags",
+            (CursorPosition::ValueInput, _) => "default value",
+            _ => "weekly target",
+        }
+    }
+
+    /**
+     * Explanation of the focused field, or of the popup if a button is
+     * focused.
+     */
+    fn hint(&self) -> &'static str {
+        match (self.cursor_position, &self.popup_type) {
+            (CursorPosition::ColorPicker, _) => "(←/→ to change the color)",
+            (CursorPosition::TagsInput, _) => "(comma separated tags)",
+            (CursorPosition::TargetInput, _) => "(times a week, empty for no target)",
+            (CursorPosition::ValueInput, _) => "(value of each completion, empty for none)",
+            (_, PopupType::EditTags) => "(comma separated tags)",
+            (_, PopupType::AddTags) => "(comma separated tags to add to the marked activities)",
+            (_, PopupType::EditHeatMax) => "(hottest heat, empty to scale)",
+            (_, PopupType::EditSchedule) => "(comma separated days, empty for every day)",
+            (_, PopupType::Edit) => "(activity name)",
+            (_, PopupType::Create) => "(new activity name)",
+        }
+    }
+}
+
+/**
+ * Comma separated tags, trimmed and without empty tags.
+ */
+fn parse_tags(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(|tag| tag.trim().to_owned())
+        .filter(|tag| !tag.is_empty())
+        .collect()
 }
 
 impl Popup<ActivityPopupState> for ActivityPopup {
     type Action = ActivityPopupAction;
 
     fn handle_event(event: &Event, state: &mut ActivityPopupState) -> Option<Self::Action> {
-        match event {
-            Event::Key(key_event) => match key_event.code {
-                KeyCode::Enter => match state.cursor_position {
-                    CursorPosition::TextInput => None,
-                    CursorPosition::CreateOrEditButton => match state.popup_type {
-                        PopupType::Create => Some(ActivityPopupAction::CreateActivity(
-                            state.text_input.clone(),
-                        )),
-                        PopupType::Edit => Some(ActivityPopupAction::EditActivity(
-                            state.activity_id.unwrap(),
-                            state.text_input.clone(),
-                        )),
-                    },
-                    CursorPosition::ExitButton => Some(ActivityPopupAction::Exit),
-                },
-                KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
-                    let new_position = state
-                        .cursor_position
-                        .next(state.last_cursor_position, key_event.code);
-                    state.last_cursor_position = Some(state.cursor_position);
-                    state.cursor_position = new_position;
-                    None
-                }
-                KeyCode::Char(c) if matches!(state.cursor_position, CursorPosition::TextInput) => {
-                    state.text_input.push(c);
-                    None
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        let position = state.cursor_position;
+        match key_event.code {
+            KeyCode::Esc => Some(ActivityPopupAction::Exit),
+            KeyCode::Enter => match position {
+                CursorPosition::CreateOrEditButton => state.submit(),
+                CursorPosition::CreateAndCompleteButton => {
+                    Some(ActivityPopupAction::SaveCompleted(state.validated_draft()?))
                 }
-                KeyCode::Backspace => {
-                    state.text_input.pop();
+                CursorPosition::ExitButton => Some(ActivityPopupAction::Exit),
+                // As if the save button were pressed, unless there is nothing
+                // entered yet, in which case the focus moves to it.
+                CursorPosition::TextInput if state.text_input.text().trim().is_empty() => {
+                    state.cursor_position = CursorPosition::CreateOrEditButton;
                     None
                 }
+                CursorPosition::TextInput => state.submit(),
                 _ => None,
             },
-            _ => None,
+            KeyCode::Left | KeyCode::Right if position == CursorPosition::ColorPicker => {
+                state.color = match key_event.code {
+                    KeyCode::Left => ActivityColor::previous(state.color),
+                    _ => ActivityColor::next(state.color),
+                };
+                state.modified = true;
+                None
+            }
+            // Only digits can be typed into the weekly target.
+            KeyCode::Char(c) if position == CursorPosition::TargetInput && !c.is_ascii_digit() => {
+                None
+            }
+            // Only a number can be typed into the default value.
+            KeyCode::Char(c)
+                if position == CursorPosition::ValueInput && !(c.is_ascii_digit() || c == '.') =>
+            {
+                None
+            }
+            KeyCode::Up | KeyCode::Down | KeyCode::Tab | KeyCode::BackTab => {
+                state.move_focus(key_event.code);
+                None
+            }
+            code => {
+                if let Some(field) = state.text_field_mut(position) {
+                    let text = field.text().to_owned();
+                    if field.handle_key(*key_event) {
+                        // Moving the cursor isn't a change.
+                        state.modified |= field.text() != text;
+                        state.error = None;
+                    }
+                } else if matches!(code, KeyCode::Left | KeyCode::Right) {
+                    state.move_focus(code);
+                }
+                None
+            }
         }
     }
+
+    fn desired_size(&self, state: &ActivityPopupState, _area: &Rect) -> (u16, u16) {
+        // +6: Borders, and padding of two columns on each side.
+        let hint_width = state
+            .hint()
+            .width()
+            .max(state.error.as_deref().map_or(0, str::width));
+        let width = (hint_width as u16 + 6).max(ACTIVITY_POPUP_WIDTH);
+        // Borders, each field with a blank line above it, a blank line, the
+        // hint, a blank line, and the buttons.
+        let height = 2 + state.fields().len() as u16 * 2 + 4;
+        (width, height)
+    }
 }
 
 impl StatefulWidget for ActivityPopup {
@@ -144,102 +600,239 @@ impl StatefulWidget for ActivityPopup {
 
     fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
         let block = Block::default()
-            .title("   Activity Editor   ")
+            .title(match state.popup_type {
+                PopupType::EditTags | PopupType::AddTags => "   Tag Editor   ",
+                PopupType::EditHeatMax => "   Heat Editor   ",
+                PopupType::EditSchedule => "   Schedule Editor   ",
+                _ => "   Activity Editor   ",
+            })
             .borders(Borders::ALL)
             .title_alignment(Alignment::Center)
             .style(Style::default());
+        block.render(area, buffer);
+        let inner = area.inner(&Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+        if inner.height < 3 {
+            return;
+        }
 
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![
-                Constraint::Length(area.height - 3),
-                Constraint::Length(1),
-            ])
-            .split(area);
-
-        let text_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .margin(1)
-            .constraints(vec![
-                Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Length(3),
-            ])
-            .split(layout[0]);
-
-        let bottom_row = Layout::default()
-            .direction(Direction::Horizontal)
-            .margin(1)
-            .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(layout[1]);
-
-        let text_input_title = Block::default()
-            .title("(new activity name)")
-            .borders(Borders::NONE)
-            .title_alignment(Alignment::Center)
-            .style(Style::default());
-
-        let selected_color = Color::Black;
-        let not_selected_color = Color::Gray;
-        let text_input = Block::default()
-            .borders(Borders::ALL)
-            .border_type(BorderType::Thick)
-            .style(Style::default().bg(
-                if matches!(state.cursor_position, CursorPosition::TextInput) {
-                    selected_color
-                } else {
-                    not_selected_color
-                },
-            ));
+        // From the bottom: the buttons, a blank line, and the hint or error.
+        let buttons_area = Rect::new(inner.x, inner.bottom() - 1, inner.width, 1);
+        let message_area = Rect::new(inner.x, inner.bottom() - 3, inner.width, 1);
+        let fields_area = Rect::new(inner.x, inner.y, inner.width, inner.height - 3);
+        self.render_fields(fields_area, buffer, state);
 
-        let exit = Block::default()
-            .title("exit")
-            .borders(Borders::NONE)
-            .title_alignment(Alignment::Center)
-            .style(Style::default().bg(
-                if matches!(state.cursor_position, CursorPosition::ExitButton) {
-                    selected_color
-                } else {
-                    not_selected_color
-                },
-            ));
-        let create = Block::default()
-            .title(match state.popup_type {
-                PopupType::Create => "create",
-                PopupType::Edit => "save",
-            })
-            .borders(Borders::NONE)
+        let (message, message_style) = match &state.error {
+            Some(error) => (error.as_str(), Style::default().fg(Color::Red)),
+            None => (state.hint(), Style::default().fg(Color::Gray)),
+        };
+        Block::default()
+            .title(message)
             .title_alignment(Alignment::Center)
-            .style(Style::default().bg(
-                if matches!(state.cursor_position, CursorPosition::CreateOrEditButton) {
-                    selected_color
-                } else {
-                    not_selected_color
-                },
-            ));
+            .style(message_style)
+            .render(message_area, buffer);
+        state.button_row().render(buttons_area, buffer);
+    }
+}
 
-        let text = if state.text_input.len() == 0 {
-            String::from("Enter activity name")
+impl ActivityPopup {
+    /**
+     * Draw a line for each field, with its label on the left. Fields are
+     * separated by blank lines when there is room, and otherwise only the
+     * fields up to the focused one are drawn.
+     */
+    fn render_fields(&self, area: Rect, buffer: &mut Buffer, state: &mut ActivityPopupState) {
+        let fields = state.fields();
+        let spacing = if area.height as usize >= fields.len() * 2 {
+            2
         } else {
-            let mut temp = state.text_input.clone();
-            if matches!(state.cursor_position, CursorPosition::TextInput) {
-                temp.push_str("| ");
-            } else {
-                temp.push_str("  ");
-            }
-            temp
+            1
         };
+        let visible = (area.height as usize / spacing).min(fields.len());
+        if visible == 0 {
+            return;
+        }
+        // While a button is focused, the last field the focus moves up to is
+        // kept visible.
+        let focused = fields
+            .iter()
+            .position(|field| *field == state.cursor_position)
+            .unwrap_or(fields.len() - 1);
+        let first = (focused + 1).saturating_sub(visible);
 
-        block.render(area, buffer);
-        text_input.render(text_layout[1], buffer);
-        text_input_title.render(text_layout[2], buffer);
-        exit.render(bottom_row[0], buffer);
-        create.render(bottom_row[1], buffer);
-
-        for i in 0..text.len() {
-            buffer
-                .get_mut(text_layout[1].x + i as u16 + 1, text_layout[1].y + 1)
-                .set_symbol(&text);
+        let label_width = fields
+            .iter()
+            .map(|field| state.label(*field).width())
+            .max()
+            .unwrap_or(0) as u16;
+        // The first field is below a blank line, when there is room for it.
+        let top = area.y + u16::from(spacing == 2 && area.height as usize > fields.len() * 2);
+        for (row, field) in fields[first..first + visible].iter().enumerate() {
+            let y = top + (row * spacing) as u16;
+            let label = state.label(*field);
+            buffer.set_stringn(
+                area.x + label_width.saturating_sub(label.width() as u16),
+                y,
+                label,
+                area.width as usize,
+                Style::default().fg(Color::Gray),
+            );
+            // +1: Space after the label.
+            let input_x = area.x + (label_width + 1).min(area.width);
+            let input_area = Rect::new(input_x, y, area.right() - input_x, 1);
+            let is_focused = *field == state.cursor_position;
+            buffer.set_style(
+                input_area,
+                Style::default().bg(if is_focused {
+                    Color::Black
+                } else {
+                    Color::DarkGray
+                }),
+            );
+            // Padding within the input.
+            let text_area = input_area.inner(&Margin {
+                horizontal: 1,
+                vertical: 0,
+            });
+            match field {
+                CursorPosition::ColorPicker => {
+                    let label = state.color.map_or("default", ActivityColor::label);
+                    let (x, _) = buffer.set_stringn(
+                        text_area.x,
+                        y,
+                        "◀ ",
+                        text_area.width as usize,
+                        Style::default(),
+                    );
+                    let remaining = text_area.right().saturating_sub(x) as usize;
+                    let (x, _) = buffer.set_stringn(
+                        x,
+                        y,
+                        "██ ",
+                        remaining,
+                        Style::default()
+                            .fg(state.color.map_or(Color::Green, ActivityColor::to_color)),
+                    );
+                    let remaining = text_area.right().saturating_sub(x) as usize;
+                    buffer.set_stringn(x, y, format!("{} ▶", label), remaining, Style::default());
+                }
+                field => {
+                    if let Some(input) = state.text_field_mut(*field) {
+                        TextInput::default()
+                            .focused(is_focused)
+                            .render(text_area, buffer, input);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    use super::*;
+    use crate::activites::ActivityTypesStore;
+
+    fn press(state: &mut ActivityPopupState, code: KeyCode) -> Option<ActivityPopupAction> {
+        let event = Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        ActivityPopup::handle_event(&event, state)
+    }
+
+    fn type_text(state: &mut ActivityPopupState, text: &str) {
+        for c in text.chars() {
+            press(state, KeyCode::Char(c));
+        }
+    }
+
+    fn press_create(state: &mut ActivityPopupState) -> Option<ActivityPopupAction> {
+        state.cursor_position = CursorPosition::CreateOrEditButton;
+        press(state, KeyCode::Enter)
+    }
+
+    #[test]
+    fn creates_an_activity_with_the_trimmed_name() {
+        let mut state = ActivityPopupState::new_creator();
+        type_text(&mut state, "  Read ");
+        assert!(matches!(
+            press_create(&mut state),
+            Some(ActivityPopupAction::Save(draft)) if draft.name == "Read" && draft.id.is_none()
+        ));
+        assert!(state.error.is_none());
+    }
+
+    #[test]
+    fn rejects_an_empty_name() {
+        let mut state = ActivityPopupState::new_creator();
+        assert!(press_create(&mut state).is_none());
+        assert!(state.error.is_some());
+
+        // Typing clears the error.
+        state.cursor_position = CursorPosition::TextInput;
+        type_text(&mut state, "R");
+        assert!(state.error.is_none());
+    }
+
+    #[test]
+    fn rejects_a_whitespace_only_name() {
+        let mut state = ActivityPopupState::new_creator();
+        type_text(&mut state, "   ");
+        assert!(press_create(&mut state).is_none());
+        assert!(state.error.is_some());
+
+        let mut activity_types = ActivityTypesStore::default();
+        let activity_id = activity_types.create_new_activity(String::from("Read"));
+        let mut state =
+            ActivityPopupState::new_editor(activity_types.activity_type(activity_id).unwrap());
+        for _ in 0..4 {
+            press(&mut state, KeyCode::Backspace);
+        }
+        type_text(&mut state, " \t");
+        assert!(press_create(&mut state).is_none());
+        assert!(state.error.is_some());
+    }
+
+    #[test]
+    fn exits_with_esc() {
+        let mut state = ActivityPopupState::new_creator();
+        type_text(&mut state, "Read");
+        assert!(matches!(
+            press(&mut state, KeyCode::Esc),
+            Some(ActivityPopupAction::Exit)
+        ));
+    }
+
+    #[test]
+    fn saves_with_enter_in_the_text_field() {
+        let mut state = ActivityPopupState::new_creator();
+        type_text(&mut state, "Read");
+        assert!(matches!(
+            press(&mut state, KeyCode::Enter),
+            Some(ActivityPopupAction::Save(draft)) if draft.name == "Read"
+        ));
+
+        let mut activity_types = ActivityTypesStore::default();
+        let activity_id = activity_types.create_new_activity(String::from("Read"));
+        let mut state = ActivityPopupState::new_tags_editor(&[], activity_id);
+        type_text(&mut state, "books, evening");
+        assert!(matches!(
+            press(&mut state, KeyCode::Enter),
+            Some(ActivityPopupAction::EditTags(id, tags))
+                if id == activity_id && tags == ["books", "evening"]
+        ));
+    }
+
+    #[test]
+    fn moves_to_the_save_button_with_enter_in_an_empty_text_field() {
+        for text in ["", "  "] {
+            let mut state = ActivityPopupState::new_creator();
+            type_text(&mut state, text);
+            assert!(press(&mut state, KeyCode::Enter).is_none());
+            assert!(state.cursor_position == CursorPosition::CreateOrEditButton);
+            assert!(state.error.is_none());
         }
     }
 }
@@ -1,54 +1,363 @@
 #![allow(dead_code)]
-use std::cmp::min;
-
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::Span,
     widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
 };
+use std::collections::HashSet;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::activites::{ActivityId, ActivitySortMode};
 
-const ACTIVITIES_PER_ROW: u16 = 3;
+// Number of activities per row until the selector is rendered.
+const DEFAULT_ACTIVITIES_PER_ROW: u16 = 3;
+const ELLIPSIS: char = '…';
+const MORE_ABOVE: &str = "▲ more";
+const MORE_BELOW: &str = "▼ more";
+// Drawn before the name of values with a color.
+const SWATCH: &str = "██ ";
+// Narrowest an activity cell can be before the row is given fewer columns.
+const MIN_CELL_WIDTH: u16 = 20;
+// Number of segments in the bar of progress towards a goal.
+const PROGRESS_SEGMENTS: u32 = 5;
+// Fewest columns of the name kept when a progress bar is drawn before it.
+const MIN_NAME_WIDTH: usize = 4;
+
+/**
+ * Number of activities per row that fit in a selector of the given width.
+ */
+fn activities_per_row(width: u16) -> u16 {
+    // -2: Left and right border.
+    (width.saturating_sub(2) / MIN_CELL_WIDTH).max(1)
+}
+
+/**
+ * A bar of the progress towards a goal, followed by the amount done and the
+ * goal (e.g. "▮▮▮▯▯ 3/5 "). Progress past the goal fills the bar.
+ */
+fn progress_bar(done: u32, goal: u32) -> String {
+    // Rounded down, so the bar is only full once the goal is met. A goal of
+    // zero is always met.
+    let filled = (done * PROGRESS_SEGMENTS)
+        .checked_div(goal)
+        .map_or(PROGRESS_SEGMENTS, |filled| filled.min(PROGRESS_SEGMENTS));
+    format!(
+        "{}{} {}/{} ",
+        "▮".repeat(filled as usize),
+        "▯".repeat((PROGRESS_SEGMENTS - filled) as usize),
+        done,
+        goal
+    )
+}
+
+/**
+ * The text, cut short with an ellipsis if it is wider than `width` columns.
+ */
+fn truncate_to_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_owned();
+    }
+    let mut truncated = String::new();
+    let mut truncated_width = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        // +1: The ellipsis.
+        if truncated_width + char_width + 1 > width {
+            break;
+        }
+        truncated.push(c);
+        truncated_width += char_width;
+    }
+    // When cut between words, the ellipsis follows the last word ("Morning…"
+    // rather than "Morning …").
+    truncated.truncate(truncated.trim_end().len());
+    if width > 0 {
+        truncated.push(ELLIPSIS);
+    }
+    truncated
+}
+
+/**
+ * Score of matching the pattern's characters, in order, in the name from
+ * `start` onwards, each at the first place it appears. None if they don't
+ * all appear.
+ */
+fn fuzzy_score_from(pattern: &[char], name: &[char], start: usize) -> Option<u32> {
+    let mut score = 0;
+    let mut next = start;
+    let mut previous: Option<usize> = None;
+    for &c in pattern {
+        let index = next + name.get(next..)?.iter().position(|&n| n == c)?;
+        score += 1;
+        // Consecutive characters, e.g. "run" in "Running", score the most.
+        if previous.is_some_and(|previous| previous + 1 == index) {
+            score += 4;
+        }
+        // As do the first letters of words, e.g. "mr" in "Morning Run".
+        if index == 0 || !name[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(index);
+        next = index + 1;
+    }
+    Some(score)
+}
+
+/**
+ * How well a pattern matches a name, ignoring case and the pattern's spaces,
+ * if the pattern's characters all appear in the name in order ("mrn run"
+ * matches "Morning Run"). Higher scores are better matches.
+ */
+pub fn fuzzy_score(pattern: &str, name: &str) -> Option<u32> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let name: Vec<char> = name.chars().flat_map(char::to_lowercase).collect();
+    let Some(&first) = pattern.first() else {
+        return Some(0);
+    };
+    // Try the match from each place the first character appears, as the
+    // first place isn't always the best, e.g. "run" in "Morning Run".
+    (0..name.len())
+        .filter(|&start| name[start] == first)
+        .filter_map(|start| fuzzy_score_from(&pattern, &name, start))
+        .max()
+}
+
+/**
+ * Index of the first activity in each row of the grid. Activities from
+ * `divider` onwards start on a new row.
+ */
+fn row_starts(activity_count: usize, per_row: usize, divider: Option<usize>) -> Vec<usize> {
+    let divider = divider
+        .filter(|&divider| 0 < divider && divider < activity_count)
+        .unwrap_or(activity_count);
+    (0..divider)
+        .step_by(per_row)
+        .chain((divider..activity_count).step_by(per_row))
+        .collect()
+}
 
 #[derive(Clone)]
 pub struct ActivitySelectorState {
     activity_count: usize,
     selected_index: Option<usize>,
+    // Only activities with this tag are displayed.
+    tag_filter: Option<String>,
+    // Only activities whose names fuzzily match this are displayed, best
+    // matches first.
+    name_filter: String,
+    sort_mode: ActivitySortMode,
+    // List activities that aren't done before those that are.
+    incomplete_first: bool,
+    // Number of activities per row when the selector was last rendered.
+    activities_per_row: usize,
+    // Index of the first activity after the divider when the selector was
+    // last rendered.
+    divider: Option<usize>,
+    // First row displayed, when there are more rows than fit.
+    scroll: usize,
+    // Index and area of each activity displayed when the selector was last
+    // rendered.
+    cells: Vec<(usize, Rect)>,
+    // Activities marked to be acted on together, when marking.
+    marked: Option<HashSet<ActivityId>>,
 }
 
 impl ActivitySelectorState {
     pub fn new(activity_count: usize) -> Self {
-        return Self {
+        Self {
             activity_count,
             selected_index: if activity_count == 0 { None } else { Some(0) },
+            tag_filter: None,
+            name_filter: String::new(),
+            sort_mode: ActivitySortMode::default(),
+            incomplete_first: false,
+            activities_per_row: DEFAULT_ACTIVITIES_PER_ROW as usize,
+            divider: None,
+            scroll: 0,
+            cells: vec![],
+            marked: None,
+        }
+    }
+
+    /**
+     * Update the number of activities displayed, keeping the selected index
+     * valid.
+     */
+    pub fn set_activity_count(&mut self, activity_count: usize) {
+        self.activity_count = activity_count;
+        self.selected_index = match self.selected_index {
+            _ if activity_count == 0 => None,
+            Some(index) => Some(index.min(activity_count - 1)),
+            None => Some(0),
         };
     }
+
+    pub fn tag_filter(&self) -> Option<&str> {
+        self.tag_filter.as_deref()
+    }
+
+    pub fn set_tag_filter(&mut self, tag_filter: Option<String>) {
+        self.tag_filter = tag_filter;
+    }
+
+    pub fn name_filter(&self) -> &str {
+        &self.name_filter
+    }
+
+    pub fn set_name_filter(&mut self, name_filter: String) {
+        self.name_filter = name_filter;
+    }
+
+    pub fn sort_mode(&self) -> ActivitySortMode {
+        self.sort_mode
+    }
+
+    pub fn set_sort_mode(&mut self, sort_mode: ActivitySortMode) {
+        self.sort_mode = sort_mode;
+    }
+
+    pub fn marking(&self) -> bool {
+        self.marked.is_some()
+    }
+
+    /**
+     * Start or stop marking activities. Stopping clears the marks.
+     */
+    pub fn set_marking(&mut self, marking: bool) {
+        self.marked = marking.then(HashSet::new);
+    }
+
+    /**
+     * Mark an activity, or unmark it if it is marked. Does nothing unless
+     * marking.
+     */
+    pub fn toggle_marked(&mut self, activity_id: ActivityId) {
+        if let Some(marked) = &mut self.marked {
+            if !marked.remove(&activity_id) {
+                marked.insert(activity_id);
+            }
+        }
+    }
+
+    pub fn is_marked(&self, activity_id: ActivityId) -> bool {
+        self.marked
+            .as_ref()
+            .is_some_and(|marked| marked.contains(&activity_id))
+    }
+
+    /**
+     * The marked activities, in no particular order.
+     */
+    pub fn marked(&self) -> Vec<ActivityId> {
+        self.marked
+            .as_ref()
+            .map_or(vec![], |marked| marked.iter().copied().collect())
+    }
+
+    pub fn incomplete_first(&self) -> bool {
+        self.incomplete_first
+    }
+
+    pub fn set_incomplete_first(&mut self, incomplete_first: bool) {
+        self.incomplete_first = incomplete_first;
+    }
+
+    /**
+     * Select the activity at an index, if there is one.
+     */
+    pub fn select(&mut self, index: usize) {
+        if index < self.activity_count {
+            self.selected_index = Some(index);
+        }
+    }
+
     pub fn select_right(&mut self) {
+        self.select_next();
+    }
+
+    pub fn select_left(&mut self) {
+        self.select_previous();
+    }
+
+    /**
+     * Select the activity after the selected one, in the order they are
+     * listed regardless of rows, wrapping around from the last to the first.
+     */
+    pub fn select_next(&mut self) {
         if let Some(index) = self.selected_index {
             self.selected_index = Some((index + 1) % self.activity_count);
         }
     }
 
-    pub fn select_left(&mut self) {
+    /**
+     * Select the activity before the selected one, in the order they are
+     * listed regardless of rows, wrapping around from the first to the last.
+     */
+    pub fn select_previous(&mut self) {
         if let Some(index) = self.selected_index {
             self.selected_index = Some((index + self.activity_count - 1) % self.activity_count);
         }
     }
 
+    fn row_starts(&self) -> Vec<usize> {
+        row_starts(self.activity_count, self.activities_per_row, self.divider)
+    }
+
+    /**
+     * The row and column of the activity at an index.
+     */
+    fn position(&self, index: usize) -> (usize, usize) {
+        let row_starts = self.row_starts();
+        let row = row_starts
+            .iter()
+            .rposition(|&start| start <= index)
+            .unwrap_or(0);
+        (row, index - row_starts[row])
+    }
+
+    /**
+     * Select the activity in the same column of another row, or the last
+     * activity of the row if it is too short.
+     */
+    fn select_in_row(&mut self, row: usize, column: usize) {
+        let row_starts = self.row_starts();
+        let row_end = row_starts
+            .get(row + 1)
+            .copied()
+            .unwrap_or(self.activity_count);
+        self.selected_index = Some((row_starts[row] + column).min(row_end - 1));
+    }
+
+    /**
+     * Select the activity in the row above, wrapping around from the top row
+     * to the last row. If the row above is too short, its last activity is
+     * selected.
+     */
     pub fn select_up(&mut self) {
         if let Some(index) = self.selected_index {
-            if index >= ACTIVITIES_PER_ROW as usize {
-                self.selected_index = Some(index - ACTIVITIES_PER_ROW as usize);
-            }
+            let (row, column) = self.position(index);
+            let rows = self.row_starts().len();
+            self.select_in_row((row + rows - 1) % rows, column);
         }
     }
 
+    /**
+     * Select the activity in the row below, wrapping around from the last row
+     * to the top row. If the row below is too short, its last activity is
+     * selected.
+     */
     pub fn select_down(&mut self) {
         if let Some(index) = self.selected_index {
-            if (index + ACTIVITIES_PER_ROW as usize) < self.activity_count {
-                self.selected_index = Some(index + ACTIVITIES_PER_ROW as usize);
-            }
+            let (row, column) = self.position(index);
+            let rows = self.row_starts().len();
+            self.select_in_row((row + 1) % rows, column);
         }
     }
 
@@ -59,17 +368,97 @@ impl ActivitySelectorState {
     pub fn selected_index(&self) -> Option<usize> {
         self.selected_index
     }
+
+    /**
+     * Index of the activity displayed at (x, y) when the selector was last
+     * rendered.
+     */
+    pub fn index_at(&self, x: u16, y: u16) -> Option<usize> {
+        self.cells
+            .iter()
+            .find(|(_, cell)| {
+                cell.left() <= x && x < cell.right() && cell.top() <= y && y < cell.bottom()
+            })
+            .map(|(index, _)| *index)
+    }
+
+    /**
+     * Scroll so that the selected activity is within the `visible_rows` rows
+     * displayed, out of `rows` rows.
+     */
+    fn scroll_to_selected(&mut self, rows: usize, visible_rows: usize) {
+        if let Some(index) = self.selected_index {
+            let (row, _) = self.position(index);
+            if row < self.scroll {
+                self.scroll = row;
+            } else if row >= self.scroll + visible_rows {
+                self.scroll = row + 1 - visible_rows;
+            }
+        }
+        self.scroll = self.scroll.min(rows.saturating_sub(visible_rows));
+    }
 }
 
 pub trait ActivitySelectorValue {
     fn name(&self) -> &str;
     fn completed(&self) -> bool;
+    /**
+     * Number of times the value was completed. Displayed when more than one.
+     */
+    fn count(&self) -> u32 {
+        u32::from(self.completed())
+    }
+    /**
+     * Number of consecutive days the value was completed. Not displayed when
+     * zero.
+     */
+    fn streak(&self) -> u32 {
+        0
+    }
+    /**
+     * Color of the swatch before the name, and of the name once completed.
+     * Without one, no swatch is drawn.
+     */
+    fn color(&self) -> Option<Color> {
+        None
+    }
+    /**
+     * Whether the value is drawn muted, e.g. when it isn't due.
+     */
+    fn dimmed(&self) -> bool {
+        false
+    }
+    /**
+     * Muted text displayed after the name, if there is room for it.
+     */
+    fn hint(&self) -> Option<String> {
+        None
+    }
+    /**
+     * Progress towards a goal, as the amount done and the goal (e.g. three of
+     * five times a week). Drawn as a bar in place of the completion marker.
+     */
+    fn progress(&self) -> Option<(u32, u32)> {
+        None
+    }
 }
 
 pub struct ActivitySelector<'a, T: ActivitySelectorValue> {
     title: String,
     values: Vec<&'a T>,
     row_height: u16,
+    // Width the selector will be rendered at, which determines its height.
+    width: u16,
+    title_color: Color,
+    // Whether each value is a reminder: part of the routine, but not yet done.
+    reminders: Vec<bool>,
+    // Display each value on a single line, without a border around the
+    // selected value.
+    compact: bool,
+    // Whether each value is marked, when values are being marked.
+    marks: Option<Vec<bool>>,
+    // Index of the first value after the divider, which starts a new row.
+    divider: Option<usize>,
 }
 
 impl<'a, T: ActivitySelectorValue> Default for ActivitySelector<'a, T> {
@@ -78,6 +467,12 @@ impl<'a, T: ActivitySelectorValue> Default for ActivitySelector<'a, T> {
             title: String::from("Activity Selector"),
             values: vec![],
             row_height: 5,
+            width: DEFAULT_ACTIVITIES_PER_ROW * MIN_CELL_WIDTH + 2,
+            title_color: Color::Yellow,
+            reminders: vec![],
+            compact: false,
+            marks: None,
+            divider: None,
         }
     }
 }
@@ -97,22 +492,158 @@ impl<'a, T: ActivitySelectorValue> ActivitySelector<'a, T> {
         self
     }
 
-    fn render_value(&self, area: Rect, buffer: &mut Buffer, index: usize, selected: bool) {
+    pub fn title_color(mut self, title_color: Color) -> Self {
+        self.title_color = title_color;
+        self
+    }
+
+    /**
+     * Flags, in the same order as the values, marking the values to remind
+     * the user of.
+     */
+    pub fn reminders(mut self, reminders: Vec<bool>) -> Self {
+        self.reminders = reminders;
+        self
+    }
+
+    /**
+     * Flags, in the same order as the values, marking the values to act on
+     * together. Each value is displayed with a box showing its mark.
+     */
+    pub fn marks(mut self, marks: Vec<bool>) -> Self {
+        self.marks = Some(marks);
+        self
+    }
+
+    /**
+     * Separate the values before an index from the values after it with a
+     * divider. The values after it start on a new row.
+     */
+    pub fn divider(mut self, divider: Option<usize>) -> Self {
+        self.divider = divider;
+        self
+    }
+
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /**
+     * The width the selector will be rendered at. The number of activities
+     * per row, and so the height of the selector, depend on it.
+     */
+    pub fn width(mut self, width: u16) -> Self {
+        self.width = width;
+        self
+    }
+
+    fn render_value(
+        &self,
+        area: Rect,
+        buffer: &mut Buffer,
+        index: usize,
+        selected: bool,
+        reminder: bool,
+    ) {
         let item = self.values[index];
-        let name = item.name();
-        let (display_string, color) = if item.completed() {
-            (format!("✅ {}", name), Color::Green)
+        let (marker, style) = if item.completed() {
+            (
+                "✅ ",
+                Style::default().fg(item.color().unwrap_or(Color::Green)),
+            )
+        } else if reminder {
+            (
+                "⚠  ",
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else if item.dimmed() {
+            ("―  ", Style::default().fg(Color::DarkGray))
+        } else {
+            ("―  ", Style::default().fg(Color::White))
+        };
+        let mark = match &self.marks {
+            Some(marks) if marks.get(index).copied().unwrap_or(false) => "■ ",
+            Some(_) => "□ ",
+            None => "",
+        };
+        let suffix = match item.count() {
+            0 | 1 => String::new(),
+            count => format!(" ×{}", count),
+        };
+
+        // Compact values have no border, only padding.
+        let (padding, text_y) = if self.compact {
+            (1, area.y)
         } else {
-            (format!("―  {}", name), Color::White)
+            (2, area.y + 1)
+        };
+        // Left and right border and padding.
+        let mut text_width = area.width.saturating_sub(padding * 2);
+        // The streak badge is drawn at the right of the cell, and the name is
+        // cut short to make room for it.
+        let badge = match item.streak() {
+            0 => String::new(),
+            streak => format!("🔥{}", streak),
+        };
+        let badge_width = badge.width() as u16;
+        if badge_width > 0 && badge_width < text_width {
+            // Right border and padding.
+            let badge_x = area.right() - badge_width - padding;
+            buffer.set_string(badge_x, text_y, &badge, Style::default().fg(Color::Yellow));
+            // -1: Space before the badge.
+            text_width -= badge_width + 1;
+        }
+
+        let swatch = match item.color() {
+            Some(_) => SWATCH,
+            None => "",
         };
-        for j in 0..min(display_string.len(), area.width as usize) {
-            buffer
-                .get_mut(area.x + j as u16 + 2, area.y + 1)
-                .set_fg(color)
-                .set_symbol(&display_string);
+        // Progress replaces the completion marker, unless it leaves too little
+        // room for the name.
+        let marker = item
+            .progress()
+            .map(|(done, goal)| progress_bar(done, goal))
+            .filter(|bar| {
+                mark.width() + bar.width() + swatch.width() + suffix.width() + MIN_NAME_WIDTH
+                    <= text_width as usize
+            })
+            .unwrap_or_else(|| marker.to_owned());
+        let prefix = format!("{}{}", mark, marker);
+        let fixed_width = prefix.width() + swatch.width() + suffix.width();
+
+        // The hint is dropped before the name is truncated.
+        let hint = item
+            .hint()
+            .map(|hint| format!(" {}", hint))
+            .filter(|hint| fixed_width + item.name().width() + hint.width() <= text_width as usize)
+            .unwrap_or_default();
+
+        // The prefix, swatch, and count are kept whole, and the name is
+        // truncated.
+        let name_width = (text_width as usize).saturating_sub(fixed_width);
+        let name = format!("{}{}", truncate_to_width(item.name(), name_width), suffix);
+        let text_x = area.x + padding;
+        let mut x = text_x;
+        for (text, style) in [
+            (prefix.as_str(), style),
+            (
+                swatch,
+                Style::default().fg(item.color().unwrap_or(Color::Reset)),
+            ),
+            (name.as_str(), style),
+            (hint.as_str(), Style::default().fg(Color::DarkGray)),
+        ] {
+            let remaining = (text_width - (x - text_x)) as usize;
+            (x, _) = buffer.set_stringn(x, text_y, text, remaining, style);
         }
 
-        if selected {
+        if selected && self.compact {
+            // Invert the colors of the selected item.
+            buffer.set_style(area, Style::default().add_modifier(Modifier::REVERSED));
+        } else if selected {
             // Draw borders around the selected item.
             Block::default()
                 .borders(Borders::ALL)
@@ -121,19 +652,63 @@ impl<'a, T: ActivitySelectorValue> ActivitySelector<'a, T> {
         }
     }
 
-    fn formatted_title(&self) -> String {
-        format!("{: ^width$}", self.title, width = 34)
+    fn render_divider(&self, row_area: Rect, buffer: &mut Buffer) {
+        // -2: Left and right border.
+        let width = row_area.width.saturating_sub(2) as usize;
+        let label = " done ";
+        let line = "┄".repeat(width.saturating_sub(label.len()) / 2);
+        buffer.set_stringn(
+            row_area.x + 1,
+            row_area.y,
+            format!("{}{}{}", line, label, line),
+            width,
+            Style::default().fg(Color::DarkGray),
+        );
+    }
+
+    /**
+     * The title, padded to half the width of the selector, without clipping it.
+     */
+    fn formatted_title(&self, area: Rect) -> String {
+        // -2: Left and right border.
+        let max_width = area.width.saturating_sub(2) as usize;
+        let width = (area.width as usize / 2)
+            .max(self.title.width() + 2)
+            .min(max_width);
+        let padding = width.saturating_sub(self.title.width());
+        format!(
+            "{}{}{}",
+            " ".repeat(padding / 2),
+            self.title,
+            " ".repeat(padding - padding / 2)
+        )
     }
 
+    /**
+     * Height needed to display every row of activities.
+     */
     pub fn height(&self) -> u16 {
-        let values = self.values.len() as u16;
-        let rows = if values % ACTIVITIES_PER_ROW != 0 {
-            values / ACTIVITIES_PER_ROW + 1
-        } else {
-            values / ACTIVITIES_PER_ROW
-        };
+        let per_row = activities_per_row(self.width) as usize;
+        let rows = row_starts(self.values.len(), per_row, self.divider).len() as u16;
+        // +2: Upper and lower border.
+        rows * self.row_height() + 2
+    }
+
+    /**
+     * Height needed to display a single row of activities. With less than
+     * `height()`, the rows are scrolled.
+     */
+    pub fn min_height(&self) -> u16 {
         // +2: Upper and lower border.
-        rows * self.row_height + 2
+        self.row_height() + 2
+    }
+
+    fn row_height(&self) -> u16 {
+        if self.compact {
+            1
+        } else {
+            self.row_height
+        }
     }
 }
 
@@ -141,8 +716,18 @@ impl<'a, T: ActivitySelectorValue> StatefulWidget for ActivitySelector<'a, T> {
     type State = ActivitySelectorState;
 
     fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
-        let title_style = Style::default().fg(Color::Yellow);
-        let title = Span::styled(self.formatted_title(), title_style);
+        let per_row = activities_per_row(area.width);
+        // Navigation moves between rows as they are displayed.
+        state.activities_per_row = per_row as usize;
+        state.divider = self.divider;
+        let row_starts = state.row_starts();
+        let rows = row_starts.len();
+        // -2: Upper and lower border.
+        let visible_rows = (area.height.saturating_sub(2) / self.row_height()).max(1) as usize;
+        state.scroll_to_selected(rows, visible_rows);
+
+        let title_style = Style::default().fg(self.title_color);
+        let title = Span::styled(self.formatted_title(area), title_style);
 
         let border = Block::default()
             .borders(Borders::ALL)
@@ -151,29 +736,162 @@ impl<'a, T: ActivitySelectorValue> StatefulWidget for ActivitySelector<'a, T> {
             .border_type(BorderType::Rounded);
         let row_layout = Layout::default()
             .direction(Direction::Horizontal)
-            .margin(1)
-            .constraints(vec![
-                Constraint::Ratio(1, ACTIVITIES_PER_ROW as u32);
-                ACTIVITIES_PER_ROW as usize
-            ]);
-
-        let mut row_cells: Vec<Rect> = vec![];
-        for i in 0..self.values.len() {
-            let row = i as u16 / ACTIVITIES_PER_ROW;
-            if i as u16 % ACTIVITIES_PER_ROW == 0 {
-                row_cells = row_layout
-                    .clone()
-                    .split(Rect {
-                        x: area.x,
-                        y: area.y + self.row_height * row as u16,
-                        width: area.width,
-                        height: self.row_height,
-                    })
-                    .to_vec();
+            .constraints(vec![Constraint::Ratio(1, per_row as u32); per_row as usize]);
+        // Compact rows are a single line inside the border, while other rows
+        // include the borders of the selected value.
+        let row_layout = if self.compact {
+            row_layout.horizontal_margin(1)
+        } else {
+            row_layout.margin(1)
+        };
+        let row_offset = u16::from(self.compact);
+
+        state.cells.clear();
+        let last_visible_row = (state.scroll + visible_rows).min(rows);
+        for row in state.scroll..last_visible_row {
+            let row_area = Rect {
+                x: area.x,
+                y: area.y + row_offset + self.row_height() * (row - state.scroll) as u16,
+                width: area.width,
+                height: self.row_height(),
+            };
+            let row_cells = row_layout.clone().split(row_area);
+            let row_end = row_starts
+                .get(row + 1)
+                .copied()
+                .unwrap_or(self.values.len());
+            for i in row_starts[row]..row_end {
+                let cell = row_cells[i - row_starts[row]];
+                state.cells.push((i, cell));
+                let reminder = self.reminders.get(i).copied().unwrap_or(false);
+                self.render_value(cell, buffer, i, state.selected(i), reminder);
+            }
+            if Some(row_starts[row]) == self.divider && row > state.scroll && !self.compact {
+                // The divider is drawn in the empty line above the row.
+                self.render_divider(row_area, buffer);
             }
-            let grid_index = (i as u16 % ACTIVITIES_PER_ROW) as usize;
-            self.render_value(row_cells[grid_index], buffer, i, state.selected(i));
         }
         border.render(area, buffer);
+
+        // Indicate rows scrolled out of view, on the right of the borders.
+        let more_style = Style::default().fg(Color::Gray);
+        let more_x = area.right().saturating_sub(MORE_ABOVE.width() as u16 + 2);
+        if state.scroll > 0 {
+            buffer.set_string(more_x, area.top(), MORE_ABOVE, more_style);
+        }
+        if state.scroll + visible_rows < rows {
+            buffer.set_string(more_x, area.bottom() - 1, MORE_BELOW, more_style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Value {
+        name: &'static str,
+        completed: bool,
+    }
+
+    impl ActivitySelectorValue for Value {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn completed(&self) -> bool {
+            self.completed
+        }
+    }
+
+    /**
+     * The text of an activity displayed alone in a selector 22 columns wide,
+     * which leaves 16 columns for the text.
+     */
+    fn rendered_text(name: &'static str) -> String {
+        let value = Value {
+            name,
+            completed: false,
+        };
+        let area = Rect::new(0, 0, 22, 7);
+        let mut buffer = Buffer::empty(area);
+        ActivitySelector::new().values(vec![&value]).render(
+            area,
+            &mut buffer,
+            &mut ActivitySelectorState::new(1),
+        );
+        (3..19).map(|x| buffer.get(x, 2).symbol.as_str()).collect()
+    }
+
+    #[test]
+    fn truncates_long_names_with_an_ellipsis() {
+        assert_eq!(truncate_to_width("Meditation", 10), "Meditation");
+        assert_eq!(truncate_to_width("Meditation", 6), "Medit…");
+        assert_eq!(truncate_to_width("Meditation", 1), "…");
+        assert_eq!(truncate_to_width("Meditation", 0), "");
+    }
+
+    #[test]
+    fn truncates_wide_characters_by_width() {
+        assert_eq!("日本語の勉強".width(), 12);
+        assert_eq!(truncate_to_width("日本語の勉強", 12), "日本語の勉強");
+        assert_eq!(truncate_to_width("日本語の勉強", 6), "日本…");
+        // A character that doesn't fit whole is dropped.
+        assert_eq!(truncate_to_width("日本語の勉強", 7), "日本語…");
+        assert_eq!(truncate_to_width("日本語の勉強", 8), "日本語…");
+    }
+
+    #[test]
+    fn renders_short_names_whole() {
+        assert_eq!(rendered_text("Read"), "―  Read         ");
+    }
+
+    #[test]
+    fn renders_long_names_truncated() {
+        assert_eq!(rendered_text("Practice the piano"), "―  Practice the…");
+    }
+
+    #[test]
+    fn renders_wide_names_truncated() {
+        // Each wide character fills its own cell, and the cell it covers
+        // is left blank.
+        assert_eq!(
+            rendered_text("日本語の勉強をする"),
+            "―  日 本 語 の 勉 強 …"
+        );
+    }
+
+    /**
+     * The index selected after each move, starting from `index` in a grid of
+     * 7 activities, 3 per row:
+     *
+     *   0 1 2
+     *   3 4 5
+     *   6
+     */
+    fn moves(index: usize, select: fn(&mut ActivitySelectorState), count: usize) -> Vec<usize> {
+        let mut state = ActivitySelectorState::new(7);
+        state.select(index);
+        (0..count)
+            .map(|_| {
+                select(&mut state);
+                state.selected_index().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn moves_down_to_the_last_activity_of_a_short_row() {
+        assert_eq!(moves(2, ActivitySelectorState::select_down, 3), [5, 6, 0]);
+        assert_eq!(moves(1, ActivitySelectorState::select_down, 3), [4, 6, 0]);
+        assert_eq!(moves(0, ActivitySelectorState::select_down, 3), [3, 6, 0]);
+    }
+
+    #[test]
+    fn wraps_up_from_the_top_row() {
+        assert_eq!(moves(0, ActivitySelectorState::select_up, 3), [6, 3, 0]);
+        // The last row has no third column.
+        assert_eq!(moves(2, ActivitySelectorState::select_up, 3), [6, 3, 0]);
+        assert_eq!(moves(5, ActivitySelectorState::select_up, 2), [2, 6]);
     }
 }
@@ -0,0 +1,132 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    activites::{ActivitiesStore, ActivityTypesStore},
+    file::File,
+};
+
+/**
+ * A snapshot of both stores in a single file, so that backups are always
+ * consistent with each other.
+ */
+#[derive(Serialize, Deserialize)]
+pub struct Backup {
+    // Version of Daila that created the backup.
+    version: String,
+    created_at: DateTime<Local>,
+    activity_types: ActivityTypesStore,
+    activities: ActivitiesStore,
+}
+
+impl Backup {
+    pub fn new(activity_types: ActivityTypesStore, activities: ActivitiesStore) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            created_at: Local::now(),
+            activity_types,
+            activities,
+        }
+    }
+
+    /**
+     * Snapshot of the stores as they are currently saved, including the
+     * archived activities.
+     */
+    pub fn from_saved() -> Self {
+        let mut activities = ActivitiesStore::load();
+        activities.unarchive();
+        Self::new(ActivityTypesStore::load(), activities)
+    }
+
+    /**
+     * Timestamped file name for a backup created now, in the current directory.
+     */
+    pub fn default_path() -> PathBuf {
+        PathBuf::from(format!(
+            "daila-backup-{}.json",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ))
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let file = std::fs::File::create(path)?;
+        let writer = io::BufWriter::new(file);
+        serde_json::to_writer_pretty(writer, self).map_err(io::Error::from)
+    }
+
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let reader = io::BufReader::new(file);
+        serde_json::from_reader(reader).map_err(io::Error::from)
+    }
+
+    /**
+     * Overwrite both saved stores with the contents of the backup.
+     */
+    pub fn restore(self) {
+        self.activity_types.save();
+        self.activities.save();
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn created_at(&self) -> DateTime<Local> {
+        self.created_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::activites::Activity;
+
+    #[test]
+    fn round_trips_through_a_file() {
+        let mut activity_types = ActivityTypesStore::default();
+        let read = activity_types.create_new_activity(String::from("Read"));
+        let run = activity_types.create_new_activity(String::from("Run"));
+        let mut activities = ActivitiesStore::default();
+        for day in 1..=3 {
+            let date = NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+            activities.add_activity(Activity::new(read, date));
+        }
+        activities.add_activity(Activity::new(
+            run,
+            NaiveDate::from_ymd_opt(2024, 3, 2).unwrap(),
+        ));
+        let backup = Backup::new(activity_types, activities);
+
+        let path =
+            std::env::temp_dir().join(format!("daila-backup-test-{}.json", std::process::id()));
+        backup.write(&path).unwrap();
+        let restored = Backup::read(&path);
+        std::fs::remove_file(&path).unwrap();
+        let restored = restored.unwrap();
+
+        assert_eq!(restored.version(), env!("CARGO_PKG_VERSION"));
+        assert_eq!(restored.created_at(), backup.created_at());
+        let json = |backup: &Backup| {
+            (
+                serde_json::to_value(&backup.activity_types).unwrap(),
+                serde_json::to_value(&backup.activities).unwrap(),
+            )
+        };
+        assert_eq!(json(&restored), json(&backup));
+    }
+
+    #[test]
+    fn fails_to_read_a_missing_file() {
+        let path = std::env::temp_dir().join("daila-backup-test-missing.json");
+        assert!(Backup::read(&path).is_err());
+    }
+}
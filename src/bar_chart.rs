@@ -0,0 +1,374 @@
+use chrono::{Datelike, Days, Months};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    symbols::bar,
+    widgets::Widget,
+};
+
+use crate::heatmap::{self, CalendarDate, HeatMapValue};
+
+const BAR_COLOR: Color = Color::Green;
+// Number of lines the tallest bar spans.
+const BAR_LINES: u16 = 4;
+// Each line of a bar is split into eighths by the bar symbols.
+const LEVELS_PER_LINE: u16 = 8;
+const WEEKDAY_LABELS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/**
+ * The days covered by a bar chart.
+ */
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum BarChartPeriod {
+    // Monday to Sunday.
+    Week,
+    // The first to the last day of the month.
+    Month,
+}
+
+impl BarChartPeriod {
+    /**
+     * Width of the bar of each day, including the space after it.
+     */
+    fn bar_width(&self) -> u16 {
+        match self {
+            BarChartPeriod::Week => 4,
+            BarChartPeriod::Month => 2,
+        }
+    }
+
+    /**
+     * Number of columns the bar itself is drawn in.
+     */
+    fn bar_columns(&self) -> u16 {
+        match self {
+            BarChartPeriod::Week => 2,
+            BarChartPeriod::Month => 1,
+        }
+    }
+}
+
+/**
+ * A bar chart of the number of completions on each day of the week or month
+ * containing a date.
+ */
+pub struct DailyBarChart {
+    // Date whose week or month is displayed.
+    date: CalendarDate,
+    period: BarChartPeriod,
+    // Count of each day of the period, starting at its first day.
+    counts: Vec<Option<f32>>,
+}
+
+impl Default for DailyBarChart {
+    fn default() -> Self {
+        Self {
+            date: chrono::Local::now().date_naive(),
+            period: BarChartPeriod::Week,
+            counts: vec![],
+        }
+    }
+}
+
+// Constructor helpers.
+impl DailyBarChart {
+    pub fn date(mut self, date: CalendarDate) -> Self {
+        self.date = date;
+        self
+    }
+
+    pub fn period(mut self, period: BarChartPeriod) -> Self {
+        self.period = period;
+        self
+    }
+
+    /**
+     * Values to count. Set after the date and period, which determine the
+     * days counted.
+     */
+    pub fn values<T: HeatMapValue>(mut self, values: Vec<&T>) -> Self {
+        self.counts = heatmap::dense_heats(self.first_day(), values);
+        self
+    }
+}
+
+impl DailyBarChart {
+    pub fn first_day(&self) -> CalendarDate {
+        match self.period {
+            BarChartPeriod::Week => self
+                .date
+                .checked_sub_days(Days::new(self.date.weekday().num_days_from_monday().into()))
+                .unwrap(),
+            BarChartPeriod::Month => self.date.with_day(1).unwrap(),
+        }
+    }
+
+    pub fn last_day(&self) -> CalendarDate {
+        match self.period {
+            BarChartPeriod::Week => self.first_day().checked_add_days(Days::new(6)).unwrap(),
+            BarChartPeriod::Month => self
+                .first_day()
+                .checked_add_months(Months::new(1))
+                .and_then(|date| date.pred_opt())
+                .unwrap(),
+        }
+    }
+
+    fn days(&self) -> u16 {
+        self.last_day()
+            .signed_duration_since(self.first_day())
+            .num_days() as u16
+            + 1
+    }
+
+    fn count(&self, day: u16) -> f32 {
+        self.counts
+            .get(day as usize)
+            .copied()
+            .flatten()
+            .unwrap_or(0.0)
+    }
+
+    fn title(&self) -> String {
+        match self.period {
+            BarChartPeriod::Week => format!("Week of {}", self.first_day().format("%-d %B")),
+            BarChartPeriod::Month => self.date.format("%B %Y").to_string(),
+        }
+    }
+
+    /**
+     * The label below the bar of a day: the weekday, abbreviated to fit.
+     */
+    fn label(&self, date: CalendarDate) -> &'static str {
+        let label = WEEKDAY_LABELS[date.weekday().num_days_from_monday() as usize];
+        match self.period {
+            BarChartPeriod::Week => label,
+            BarChartPeriod::Month => &label[..1],
+        }
+    }
+
+    /**
+     * The symbol of a line of a bar, counting lines up from the bottom, for a
+     * bar `level` eighths of a line tall.
+     */
+    fn bar_symbol(level: u16, line: u16) -> &'static str {
+        let line_level = level.saturating_sub(line * LEVELS_PER_LINE);
+        match line_level {
+            0 => bar::NINE_LEVELS.empty,
+            1 => bar::NINE_LEVELS.one_eighth,
+            2 => bar::NINE_LEVELS.one_quarter,
+            3 => bar::NINE_LEVELS.three_eighths,
+            4 => bar::NINE_LEVELS.half,
+            5 => bar::NINE_LEVELS.five_eighths,
+            6 => bar::NINE_LEVELS.three_quarters,
+            7 => bar::NINE_LEVELS.seven_eighths,
+            _ => bar::NINE_LEVELS.full,
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        (self.days() * self.period.bar_width()).max(self.title().len() as u16)
+    }
+
+    pub fn height(&self) -> u16 {
+        // +2: Title and weekday labels.
+        BAR_LINES + 2
+    }
+}
+
+impl Widget for DailyBarChart {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let area = area.intersection(*buffer.area());
+        if area.width < self.width() || area.height < self.height() {
+            return;
+        }
+
+        buffer.set_string(
+            area.x,
+            area.y,
+            self.title(),
+            Style::default().fg(Color::Yellow),
+        );
+
+        // Bars are scaled so that the busiest day is full height.
+        let max_count = (0..self.days())
+            .map(|day| self.count(day))
+            .fold(1.0, f32::max);
+        let max_level = BAR_LINES * LEVELS_PER_LINE;
+        let bottom = area.y + BAR_LINES;
+        let bar_style = Style::default().fg(BAR_COLOR);
+        let baseline_style = Style::default().fg(Color::DarkGray);
+
+        let mut date = self.first_day();
+        for day in 0..self.days() {
+            let x = area.x + day * self.period.bar_width();
+            let level = (self.count(day) / max_count * max_level as f32).round() as u16;
+            for column in x..x + self.period.bar_columns() {
+                for line in 0..BAR_LINES {
+                    let symbol = Self::bar_symbol(level, line);
+                    buffer.set_string(column, bottom - line, symbol, bar_style);
+                }
+                if level == 0 {
+                    // Days without completions are drawn as a flat baseline.
+                    let symbol = bar::NINE_LEVELS.one_eighth;
+                    buffer.set_string(column, bottom, symbol, baseline_style);
+                }
+            }
+            buffer.set_string(
+                x,
+                bottom + 1,
+                self.label(date),
+                Style::default().fg(Color::Gray),
+            );
+            date = date.checked_add_days(Days::new(1)).unwrap();
+        }
+    }
+}
+
+/**
+ * A bar chart of the share of each day of the week an activity was done on,
+ * between two dates.
+ */
+pub struct WeekdayBarChart {
+    start: CalendarDate,
+    end: CalendarDate,
+    // Share of each day of the week, from Monday, the activity was done on,
+    // or None for days of the week not between the dates.
+    rates: [Option<f32>; 7],
+}
+
+impl Default for WeekdayBarChart {
+    fn default() -> Self {
+        let today = chrono::Local::now().date_naive();
+        Self {
+            start: today,
+            end: today,
+            rates: [None; 7],
+        }
+    }
+}
+
+// Constructor helpers.
+impl WeekdayBarChart {
+    pub fn range(mut self, start: CalendarDate, end: CalendarDate) -> Self {
+        self.start = start;
+        self.end = end;
+        self
+    }
+
+    pub fn rates(mut self, rates: [Option<f32>; 7]) -> Self {
+        self.rates = rates;
+        self
+    }
+}
+
+impl WeekdayBarChart {
+    // Width of the bar of each day, including the space after it, which fits
+    // "100%".
+    const BAR_WIDTH: u16 = 5;
+    const BAR_COLUMNS: u16 = 3;
+
+    fn title(&self) -> String {
+        format!(
+            "By day of the week, {} to {}",
+            self.start.format("%-d %b %Y"),
+            self.end.format("%-d %b %Y")
+        )
+    }
+
+    pub fn width(&self) -> u16 {
+        (7 * Self::BAR_WIDTH).max(self.title().len() as u16)
+    }
+
+    pub fn height(&self) -> u16 {
+        // +3: Title, weekday labels, and percentages.
+        BAR_LINES + 3
+    }
+}
+
+impl Widget for WeekdayBarChart {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let area = area.intersection(*buffer.area());
+        if area.width < self.width() || area.height < self.height() {
+            return;
+        }
+
+        buffer.set_string(
+            area.x,
+            area.y,
+            self.title(),
+            Style::default().fg(Color::Yellow),
+        );
+        let bottom = area.y + BAR_LINES;
+        if self.rates.iter().flatten().all(|rate| *rate == 0.0) {
+            buffer.set_string(
+                area.x,
+                bottom,
+                "Not done between these dates",
+                Style::default().fg(Color::Gray),
+            );
+            return;
+        }
+
+        // Bars are full height for days of the week the activity was done on
+        // every time.
+        let max_level = BAR_LINES * LEVELS_PER_LINE;
+        let bar_style = Style::default().fg(BAR_COLOR);
+        let baseline_style = Style::default().fg(Color::DarkGray);
+        let label_style = Style::default().fg(Color::Gray);
+        for (weekday, rate) in self.rates.iter().enumerate() {
+            let x = area.x + weekday as u16 * Self::BAR_WIDTH;
+            let level = (rate.unwrap_or(0.0) * max_level as f32).round() as u16;
+            for column in x..x + Self::BAR_COLUMNS {
+                for line in 0..BAR_LINES {
+                    let symbol = DailyBarChart::bar_symbol(level, line);
+                    buffer.set_string(column, bottom - line, symbol, bar_style);
+                }
+                if level == 0 {
+                    let symbol = bar::NINE_LEVELS.one_eighth;
+                    buffer.set_string(column, bottom, symbol, baseline_style);
+                }
+            }
+            buffer.set_string(x, bottom + 1, WEEKDAY_LABELS[weekday], label_style);
+            let percentage = match rate {
+                Some(rate) => format!("{:.0}%", rate * 100.0),
+                None => String::from("-"),
+            };
+            buffer.set_string(x, bottom + 2, percentage, label_style);
+        }
+    }
+}
+
+/**
+ * Either bar chart, so that they can be displayed in the same place.
+ */
+pub enum BarChart {
+    Daily(DailyBarChart),
+    Weekdays(WeekdayBarChart),
+}
+
+impl BarChart {
+    pub fn width(&self) -> u16 {
+        match self {
+            BarChart::Daily(chart) => chart.width(),
+            BarChart::Weekdays(chart) => chart.width(),
+        }
+    }
+
+    pub fn height(&self) -> u16 {
+        match self {
+            BarChart::Daily(chart) => chart.height(),
+            BarChart::Weekdays(chart) => chart.height(),
+        }
+    }
+}
+
+impl Widget for BarChart {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        match self {
+            BarChart::Daily(chart) => chart.render(area, buffer),
+            BarChart::Weekdays(chart) => chart.render(area, buffer),
+        }
+    }
+}
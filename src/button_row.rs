@@ -0,0 +1,170 @@
+/*!
+ * The row of buttons along the bottom of a popup, e.g. "cancel" and
+ * "continue", drawn and moved between the same way in every popup.
+ */
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Widget},
+};
+
+pub enum ButtonRowEvent {
+    // The focus moved to the button at an index.
+    Focus(usize),
+    // The button at an index was pressed.
+    Press(usize),
+}
+
+/**
+ * Buttons side by side, each as wide as the others, the focused one (if any)
+ * drawn in brackets on a dark background and the others in dark text on a
+ * light background.
+ */
+pub struct ButtonRow<'a> {
+    labels: Vec<&'a str>,
+    // Index of the focused button, if a button is focused.
+    selected: Option<usize>,
+}
+
+impl<'a> ButtonRow<'a> {
+    pub fn new(labels: Vec<&'a str>) -> Self {
+        Self {
+            labels,
+            selected: None,
+        }
+    }
+
+    pub fn selected(mut self, selected: Option<usize>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /**
+     * Move the focus along the buttons (Left or Right), stopping at either
+     * end, or press the focused button (Enter). None if no button is focused
+     * or the key isn't for the buttons.
+     */
+    pub fn handle_key(&self, code: KeyCode) -> Option<ButtonRowEvent> {
+        let selected = self.selected?;
+        match code {
+            KeyCode::Left => Some(ButtonRowEvent::Focus(selected.saturating_sub(1))),
+            KeyCode::Right => Some(ButtonRowEvent::Focus(
+                (selected + 1).min(self.labels.len().saturating_sub(1)),
+            )),
+            KeyCode::Enter => Some(ButtonRowEvent::Press(selected)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Widget for ButtonRow<'a> {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        if self.labels.is_empty() {
+            return;
+        }
+        let count = self.labels.len() as u32;
+        let button_areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, count); self.labels.len()])
+            .split(area);
+        for (index, (label, button_area)) in self.labels.iter().zip(button_areas.iter()).enumerate()
+        {
+            let focused = self.selected == Some(index);
+            Block::default()
+                .title(if focused {
+                    format!("[{}]", label)
+                } else {
+                    (*label).to_owned()
+                })
+                .title_alignment(Alignment::Center)
+                .style(if focused {
+                    Style::default().fg(Color::White).bg(Color::Black)
+                } else {
+                    Style::default().fg(Color::Black).bg(Color::Gray)
+                })
+                .render(*button_area, buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /**
+     * A row of buttons rendered on a single line of the given width.
+     */
+    fn render(button_row: ButtonRow, width: u16) -> Buffer {
+        let area = Rect::new(0, 0, width, 1);
+        let mut buffer = Buffer::empty(area);
+        button_row.render(area, &mut buffer);
+        buffer
+    }
+
+    fn text(buffer: &Buffer) -> String {
+        (0..buffer.area.width)
+            .map(|x| buffer.get(x, 0).symbol.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn draws_the_focused_button_in_brackets() {
+        let buffer = render(ButtonRow::new(vec!["cancel", "save"]).selected(Some(1)), 20);
+        assert_eq!(text(&buffer), "  cancel    [save]  ");
+        assert_eq!(buffer.get(0, 0).bg, Color::Gray);
+        assert_eq!(buffer.get(10, 0).bg, Color::Black);
+    }
+
+    #[test]
+    fn draws_no_button_focused_without_a_selection() {
+        let buffer = render(ButtonRow::new(vec!["no", "yes"]), 10);
+        assert_eq!(text(&buffer), " no   yes ");
+        assert!((0..10).all(|x| buffer.get(x, 0).bg == Color::Gray));
+    }
+
+    #[test]
+    fn draws_nothing_without_buttons() {
+        let buffer = render(ButtonRow::new(vec![]).selected(Some(0)), 10);
+        assert_eq!(buffer, Buffer::empty(buffer.area));
+    }
+
+    #[test]
+    fn moves_the_focus_up_to_either_end() {
+        let button_row = ButtonRow::new(vec!["a", "b", "c"]).selected(Some(1));
+        assert!(matches!(
+            button_row.handle_key(KeyCode::Left),
+            Some(ButtonRowEvent::Focus(0))
+        ));
+        assert!(matches!(
+            button_row.handle_key(KeyCode::Right),
+            Some(ButtonRowEvent::Focus(2))
+        ));
+
+        let button_row = ButtonRow::new(vec!["a", "b", "c"]).selected(Some(0));
+        assert!(matches!(
+            button_row.handle_key(KeyCode::Left),
+            Some(ButtonRowEvent::Focus(0))
+        ));
+        let button_row = button_row.selected(Some(2));
+        assert!(matches!(
+            button_row.handle_key(KeyCode::Right),
+            Some(ButtonRowEvent::Focus(2))
+        ));
+    }
+
+    #[test]
+    fn presses_the_focused_button_with_enter() {
+        let button_row = ButtonRow::new(vec!["a", "b"]).selected(Some(1));
+        assert!(matches!(
+            button_row.handle_key(KeyCode::Enter),
+            Some(ButtonRowEvent::Press(1))
+        ));
+        assert!(button_row.handle_key(KeyCode::Char('a')).is_none());
+
+        let button_row = ButtonRow::new(vec!["a", "b"]);
+        assert!(button_row.handle_key(KeyCode::Enter).is_none());
+        assert!(button_row.handle_key(KeyCode::Left).is_none());
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, Days, Months};
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{heatmap::CalendarDate, month_view::MonthView, popup::Popup};
+
+// Keys of the popup, displayed below the calendar.
+const HINTS: &str = "←/→ day · ↑/↓ week · </> month · enter: go";
+
+#[derive(Default)]
+pub struct CalendarPopup {}
+
+pub enum CalendarPopupAction {
+    // Go to the selected date.
+    Select(CalendarDate),
+    Exit,
+}
+
+/**
+ * State for a popup picking a day from a calendar of its month.
+ */
+pub struct CalendarPopupState {
+    // Day selected in the calendar, whose month is displayed.
+    date: CalendarDate,
+    // Days the activity was completed on, marked in the calendar.
+    completed: BTreeSet<CalendarDate>,
+    // Color of the days the activity was completed on.
+    color: Color,
+}
+
+impl CalendarPopupState {
+    pub fn new(date: CalendarDate, completed: BTreeSet<CalendarDate>) -> Self {
+        Self {
+            date,
+            completed,
+            color: Color::Green,
+        }
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /**
+     * Move the selection by a number of days, staying on the same day if it
+     * would leave the supported dates.
+     */
+    fn move_days(&mut self, days: i64) {
+        let date = if days < 0 {
+            self.date.checked_sub_days(Days::new(days.unsigned_abs()))
+        } else {
+            self.date.checked_add_days(Days::new(days as u64))
+        };
+        if let Some(date) = date {
+            self.date = date;
+        }
+    }
+
+    /**
+     * Move the selection to the same day of the previous or next month,
+     * clamped to the last day of the month.
+     */
+    fn move_months(&mut self, months: i32) {
+        let date = if months < 0 {
+            self.date
+                .checked_sub_months(Months::new(months.unsigned_abs()))
+        } else {
+            self.date.checked_add_months(Months::new(months as u32))
+        };
+        if let Some(date) = date {
+            self.date = date;
+        }
+    }
+
+    /**
+     * Heat of each day of the selected date's month, starting at its first
+     * day.
+     */
+    fn heats(&self) -> Vec<Option<f32>> {
+        let first_day = self.date.with_day(1).unwrap();
+        first_day
+            .iter_days()
+            .take_while(|date| date.month() == first_day.month())
+            .map(|date| {
+                Some(if self.completed.contains(&date) {
+                    1.0
+                } else {
+                    0.0
+                })
+            })
+            .collect()
+    }
+}
+
+impl Popup<CalendarPopupState> for CalendarPopup {
+    type Action = CalendarPopupAction;
+
+    fn handle_event(event: &Event, state: &mut CalendarPopupState) -> Option<Self::Action> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        match key_event.code {
+            KeyCode::Esc => return Some(CalendarPopupAction::Exit),
+            KeyCode::Enter => return Some(CalendarPopupAction::Select(state.date)),
+            KeyCode::Left => state.move_days(-1),
+            KeyCode::Right => state.move_days(1),
+            KeyCode::Up => state.move_days(-7),
+            KeyCode::Down => state.move_days(7),
+            KeyCode::Char('<') | KeyCode::PageUp => state.move_months(-1),
+            KeyCode::Char('>') | KeyCode::PageDown => state.move_months(1),
+            _ => (),
+        }
+        None
+    }
+
+    fn desired_size(&self, state: &CalendarPopupState, _area: &Rect) -> (u16, u16) {
+        let month_view = MonthView::default().date(state.date);
+        // +4: Borders, and padding of a column on each side.
+        let width = month_view.width().max(HINTS.width() as u16) + 4;
+        // +6: Borders, a blank line above and below the calendar, and the
+        // hints.
+        (width, month_view.height() + 6)
+    }
+}
+
+impl StatefulWidget for CalendarPopup {
+    type State = CalendarPopupState;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title("  Calendar  ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title_alignment(Alignment::Center)
+            .style(Style::default());
+        let inner = block.inner(area);
+        block.render(area, buffer);
+
+        let month_view = MonthView::default()
+            .date(state.date)
+            .heats(state.heats())
+            .color_range(Color::DarkGray, state.color);
+        let (width, height) = (month_view.width(), month_view.height());
+        // +2: Blank line and key hints below the calendar.
+        if inner.width < width || inner.height < height + 2 {
+            return;
+        }
+        let x = inner.x + (inner.width - width) / 2;
+        let y = inner.y + (inner.height - height - 2) / 2;
+        month_view.render(Rect::new(x, y, width, height), buffer);
+
+        Block::default()
+            .title(HINTS)
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(Color::Gray))
+            .render(Rect::new(inner.x, y + height + 1, inner.width, 1), buffer);
+    }
+}
@@ -1,33 +1,37 @@
 use crossterm::event::{Event, KeyCode};
 use ratatui::{
     buffer::Buffer,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Rect},
     style::{Color, Style},
-    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Paragraph, StatefulWidget, Widget},
 };
 
-use crate::popup::{self, Popup};
+use std::time::{Duration, Instant};
 
-#[derive(Copy, Clone)]
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{
+    button_row::{ButtonRow, ButtonRowEvent},
+    focus,
+    popup::Popup,
+};
+
+// Widest a line of the prompt is, before it wraps.
+const MAX_PROMPT_WIDTH: usize = 60;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
 enum CursorPosition {
     LeftButton,
     RightButton,
 }
 
-impl CursorPosition {
-    fn next(&self, direction: KeyCode) -> Self {
-        match &self {
-            CursorPosition::LeftButton => match direction {
-                KeyCode::Right => CursorPosition::RightButton,
-                _ => CursorPosition::LeftButton,
-            },
-            CursorPosition::RightButton => match direction {
-                KeyCode::Left => CursorPosition::LeftButton,
-                _ => CursorPosition::RightButton,
-            },
-        }
-    }
-}
+// Buttons from left to right, and their labels.
+const BUTTONS: [CursorPosition; 2] = [CursorPosition::LeftButton, CursorPosition::RightButton];
+const BUTTON_LABELS: [&str; 2] = ["(n) cancel", "(y) continue"];
+
+// Buttons in the order Tab moves the focus through them.
+const FOCUS_ORDER: [CursorPosition; 2] = [CursorPosition::RightButton, CursorPosition::LeftButton];
 
 #[derive(Default)]
 pub struct ConfirmationPopup {}
@@ -40,6 +44,10 @@ pub enum ConfirmationPopupAction {
 pub struct ConfirmationPopupState {
     cursor_position: CursorPosition,
     prompt: String,
+    // Lines displayed under the prompt, e.g. what would be lost.
+    details: Vec<String>,
+    // When the popup is declined if it is still open, if ever.
+    deadline: Option<Instant>,
 }
 
 impl ConfirmationPopupState {
@@ -47,8 +55,103 @@ impl ConfirmationPopupState {
         Self {
             cursor_position: CursorPosition::LeftButton,
             prompt,
+            details: vec![],
+            deadline: None,
+        }
+    }
+
+    pub fn details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+
+    /**
+     * The prompt and the details, wrapped to `width` columns, with a blank
+     * line between them if there are details.
+     */
+    fn lines(&self, width: usize) -> Vec<(String, Color)> {
+        let mut lines: Vec<(String, Color)> = wrap(&self.prompt, width)
+            .into_iter()
+            .map(|line| (line, Color::Red))
+            .collect();
+        if !self.details.is_empty() {
+            lines.push((String::new(), Color::Reset));
+        }
+        for detail in &self.details {
+            lines.extend(
+                wrap(detail, width)
+                    .into_iter()
+                    .map(|line| (line, Color::Gray)),
+            );
+        }
+        lines
+    }
+
+    /**
+     * Decline the popup if it is left open for the given duration, with a
+     * countdown displayed.
+     */
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    /**
+     * Whether the popup has been open for longer than its timeout.
+     */
+    pub fn expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    fn button_row(&self) -> ButtonRow<'static> {
+        ButtonRow::new(BUTTON_LABELS.to_vec()).selected(
+            BUTTONS
+                .iter()
+                .position(|button| *button == self.cursor_position),
+        )
+    }
+
+    fn title(&self) -> String {
+        match self.deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                // Rounded up, so the countdown ends at 1s rather than 0s.
+                let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+                format!("  Confirmation (cancels in {}s)  ", seconds)
+            }
+            None => String::from("  Confirmation  "),
+        }
+    }
+}
+
+/**
+ * Break text into lines at most `width` columns wide, between words where
+ * possible. Words wider than a line are broken between characters.
+ */
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        // +1: Space before the word.
+        if !line.is_empty() && line.width() + 1 + word.width() > width {
+            lines.push(std::mem::take(&mut line));
         }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        for c in word.chars() {
+            if line.width() + c.width().unwrap_or(0) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
     }
+    lines
 }
 
 impl Popup<ConfirmationPopupState> for ConfirmationPopup {
@@ -57,82 +160,163 @@ impl Popup<ConfirmationPopupState> for ConfirmationPopup {
     fn handle_event(event: &Event, state: &mut ConfirmationPopupState) -> Option<Self::Action> {
         if let Event::Key(key_event) = event {
             match key_event.code {
-                KeyCode::Left | KeyCode::Right => {
-                    state.cursor_position = state.cursor_position.next(key_event.code);
+                KeyCode::Esc | KeyCode::Char('n') => Some(ConfirmationPopupAction::Decline),
+                KeyCode::Char('y') => Some(ConfirmationPopupAction::Accept),
+                KeyCode::Left | KeyCode::Right | KeyCode::Enter => {
+                    match state.button_row().handle_key(key_event.code)? {
+                        ButtonRowEvent::Focus(index) => {
+                            state.cursor_position = BUTTONS[index];
+                            None
+                        }
+                        ButtonRowEvent::Press(index) => match BUTTONS[index] {
+                            CursorPosition::LeftButton => Some(ConfirmationPopupAction::Decline),
+                            CursorPosition::RightButton => Some(ConfirmationPopupAction::Accept),
+                        },
+                    }
+                }
+                KeyCode::Tab => {
+                    state.cursor_position = focus::next(&FOCUS_ORDER, state.cursor_position);
+                    None
+                }
+                KeyCode::BackTab => {
+                    state.cursor_position = focus::previous(&FOCUS_ORDER, state.cursor_position);
                     None
                 }
-                KeyCode::Enter => match state.cursor_position {
-                    CursorPosition::LeftButton => Some(ConfirmationPopupAction::Decline),
-                    CursorPosition::RightButton => Some(ConfirmationPopupAction::Accept),
-                },
                 _ => None,
             }
         } else {
             None
         }
     }
+
+    fn desired_size(&self, state: &ConfirmationPopupState, area: &Rect) -> (u16, u16) {
+        // Borders, and padding of two columns on each side.
+        let padding = 6;
+        let prompt_width = (area.width as usize)
+            .saturating_sub(padding)
+            .min(MAX_PROMPT_WIDTH);
+        let lines = state.lines(prompt_width);
+        let widest = lines
+            .iter()
+            .map(|(line, _)| line.width())
+            .max()
+            .unwrap_or(0);
+        let width = widest.max(state.title().width()) + padding;
+        // Borders, the prompt, details and buttons, and a blank line above
+        // the prompt and the buttons.
+        (width as u16, lines.len() as u16 + 5)
+    }
 }
 
 impl StatefulWidget for ConfirmationPopup {
     type State = ConfirmationPopupState;
 
     fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
-        let selected_color = Color::Black;
-        let not_selected_color = Color::Gray;
-
         let block = Block::default()
-            .title("  Confirmation  ")
+            .title(state.title())
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title_alignment(Alignment::Center)
             .style(Style::default());
 
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![
-                Constraint::Length(area.height - 3),
-                Constraint::Length(1),
-            ])
-            .split(area);
+        // Within the padding, so that the prompt wraps as it was sized to.
+        let prompt_lines: Vec<Spans> = state
+            .lines(area.width.saturating_sub(6) as usize)
+            .into_iter()
+            .map(|(line, color)| Spans::from(Span::styled(line, Style::default().fg(color))))
+            .collect();
+        let prompt = Paragraph::new(prompt_lines).alignment(Alignment::Center);
 
-        let prompt = Block::default()
-            .title(state.prompt.as_str())
-            .title_alignment(Alignment::Center)
-            .borders(Borders::NONE)
-            .style(Style::default().fg(Color::Red));
-
-        let bottom_row = Layout::default()
-            .direction(Direction::Horizontal)
-            .margin(1)
-            .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(layout[1]);
-
-        let cancel = Block::default()
-            .title(match state.cursor_position {
-                CursorPosition::LeftButton => "[cancel]",
-                _ => "cancel",
+        block.render(area, buffer);
+        // Borders, blank lines around the prompt, and the prompt.
+        if area.height < 5 || area.width < 4 {
+            return;
+        }
+        // Below the top border and a blank line, above the buttons and the
+        // blank line above them.
+        let prompt_area = Rect::new(area.x + 2, area.y + 2, area.width - 4, area.height - 5);
+        prompt.render(prompt_area, buffer);
+        // Within the borders, above the bottom border.
+        let buttons_area = Rect::new(area.x + 1, area.bottom() - 2, area.width - 2, 1);
+        state.button_row().render(buttons_area, buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyEvent, KeyModifiers};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::popup;
+
+    fn press(state: &mut ConfirmationPopupState, code: KeyCode) -> Option<ConfirmationPopupAction> {
+        let event = Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        ConfirmationPopup::handle_event(&event, state)
+    }
+
+    #[test]
+    fn declines_with_esc() {
+        let mut state = ConfirmationPopupState::new(String::from("Quit without saving?"));
+        // Even with the accept button focused.
+        press(&mut state, KeyCode::Right);
+        assert!(matches!(
+            press(&mut state, KeyCode::Esc),
+            Some(ConfirmationPopupAction::Decline)
+        ));
+    }
+
+    #[test]
+    fn wraps_long_prompts_to_fit_the_terminal() {
+        // 40 words of four letters: 199 columns.
+        let words: Vec<String> = (0..40).map(|i| format!("w{:03}", i)).collect();
+        let prompt = words.join(" ");
+        assert_eq!(prompt.width(), 199);
+        let mut state = ConfirmationPopupState::new(prompt);
+
+        let area = Rect::new(0, 0, 80, 24);
+        // Lines of 12 words (59 columns) fit within the widest line.
+        assert_eq!(
+            ConfirmationPopup::default().desired_size(&state, &area),
+            (65, 9)
+        );
+
+        let mut terminal = Terminal::new(TestBackend::new(area.width, area.height)).unwrap();
+        terminal
+            .draw(|frame| {
+                popup::render_in_frame(
+                    frame,
+                    &frame.size(),
+                    ConfirmationPopup::default(),
+                    &mut state,
+                )
             })
-            .borders(Borders::NONE)
-            .title_alignment(Alignment::Center)
-            .style(Style::default().bg(match state.cursor_position {
-                CursorPosition::LeftButton => selected_color,
-                _ => not_selected_color,
-            }));
-        let accept = Block::default()
-            .title(match state.cursor_position {
-                CursorPosition::RightButton => "[continue]",
-                _ => "continue",
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buffer.get(x, y).symbol.as_str())
+                    .collect()
             })
-            .borders(Borders::NONE)
-            .title_alignment(Alignment::Center)
-            .style(Style::default().bg(match state.cursor_position {
-                CursorPosition::RightButton => selected_color,
-                _ => not_selected_color,
-            }));
+            .collect();
 
-        block.render(area, buffer);
-        prompt.render(popup::centered_area(&layout[0], 90, 50), buffer);
-        cancel.render(bottom_row[0], buffer);
-        accept.render(bottom_row[1], buffer);
+        // Every word is displayed, in order, within the popup.
+        let displayed: Vec<&str> = rows
+            .iter()
+            .flat_map(|row| row.split_whitespace())
+            .filter(|word| word.len() == 4 && word.starts_with('w'))
+            .collect();
+        assert_eq!(displayed, words);
+        // Nothing is drawn either side of the popup.
+        let popup_x = ((area.width - 65) / 2) as usize;
+        for row in &rows {
+            let outside: String = row
+                .chars()
+                .take(popup_x)
+                .chain(row.chars().skip(popup_x + 65))
+                .collect();
+            assert!(outside.trim().is_empty());
+        }
     }
 }
@@ -1,29 +1,98 @@
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
 use std::io;
+use std::time::{Duration, Instant};
 
-use chrono::NaiveDate;
-use crossterm::event::{self, Event, KeyCode};
+use chrono::{Datelike, Days, Months, NaiveDate};
+use crossterm::event::{
+    self, Event, KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use ratatui::backend::Backend;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Style};
 use ratatui::text::Text;
-use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
-use ratatui::Terminal;
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph};
+use ratatui::{Frame, Terminal};
 
 use crate::activites::{
-    self, ActivitiesStore, Activity, ActivityId, ActivityOption, ActivityTypesStore,
+    self, ActivitiesStore, Activity, ActivityColor, ActivityId, ActivityOption, ActivitySortMode,
+    ActivityType, ActivityTypesStore,
+};
+use crate::activity_picker_popup::{ActivityPickerPopup, ActivityPickerPopupState};
+use crate::activity_popup::{
+    ActivityDraft, ActivityPopup, ActivityPopupAction, ActivityPopupState,
 };
-use crate::activity_popup::{ActivityPopup, ActivityPopupAction, ActivityPopupState};
-use crate::activity_selector::{ActivitySelector, ActivitySelectorState, ActivitySelectorValue};
+use crate::activity_selector::{
+    fuzzy_score, ActivitySelector, ActivitySelectorState, ActivitySelectorValue,
+};
+use crate::bar_chart::{BarChart, BarChartPeriod, DailyBarChart, WeekdayBarChart};
+use crate::calendar_popup::{CalendarPopup, CalendarPopupAction, CalendarPopupState};
 use crate::confirmation_popup::{
     ConfirmationPopup, ConfirmationPopupAction, ConfirmationPopupState,
 };
 use crate::file::File;
-use crate::heatmap::HeatMap;
+use crate::freeze_popup::{FreezePopup, FreezePopupAction, FreezePopupState};
+use crate::goto_date_popup::{GotoDatePopup, GotoDatePopupAction, GotoDatePopupState};
+use crate::heatmap::{self, HeatMap, HeatMapDateRange, HeatMapValue};
+use crate::heatmap_range_popup::{
+    HeatMapRange, HeatMapRangePopup, HeatMapRangePopupAction, HeatMapRangePopupState,
+};
+use crate::help_popup::{HelpPopup, HelpPopupAction, HelpPopupState, HelpSection};
+use crate::log_on_date_popup::{LogOnDatePopup, LogOnDatePopupAction, LogOnDatePopupState};
+use crate::month_view::MonthView;
+use crate::note_editor_popup::{NoteEditorPopup, NoteEditorPopupAction, NoteEditorPopupState};
+use crate::number_prompt_popup::{
+    NumberPromptPopup, NumberPromptPopupAction, NumberPromptPopupState,
+};
 use crate::popup::{self, Popup};
+use crate::preferences::Preferences;
+use crate::search_popup::{SearchCandidate, SearchPopup, SearchPopupAction, SearchPopupState};
+
+// How long to wait for an input event before redrawing.
+const EVENT_POLL_TIMEOUT: Duration = Duration::from_millis(250);
+// How long a status message is displayed for.
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(2);
+// Number of weeks displayed by each heatmap in the overview.
+const OVERVIEW_WEEKS: u64 = 20;
+// Number of rows in each heatmap in the overview.
+const OVERVIEW_ROWS: u16 = 3;
+// Bounds on the number of rows in the heatmap.
+const MIN_HEATMAP_ROWS: u16 = 1;
+const MAX_HEATMAP_ROWS: u16 = 14;
+// Lengths, in days, of the rolling windows the heatmap can display.
+const HEATMAP_WINDOWS: [u64; 4] = [30, 90, 180, 365];
+// Height of the line summarizing everything tracked, below the calendar.
+const FOOTER_HEIGHT: u16 = 1;
+// Number of days before today an activity's routine is judged from.
+const ROUTINE_DAYS: u64 = 14;
+// Fraction of those days an activity must have been done on to be part of
+// the routine.
+const ROUTINE_COMPLETION_RATE: f32 = 0.7;
+// Number of days, up to today, listed in the search popup.
+const SEARCH_DAYS: usize = 60;
+// Time after which quitting without saving is cancelled, if not confirmed.
+const QUIT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(30);
+// Height of the line below the footer pointing to the help popup.
+const HELP_HINT_HEIGHT: u16 = 1;
+// Listed in the help popup after the keys of events, which are for events
+// outside of popups.
+const POPUP_INSTRUCTIONS: [&str; 4] = [
+    "esc: close the open popup",
+    "↑/↓: move between the fields of a popup",
+    "tab/shift+tab: move to the next/previous field or button, wrapping around",
+    "y/n: continue/cancel in a confirmation popup",
+];
 
 pub enum ConfirmationAction {
     SaveWithoutQuitting,
     DeleteActivity(ActivityId),
+    DeleteActivities(Vec<ActivityId>),
+    // Move the completions of the first activity to the second, and delete
+    // the first.
+    MergeActivity(ActivityId, ActivityId),
+    CopyPreviousDay,
+    // Close the activity popup below the confirmation without saving it.
+    DiscardActivityChanges,
 }
 
 use DailaEvent::*;
@@ -32,22 +101,70 @@ use DailaEvent::*;
 enum DailaEvent {
     GotoPreviousDay,
     GotoNextDay,
+    GotoPreviousMonth,
+    GotoNextMonth,
     GotoToday,
     ActivityUp,
     ActivityDown,
     ActivityLeft,
     ActivityRight,
+    SelectNextActivity,
+    SelectPreviousActivity,
     ToggleSelectedActivity,
+    CompleteSelectedActivity,
+    UncompleteSelectedActivity,
     SaveAndQuit,
     QuitWithoutSaving,
     CreateNewActivity,
     EditSelectedActivity,
     DeleteSelectedActivity,
+    MergeSelectedActivity,
+    ToggleOverview,
+    CompleteAllActivities,
+    ClearAllActivities,
+    CopyPreviousDay,
+    ToggleMonthZoom,
+    GotoLastCompleted,
+    OpenCalendar,
+    GotoDate,
+    OpenSearch,
+    LogOnDate,
+    FreezeDays,
+    EditNote,
+    IncreaseHeatMapRows,
+    DecreaseHeatMapRows,
+    EditSelectedActivityTags,
+    EditSelectedActivityHeatMax,
+    EditSelectedActivitySchedule,
+    CycleSelectedActivityColor,
+    CycleTagFilter,
+    TogglePinnedActivity,
+    ToggleWeekNumbers,
+    IncrementSelectedActivity,
+    DecrementSelectedActivity,
+    CycleBarChart,
+    SearchActivities,
+    ToggleAllActivitiesHeatMap,
+    CycleSortMode,
+    ToggleReminders,
+    OpenHelp,
+    ToggleCompactSelector,
+    CycleHeatMapWindow,
+    ChooseHeatMapRange,
+    ToggleMarking,
+    ToggleMarkedActivities,
+    ToggleIncompleteFirst,
 }
 
 impl DailaEvent {
     fn from_event(event: &Event) -> Option<Self> {
         match event {
+            Event::Key(key_event)
+                if key_event.modifiers.contains(KeyModifiers::CONTROL)
+                    && key_event.code == KeyCode::Char('p') =>
+            {
+                Some(OpenSearch)
+            }
             Event::Key(key_event) => Self::from_keycode(key_event.code),
             _ => None,
         }
@@ -57,47 +174,213 @@ impl DailaEvent {
         match code {
             KeyCode::Char('d') => Some(GotoNextDay),
             KeyCode::Char('a') => Some(GotoPreviousDay),
+            KeyCode::Char('<') | KeyCode::PageUp => Some(GotoPreviousMonth),
+            KeyCode::Char('>') | KeyCode::PageDown => Some(GotoNextMonth),
             KeyCode::Char('t') => Some(GotoToday),
             KeyCode::Right => Some(ActivityRight),
             KeyCode::Left => Some(ActivityLeft),
             KeyCode::Up => Some(ActivityUp),
             KeyCode::Down => Some(ActivityDown),
+            KeyCode::Tab => Some(SelectNextActivity),
+            KeyCode::BackTab => Some(SelectPreviousActivity),
             KeyCode::Char('s') => Some(SaveAndQuit),
             KeyCode::Char('q') => Some(QuitWithoutSaving),
             KeyCode::Char('c') => Some(CreateNewActivity),
             KeyCode::Char('e') => Some(EditSelectedActivity),
             KeyCode::Char('x') => Some(DeleteSelectedActivity),
+            KeyCode::Char('M') => Some(MergeSelectedActivity),
             KeyCode::Char(' ') => Some(ToggleSelectedActivity),
+            KeyCode::Char('y') => Some(CompleteSelectedActivity),
+            KeyCode::Char('n') => Some(UncompleteSelectedActivity),
+            KeyCode::Char('o') => Some(ToggleOverview),
+            KeyCode::Char('A') => Some(CompleteAllActivities),
+            KeyCode::Char('R') => Some(ClearAllActivities),
+            KeyCode::Char('p') => Some(CopyPreviousDay),
+            KeyCode::Char('m') => Some(ToggleMonthZoom),
+            KeyCode::Char('P') => Some(TogglePinnedActivity),
+            KeyCode::Char('w') => Some(ToggleWeekNumbers),
+            KeyCode::Char('b') => Some(CycleBarChart),
+            KeyCode::Char('/') => Some(SearchActivities),
+            KeyCode::Char('v') => Some(ToggleAllActivitiesHeatMap),
+            KeyCode::Char('O') => Some(CycleSortMode),
+            KeyCode::Char('r') => Some(ToggleReminders),
+            KeyCode::Char('?') => Some(OpenHelp),
+            KeyCode::Char('k') => Some(ToggleCompactSelector),
+            KeyCode::Char('z') => Some(CycleHeatMapWindow),
+            KeyCode::Char('Z') => Some(ChooseHeatMapRange),
+            KeyCode::Char('V') => Some(ToggleMarking),
+            KeyCode::Char('I') => Some(ToggleIncompleteFirst),
+            KeyCode::Enter => Some(ToggleMarkedActivities),
+            KeyCode::Char(']') => Some(IncrementSelectedActivity),
+            KeyCode::Char('[') => Some(DecrementSelectedActivity),
+            KeyCode::Char('l') => Some(GotoLastCompleted),
+            KeyCode::Char('D') => Some(OpenCalendar),
+            KeyCode::Char('g') => Some(GotoDate),
+            KeyCode::Char('L') => Some(LogOnDate),
+            KeyCode::Char('F') => Some(FreezeDays),
+            KeyCode::Char('N') => Some(EditNote),
+            KeyCode::Char('+') | KeyCode::Char('=') => Some(IncreaseHeatMapRows),
+            KeyCode::Char('-') => Some(DecreaseHeatMapRows),
+            KeyCode::Char('T') => Some(EditSelectedActivityTags),
+            KeyCode::Char('H') => Some(EditSelectedActivityHeatMax),
+            KeyCode::Char('S') => Some(EditSelectedActivitySchedule),
+            KeyCode::Char('C') => Some(CycleSelectedActivityColor),
+            KeyCode::Char('f') => Some(CycleTagFilter),
             _ => None,
         }
     }
 
-    fn to_char(&self) -> char {
-        match &self {
+    /**
+     * Whether the event changes the stored activities or activity types.
+     */
+    fn mutates(self) -> bool {
+        matches!(
+            self,
+            ToggleSelectedActivity
+                | CompleteSelectedActivity
+                | UncompleteSelectedActivity
+                | IncrementSelectedActivity
+                | DecrementSelectedActivity
+                | LogOnDate
+                | FreezeDays
+                | EditNote
+                | ToggleMarking
+                | ToggleMarkedActivities
+                | CompleteAllActivities
+                | ClearAllActivities
+                | CopyPreviousDay
+                | CreateNewActivity
+                | EditSelectedActivity
+                | EditSelectedActivityTags
+                | EditSelectedActivityHeatMax
+                | EditSelectedActivitySchedule
+                | CycleSelectedActivityColor
+                | DeleteSelectedActivity
+                | MergeSelectedActivity
+        )
+    }
+
+    fn to_char(self) -> char {
+        match self {
             GotoNextDay => 'd',
             GotoPreviousDay => 'a',
+            GotoPreviousMonth => '<',
+            GotoNextMonth => '>',
             GotoToday => 't',
             ToggleSelectedActivity => ' ',
+            CompleteSelectedActivity => 'y',
+            UncompleteSelectedActivity => 'n',
+            IncrementSelectedActivity => ']',
+            DecrementSelectedActivity => '[',
             SaveAndQuit => 's',
             QuitWithoutSaving => 'q',
             CreateNewActivity => 'c',
             EditSelectedActivity => 'e',
             DeleteSelectedActivity => 'x',
+            MergeSelectedActivity => 'M',
+            ToggleOverview => 'o',
+            CompleteAllActivities => 'A',
+            ClearAllActivities => 'R',
+            CopyPreviousDay => 'p',
+            ToggleMonthZoom => 'm',
+            TogglePinnedActivity => 'P',
+            ToggleWeekNumbers => 'w',
+            CycleBarChart => 'b',
+            SearchActivities => '/',
+            ToggleAllActivitiesHeatMap => 'v',
+            CycleSortMode => 'O',
+            ToggleReminders => 'r',
+            OpenHelp => '?',
+            ToggleCompactSelector => 'k',
+            CycleHeatMapWindow => 'z',
+            ChooseHeatMapRange => 'Z',
+            ToggleMarking => 'V',
+            ToggleMarkedActivities => '⏎',
+            ToggleIncompleteFirst => 'I',
+            GotoLastCompleted => 'l',
+            OpenCalendar => 'D',
+            GotoDate => 'g',
+            LogOnDate => 'L',
+            FreezeDays => 'F',
+            EditNote => 'N',
+            IncreaseHeatMapRows => '+',
+            DecreaseHeatMapRows => '-',
+            EditSelectedActivityTags => 'T',
+            EditSelectedActivityHeatMax => 'H',
+            EditSelectedActivitySchedule => 'S',
+            CycleSelectedActivityColor => 'C',
+            CycleTagFilter => 'f',
             _ => '_',
         }
     }
 
-    fn to_description(&self) -> String {
-        let description = match &self {
+    /**
+     * The key of the event, as listed in the help popup.
+     */
+    fn to_key(self) -> String {
+        match self {
+            OpenSearch => String::from("ctrl+p"),
+            SelectNextActivity => String::from("tab"),
+            SelectPreviousActivity => String::from("shift+tab"),
+            _ => self.to_char().to_string(),
+        }
+    }
+
+    fn to_description(self) -> String {
+        let description = match self {
             GotoNextDay => "next day",
             GotoPreviousDay => "previous day",
+            GotoPreviousMonth => "previous month",
+            GotoNextMonth => "next month",
             GotoToday => "today",
             ToggleSelectedActivity => "toggle selected activity",
+            CompleteSelectedActivity => "mark the selected activity done",
+            UncompleteSelectedActivity => "mark the selected activity not done",
+            IncrementSelectedActivity => "complete the selected activity once more",
+            DecrementSelectedActivity => "complete the selected activity once less",
             SaveAndQuit => "save and quit",
             QuitWithoutSaving => "quit without saving",
             CreateNewActivity => "add new activity type",
             EditSelectedActivity => "edit the selected activity type",
             DeleteSelectedActivity => "delete the selected activity type",
+            MergeSelectedActivity => "merge the selected activity type into another",
+            ToggleOverview => "toggle overview of all activities",
+            CompleteAllActivities => "mark all activities done",
+            ClearAllActivities => "mark all activities not done",
+            CopyPreviousDay => "copy completed activities from the previous day",
+            ToggleMonthZoom => "toggle between the year and month view",
+            TogglePinnedActivity => "pin the selected activity to compare against",
+            ToggleWeekNumbers => "toggle week numbers below the heatmap",
+            CycleBarChart => "cycle the bar chart between week, month, weekdays, and hidden",
+            SearchActivities => "search activities by name (enter: keep, esc: clear)",
+            ToggleAllActivitiesHeatMap => "toggle the heatmap of all activities combined",
+            CycleSortMode => "cycle the order of the activities",
+            ToggleReminders => "toggle reminders for routine activities not done today",
+            OpenHelp => "show every key",
+            ToggleCompactSelector => "toggle one line per activity in the selector",
+            CycleHeatMapWindow => "cycle the heatmap between the selected year and the last N days",
+            ChooseHeatMapRange => "choose the heatmap's dates, e.g. all time or a custom range",
+            ToggleMarking => "mark activities (space) to toggle, delete, tag, or complete together",
+            ToggleMarkedActivities => "toggle the marked activities",
+            ToggleIncompleteFirst => "toggle listing activities not done first",
+            GotoLastCompleted => "go to when the selected activity was last done",
+            OpenCalendar => "pick a day from a calendar of its month",
+            GotoDate => "go to a typed date",
+            OpenSearch => "search activities and recent dates",
+            SelectNextActivity => "select the next activity, across rows",
+            SelectPreviousActivity => "select the previous activity, across rows",
+            LogOnDate => "complete an activity on a typed date",
+            FreezeDays => "freeze days (e.g. a vacation) so that they don't break streaks",
+            EditNote => "write a note about the active date (ctrl+s: save)",
+            IncreaseHeatMapRows => "add a row to the heatmap",
+            DecreaseHeatMapRows => "remove a row from the heatmap",
+            EditSelectedActivityTags => "edit the tags of the selected activity type",
+            EditSelectedActivityHeatMax => "edit the heat of the hottest color",
+            EditSelectedActivitySchedule => {
+                "edit the days of the week the selected activity is done on"
+            }
+            CycleSelectedActivityColor => "cycle the color of the selected activity type",
+            CycleTagFilter => "cycle the tag activities are filtered by",
             _ => "unknown",
         };
 
@@ -108,12 +391,97 @@ impl DailaEvent {
 pub enum DailaState {
     Default,
     ActivityPopup {
-        state: ActivityPopupState,
+        state: Box<ActivityPopupState>,
     },
     ConfirmationPopup {
         action: ConfirmationAction,
         state: ConfirmationPopupState,
     },
+    // Asks for the value of an activity before it is completed.
+    NumberPromptPopup {
+        activity: Activity,
+        state: NumberPromptPopupState,
+    },
+    CalendarPopup {
+        state: CalendarPopupState,
+    },
+    GotoDatePopup {
+        state: GotoDatePopupState,
+    },
+    LogOnDatePopup {
+        state: LogOnDatePopupState,
+    },
+    FreezePopup {
+        state: FreezePopupState,
+    },
+    // Edits the note about a date.
+    NoteEditorPopup {
+        date: NaiveDate,
+        state: NoteEditorPopupState,
+    },
+    // Picks the activity type the given one is merged into.
+    MergeActivityPopup {
+        activity_id: ActivityId,
+        state: ActivityPickerPopupState,
+    },
+    HelpPopup {
+        state: HelpPopupState,
+    },
+    HeatMapRangePopup {
+        state: HeatMapRangePopupState,
+    },
+    SearchPopup {
+        state: SearchPopupState,
+    },
+    Overview {
+        // Index of the first activity type displayed.
+        scroll: usize,
+    },
+    // Typing into the selector's name filter.
+    Searching,
+}
+
+/**
+ * The bar chart displayed below the selector.
+ */
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Chart {
+    // Completions on each day of the week or month of the active date.
+    Daily(BarChartPeriod),
+    // Share of each day of the week the selected activity was done on, over
+    // the heatmap's dates.
+    Weekdays,
+}
+
+/**
+ * The activities whose completions are displayed in a heatmap.
+ */
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum HeatMapSource {
+    Empty,
+    Activity(ActivityId),
+    // Total completions of every activity.
+    AllActivities,
+}
+
+impl From<Option<ActivityId>> for HeatMapSource {
+    fn from(activity_id: Option<ActivityId>) -> Self {
+        match activity_id {
+            Some(activity_id) => HeatMapSource::Activity(activity_id),
+            None => HeatMapSource::Empty,
+        }
+    }
+}
+
+/**
+ * Heat of each day in a heatmap's date range for a source.
+ */
+struct HeatMapCache {
+    source: HeatMapSource,
+    // Revision of the activities store the heats were computed from.
+    revision: u64,
+    date_range: HeatMapDateRange,
+    heats: Vec<Option<f32>>,
 }
 
 pub struct Daila {
@@ -124,105 +492,589 @@ pub struct Daila {
     activity_selector_state: ActivitySelectorState,
     running: bool,
     state: DailaState,
-    // Refresh the display.
-    refresh: bool,
+    // States below the current one (e.g. an activity popup below a
+    // confirmation), the last of which regains the input when it is closed.
+    popup_stack: Vec<DailaState>,
+    // Transient message displayed at the bottom of the screen, and when it was set.
+    status_message: Option<(String, Instant)>,
+    // Dates displayed in the heatmap, for the rest of the session.
+    heatmap_range: HeatMapRange,
+    // Number of rows (days per column) in the heatmap.
+    heatmap_rows: u16,
+    heatmap_cache: Option<HeatMapCache>,
+    // Heats of each activity in the overview, while it is displayed.
+    overview_cache: Vec<HeatMapCache>,
+    // Activity compared against the selected activity in the heatmap.
+    pinned_activity: Option<ActivityId>,
+    comparison_cache: Option<HeatMapCache>,
+    // Display the month containing the active date instead of the heatmap.
+    month_zoom: bool,
+    // Label the heatmap's columns with ISO week numbers.
+    week_numbers: bool,
+    // Bar chart displayed below the selector, if any.
+    bar_chart: Option<Chart>,
+    // Display the total completions of every activity in the heatmap.
+    all_activities_heatmap: bool,
+    // Mark routine activities that haven't been done today.
+    reminders: bool,
+    // Display the selector compactly, even when there is room not to.
+    compact_selector: bool,
+    // Area the heatmap was last rendered in, if it was rendered.
+    heatmap_area: Option<Rect>,
+    // Hours past midnight that each day ends at.
+    day_rollover_hours: u32,
+    // Ignore events that change activities, and never save.
+    readonly: bool,
+}
+
+impl Default for Daila {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Daila {
     pub fn new() -> Self {
-        let activity_types = ActivityTypesStore::load();
+        let preferences = Preferences::load();
+        let mut daila = Self::with_stores(
+            ActivityTypesStore::load(),
+            ActivitiesStore::load(),
+            preferences.day_rollover_hours,
+        );
+        daila
+            .activity_selector_state
+            .set_incomplete_first(preferences.incomplete_first);
+        // Start with the activity selected last time, if it still exists.
+        if let Some(activity_id) = preferences.last_selected_activity {
+            daila.select_activity(activity_id);
+        }
+        daila
+    }
+
+    fn with_stores(
+        activity_types: ActivityTypesStore,
+        activities: ActivitiesStore,
+        day_rollover_hours: u32,
+    ) -> Self {
         let activity_types_len = activity_types.len();
 
         Self {
-            activity_types: activity_types,
-            activities: ActivitiesStore::load(),
-            active_date: chrono::Local::now().date_naive(),
+            activity_types,
+            activities,
+            active_date: today(day_rollover_hours),
             activity_selector_state: ActivitySelectorState::new(activity_types_len),
             running: false,
             state: DailaState::Default,
-            refresh: false,
+            popup_stack: vec![],
+            status_message: None,
+            heatmap_range: HeatMapRange::SelectedYear,
+            heatmap_rows: 7,
+            heatmap_cache: None,
+            overview_cache: vec![],
+            pinned_activity: None,
+            comparison_cache: None,
+            month_zoom: false,
+            week_numbers: false,
+            bar_chart: None,
+            all_activities_heatmap: false,
+            reminders: true,
+            compact_selector: false,
+            heatmap_area: None,
+            day_rollover_hours,
+            readonly: false,
+        }
+    }
+
+    /**
+     * Only allow viewing activities, e.g. to show them to someone without
+     * risking changes.
+     */
+    pub fn readonly(mut self, readonly: bool) -> Self {
+        self.readonly = readonly;
+        self
+    }
+
+    fn save(&self) {
+        if self.readonly {
+            return;
+        }
+        self.activity_types.save();
+        self.activities.save();
+        Preferences {
+            last_selected_activity: self
+                .selected_activity_option()
+                .map(|option| option.activity_id()),
+            incomplete_first: self.activity_selector_state.incomplete_first(),
+            day_rollover_hours: self.day_rollover_hours,
+        }
+        .save();
+    }
+
+    /**
+     * The current day, which only ends at the rollover hour past midnight.
+     */
+    fn today(&self) -> NaiveDate {
+        today(self.day_rollover_hours)
+    }
+
+    /**
+     * Open a popup over the current one, which regains the input when it is
+     * closed.
+     */
+    fn open_popup(&mut self, state: DailaState) {
+        let below = std::mem::replace(&mut self.state, state);
+        self.popup_stack.push(below);
+    }
+
+    /**
+     * Close the current popup, returning to the one below it, if any.
+     */
+    fn close_popup(&mut self) {
+        self.state = self.popup_stack.pop().unwrap_or(DailaState::Default);
+    }
+
+    fn set_status_message(&mut self, message: &str) {
+        self.status_message = Some((String::from(message), Instant::now()));
+    }
+
+    fn clear_expired_status_message(&mut self) {
+        if let Some((_, set_at)) = self.status_message {
+            if set_at.elapsed() >= STATUS_MESSAGE_DURATION {
+                self.status_message = None;
+            }
+        }
+    }
+
+    /**
+     * Close a confirmation popup left open past its timeout, as if it were
+     * declined.
+     */
+    fn decline_expired_confirmation(&mut self) {
+        if let DailaState::ConfirmationPopup { ref state, .. } = self.state {
+            if state.expired() {
+                self.close_popup();
+                self.set_status_message("Cancelled, no answer was given");
+            }
         }
     }
 
-    pub fn instructions_block(&self) -> Paragraph {
-        let instructions = vec![
-            DailaEvent::GotoPreviousDay,
-            DailaEvent::GotoNextDay,
-            DailaEvent::GotoToday,
-            DailaEvent::ToggleSelectedActivity,
-            DailaEvent::CreateNewActivity,
-            DailaEvent::EditSelectedActivity,
-            DailaEvent::DeleteSelectedActivity,
-            DailaEvent::SaveAndQuit,
-            DailaEvent::QuitWithoutSaving,
+    fn status_message_block(&self) -> Option<Paragraph<'_>> {
+        let (message, _) = self.status_message.as_ref()?;
+        Some(
+            Paragraph::new(Text::raw(format!(" {} ", message)))
+                .style(Style::default().fg(Color::Black).bg(Color::Yellow)),
+        )
+    }
+
+    /**
+     * One line summarizing everything recorded so far.
+     */
+    fn tracking_footer(&self) -> Paragraph<'_> {
+        let text = match self.activities.first_tracked_date() {
+            Some(date) => format!(
+                " Tracking since {} · {} days · {} completions",
+                date.format("%Y-%m-%d"),
+                format_count(self.activities.days_tracked()),
+                format_count(self.activities.completion_count())
+            ),
+            None => String::from(" No activity recorded yet."),
+        };
+        let text = match self.day_rollover_hours {
+            0 => text,
+            hours => format!("{} · days end at {:02}:00", text, hours),
+        };
+        Paragraph::new(Text::raw(text)).style(Style::default().fg(Color::Gray))
+    }
+
+    /**
+     * Every key, grouped by what it acts on, for the help popup. Keys of
+     * events that change activities are left out when read-only.
+     */
+    fn help_sections(&self) -> Vec<HelpSection> {
+        let sections = [
+            (
+                "Navigation",
+                vec![
+                    DailaEvent::GotoPreviousDay,
+                    DailaEvent::GotoNextDay,
+                    DailaEvent::GotoPreviousMonth,
+                    DailaEvent::GotoNextMonth,
+                    DailaEvent::GotoToday,
+                    DailaEvent::GotoLastCompleted,
+                    DailaEvent::OpenCalendar,
+                    DailaEvent::GotoDate,
+                    DailaEvent::OpenSearch,
+                ],
+            ),
+            (
+                "Activities",
+                vec![
+                    DailaEvent::SelectNextActivity,
+                    DailaEvent::SelectPreviousActivity,
+                    DailaEvent::ToggleSelectedActivity,
+                    DailaEvent::CompleteSelectedActivity,
+                    DailaEvent::UncompleteSelectedActivity,
+                    DailaEvent::IncrementSelectedActivity,
+                    DailaEvent::DecrementSelectedActivity,
+                    DailaEvent::LogOnDate,
+                    DailaEvent::FreezeDays,
+                    DailaEvent::EditNote,
+                    DailaEvent::ToggleMarking,
+                    DailaEvent::ToggleMarkedActivities,
+                    DailaEvent::CreateNewActivity,
+                    DailaEvent::EditSelectedActivity,
+                    DailaEvent::DeleteSelectedActivity,
+                    DailaEvent::MergeSelectedActivity,
+                    DailaEvent::EditSelectedActivityTags,
+                    DailaEvent::EditSelectedActivityHeatMax,
+                    DailaEvent::EditSelectedActivitySchedule,
+                    DailaEvent::CycleSelectedActivityColor,
+                    DailaEvent::CycleTagFilter,
+                    DailaEvent::SearchActivities,
+                    DailaEvent::CycleSortMode,
+                    DailaEvent::ToggleIncompleteFirst,
+                    DailaEvent::ToggleCompactSelector,
+                    DailaEvent::ToggleReminders,
+                    DailaEvent::CompleteAllActivities,
+                    DailaEvent::ClearAllActivities,
+                    DailaEvent::CopyPreviousDay,
+                ],
+            ),
+            (
+                "Heatmap",
+                vec![
+                    DailaEvent::ToggleMonthZoom,
+                    DailaEvent::TogglePinnedActivity,
+                    DailaEvent::ToggleAllActivitiesHeatMap,
+                    DailaEvent::ToggleWeekNumbers,
+                    DailaEvent::CycleBarChart,
+                    DailaEvent::CycleHeatMapWindow,
+                    DailaEvent::ChooseHeatMapRange,
+                    DailaEvent::IncreaseHeatMapRows,
+                    DailaEvent::DecreaseHeatMapRows,
+                    DailaEvent::ToggleOverview,
+                ],
+            ),
+            (
+                "General",
+                vec![
+                    DailaEvent::OpenHelp,
+                    DailaEvent::SaveAndQuit,
+                    DailaEvent::QuitWithoutSaving,
+                ],
+            ),
         ];
-        let strings: Vec<String> = instructions
+        sections
             .into_iter()
-            .map(|event| format!("{}: {}", event.to_char(), event.to_description()))
-            .collect();
-        let string = strings.join("\n");
+            .map(|(title, events)| HelpSection {
+                title: String::from(title),
+                lines: events
+                    .into_iter()
+                    .filter(|event| {
+                        !self.readonly || !(event.mutates() || matches!(event, SaveAndQuit))
+                    })
+                    .map(|event| format!("{}: {}", event.to_key(), event.to_description()))
+                    .collect(),
+            })
+            .chain([HelpSection {
+                title: String::from("Popups"),
+                lines: POPUP_INSTRUCTIONS.map(String::from).to_vec(),
+            }])
+            .collect()
+    }
 
-        Paragraph::new(Text::raw(string.to_owned())).block(Block::default().borders(Borders::ALL))
+    /**
+     * Single line pointing to the help popup, in place of listing every key.
+     */
+    fn help_hint(&self) -> Paragraph<'_> {
+        let text = if self.readonly {
+            " ? for help · q quit"
+        } else {
+            " ? for help · s save & quit"
+        };
+        Paragraph::new(Text::raw(text)).style(Style::default().fg(Color::Gray))
     }
 
     fn parse_input_event(&self, event: &Event) -> Option<DailaEvent> {
         DailaEvent::from_event(event)
     }
 
-    fn handle_event(&mut self, event: Result<Event, io::Error>) -> Option<()> {
-        let event = event.unwrap();
+    fn handle_event(&mut self, event: Event) {
+        // The frame is laid out for the size of the terminal each time it is
+        // drawn, so returning to the loop is enough to reflow it (and check
+        // whether the terminal is still too small).
+        if let Event::Resize(..) = event {
+            return;
+        }
+        let selected_index = self.activity_selector_state.selected_index();
+        let selected_activity = self
+            .selected_activity_option()
+            .map(|option| option.activity_id());
+
+        self.handle_state_event(event);
+
+        // Activities can be reordered by the event (e.g. completing one when
+        // sorted by most recent), so unless the selection was moved, follow
+        // the selected activity to its new position.
+        if self.activity_selector_state.selected_index() == selected_index {
+            if let Some(activity_id) = selected_activity {
+                self.select_activity(activity_id);
+            }
+        }
+    }
+
+    fn handle_state_event(&mut self, event: Event) -> Option<()> {
         match self.state {
             DailaState::Default => {
+                if let Event::Mouse(mouse_event) = event {
+                    self.handle_mouse_event(mouse_event);
+                    return Some(());
+                }
                 let daila_event = self.parse_input_event(&event)?;
+                if self.readonly && daila_event.mutates() {
+                    self.set_status_message("Read-only, activities can't be changed");
+                    return Some(());
+                }
                 match daila_event {
+                    // Nothing can have changed, so there is nothing to save.
+                    QuitWithoutSaving | SaveAndQuit if self.readonly => {
+                        self.running = false;
+                    }
                     QuitWithoutSaving => {
-                        self.refresh = true;
                         self.state = DailaState::ConfirmationPopup {
                             action: ConfirmationAction::SaveWithoutQuitting,
                             state: ConfirmationPopupState::new(String::from(
                                 "Quit without saving?",
-                            )),
+                            ))
+                            .timeout(QUIT_CONFIRMATION_TIMEOUT),
                         }
                     }
                     SaveAndQuit => {
                         self.running = false;
                         // Save any unsaved changes.
-                        self.activity_types.save();
-                        self.activities.save();
+                        self.save();
                     }
-                    DailaEvent::ToggleSelectedActivity => {
-                        // Toggle the activity.
+                    DailaEvent::ToggleSelectedActivity
+                        if self.activity_selector_state.marking() =>
+                    {
                         if let Some(activity_option) = self.selected_activity_option() {
-                            let activity = Activity::new(
-                                activity_option.activity_id(),
-                                self.active_date.clone(),
-                            );
-                            if activity_option.completed() {
+                            self.activity_selector_state
+                                .toggle_marked(activity_option.activity_id());
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    DailaEvent::ToggleSelectedActivity => self.toggle_selected_activity(),
+                    ToggleMarking => {
+                        let marking = !self.activity_selector_state.marking();
+                        self.activity_selector_state.set_marking(marking);
+                        self.set_status_message(if marking {
+                            "Marking activities, press space to mark"
+                        } else {
+                            "Stopped marking activities"
+                        });
+                    }
+                    ToggleMarkedActivities => {
+                        let marked = self.activity_selector_state.marked();
+                        if marked.is_empty() {
+                            self.set_status_message("No activities marked");
+                        }
+                        for activity_id in marked {
+                            let activity = Activity::new(activity_id, self.active_date);
+                            if self
+                                .activities
+                                .activity_count(self.active_date, activity_id)
+                                > 0
+                            {
                                 self.activities.remove_activity(activity);
                             } else {
-                                self.activities.add_activity(activity);
+                                self.activities.add_activity(
+                                    self.completed_activity(activity_id, self.active_date),
+                                );
+                            }
+                        }
+                    }
+                    CompleteSelectedActivity => {
+                        if let Some(activity_option) = self.selected_activity_option() {
+                            // Already done activities keep their count.
+                            if !activity_option.completed() {
+                                self.activities.add_activity(self.completed_activity(
+                                    activity_option.activity_id(),
+                                    self.active_date,
+                                ));
+                            }
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    UncompleteSelectedActivity => {
+                        if let Some(activity_option) = self.selected_activity_option() {
+                            // Every completion on the date is removed.
+                            self.activities.remove_activity(Activity::new(
+                                activity_option.activity_id(),
+                                self.active_date,
+                            ));
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    IncrementSelectedActivity => {
+                        if let Some(activity_option) = self.selected_activity_option() {
+                            self.activities.add_activity(self.completed_activity(
+                                activity_option.activity_id(),
+                                self.active_date,
+                            ));
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    DecrementSelectedActivity => {
+                        if let Some(activity_option) = self.selected_activity_option() {
+                            let activity =
+                                Activity::new(activity_option.activity_id(), self.active_date);
+                            if !self.activities.remove_one_activity(activity) {
+                                self.set_status_message("Activity is not done");
+                            }
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    CompleteAllActivities => {
+                        let changed = self.set_all_activities_completed(true);
+                        self.set_status_message(&format!("Marked {} activities done", changed));
+                    }
+                    ClearAllActivities => {
+                        let changed = self.set_all_activities_completed(false);
+                        self.set_status_message(&format!("Marked {} activities not done", changed));
+                    }
+                    CopyPreviousDay => {
+                        let to_copy = self.activities_to_copy_from_previous_day();
+                        if to_copy.is_empty() {
+                            self.set_status_message("Nothing to copy from the previous day");
+                        } else {
+                            self.state = DailaState::ConfirmationPopup {
+                                action: ConfirmationAction::CopyPreviousDay,
+                                state: ConfirmationPopupState::new(format!(
+                                    "Copy {} activities from the previous day?",
+                                    to_copy.len()
+                                )),
                             }
                         }
                     }
                     CreateNewActivity => {
-                        self.refresh = true;
                         self.state = DailaState::ActivityPopup {
-                            state: ActivityPopupState::new_creator(),
+                            state: Box::new(ActivityPopupState::new_creator()),
                         };
                     }
                     EditSelectedActivity => {
-                        self.refresh = true;
                         if let Some(activity_option) = self.selected_activity_option() {
                             self.state = DailaState::ActivityPopup {
-                                state: ActivityPopupState::new_editor(
-                                    activity_option.name().to_owned(),
+                                state: Box::new(ActivityPopupState::new_editor(
+                                    activity_option.activity_type(),
+                                )),
+                            };
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    EditSelectedActivityTags
+                        if !self.activity_selector_state.marked().is_empty() =>
+                    {
+                        self.state = DailaState::ActivityPopup {
+                            state: Box::new(ActivityPopupState::new_tags_adder(
+                                self.activity_selector_state.marked(),
+                            )),
+                        };
+                    }
+                    EditSelectedActivityTags => {
+                        if let Some(activity_option) = self.selected_activity_option() {
+                            self.state = DailaState::ActivityPopup {
+                                state: Box::new(ActivityPopupState::new_tags_editor(
+                                    activity_option.tags(),
                                     activity_option.activity_id(),
-                                ),
+                                )),
+                            };
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    EditSelectedActivitySchedule => {
+                        if let Some(activity_option) = self.selected_activity_option() {
+                            self.state = DailaState::ActivityPopup {
+                                state: Box::new(ActivityPopupState::new_schedule_editor(
+                                    activity_option.schedule(),
+                                    activity_option.activity_id(),
+                                )),
+                            };
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    EditSelectedActivityHeatMax => {
+                        if let Some(activity_option) = self.selected_activity_option() {
+                            self.state = DailaState::ActivityPopup {
+                                state: Box::new(ActivityPopupState::new_heat_max_editor(
+                                    activity_option.heat_max(),
+                                    activity_option.activity_id(),
+                                )),
                             };
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    CycleSelectedActivityColor => {
+                        if let Some(activity_option) = self.selected_activity_option() {
+                            let color = ActivityColor::next(activity_option.activity_color());
+                            self.activity_types
+                                .update_color(activity_option.activity_id(), color);
+                            self.set_status_message(&format!(
+                                "{} is {}",
+                                activity_option.name(),
+                                color.map_or("uncolored", ActivityColor::label)
+                            ));
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    CycleTagFilter => {
+                        let tags = self.activity_types.tags();
+                        let next_tag = match self.activity_selector_state.tag_filter() {
+                            None => tags.first().cloned(),
+                            Some(tag) => tags
+                                .iter()
+                                .skip_while(|t| t.as_str() != tag)
+                                .nth(1)
+                                .cloned(),
+                        };
+                        match &next_tag {
+                            Some(tag) => self.set_status_message(&format!("Showing #{}", tag)),
+                            None if tags.is_empty() => {
+                                self.set_status_message("No tags to filter by")
+                            }
+                            None => self.set_status_message("Showing all activities"),
+                        }
+                        self.update_selector_options(|state| state.set_tag_filter(next_tag));
+                    }
+                    DeleteSelectedActivity if !self.activity_selector_state.marked().is_empty() => {
+                        let marked = self.marked_activity_types();
+                        let names: Vec<&str> = marked
+                            .iter()
+                            .map(|activity_type| activity_type.name.as_str())
+                            .collect();
+                        self.state = DailaState::ConfirmationPopup {
+                            action: ConfirmationAction::DeleteActivities(
+                                marked
+                                    .iter()
+                                    .map(|activity_type| activity_type.id)
+                                    .collect(),
+                            ),
+                            state: ConfirmationPopupState::new(format!(
+                                "Confirm deletion of: {}",
+                                names.join(", ")
+                            )),
                         }
                     }
                     DeleteSelectedActivity => {
-                        self.refresh = true;
                         if let Some(activity_option) = self.selected_activity_option() {
                             self.state = DailaState::ConfirmationPopup {
                                 action: ConfirmationAction::DeleteActivity(
@@ -231,36 +1083,436 @@ impl Daila {
                                 state: ConfirmationPopupState::new(format!(
                                     "Confirm deletion of: {}",
                                     activity_option.name()
+                                ))
+                                .details(self.deletion_details(activity_option.activity_id())),
+                            }
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    MergeSelectedActivity => {
+                        let Some(activity_option) = self.selected_activity_option() else {
+                            self.set_status_message("No activity selected");
+                            return Some(());
+                        };
+                        let activity_id = activity_option.activity_id();
+                        let mut activity_types = self.activity_types.activity_types();
+                        activity_types
+                            .sort_by_key(|activity_type| activity_type.name.to_lowercase());
+                        let activities: Vec<(ActivityId, String)> = activity_types
+                            .into_iter()
+                            .filter(|activity_type| activity_type.id != activity_id)
+                            .map(|activity_type| (activity_type.id, activity_type.name.clone()))
+                            .collect();
+                        if activities.is_empty() {
+                            self.set_status_message("No other activity to merge into");
+                            return Some(());
+                        }
+                        self.state = DailaState::MergeActivityPopup {
+                            activity_id,
+                            state: ActivityPickerPopupState::new(
+                                &format!("Merge {} into", activity_option.name()),
+                                activities,
+                            ),
+                        };
+                    }
+                    GotoPreviousDay => match self.active_date.pred_opt() {
+                        Some(date) => self.active_date = date,
+                        None => self.set_status_message("No earlier days"),
+                    },
+                    GotoNextDay => match self.active_date.succ_opt() {
+                        Some(date) => self.active_date = date,
+                        None => self.set_status_message("No later days"),
+                    },
+                    // Days past the end of the month are clamped to its last day
+                    // (e.g. 31 January goes to 28 February).
+                    GotoPreviousMonth => {
+                        match self.active_date.checked_sub_months(Months::new(1)) {
+                            Some(date) => self.active_date = date,
+                            None => self.set_status_message("No earlier months"),
+                        }
+                    }
+                    GotoNextMonth => match self.active_date.checked_add_months(Months::new(1)) {
+                        Some(date) => self.active_date = date,
+                        None => self.set_status_message("No later months"),
+                    },
+                    GotoToday => self.active_date = self.today(),
+                    GotoLastCompleted => match self.selected_activity_option() {
+                        Some(activity_option) => {
+                            let today = self.today();
+                            match self
+                                .activities
+                                .last_completed_on_or_before(activity_option.activity_id(), today)
+                            {
+                                Some(date) => {
+                                    self.active_date = date;
+                                    self.set_status_message(&format!(
+                                        "{} last done on {}",
+                                        activity_option.name(),
+                                        date.format("%-d %B, %C%y")
+                                    ));
+                                }
+                                None => self.set_status_message(&format!(
+                                    "{} last done: never",
+                                    activity_option.name()
                                 )),
                             }
                         }
+                        None => self.set_status_message("No activity selected"),
+                    },
+                    OpenCalendar => {
+                        let (completed, color) = match self.selected_activity_option() {
+                            Some(activity_option) => (
+                                self.activities
+                                    .activities_with_type(activity_option.activity_type())
+                                    .into_iter()
+                                    .map(|activity| activity.heat_map_date())
+                                    .collect(),
+                                activity_option.color().unwrap_or(Color::Green),
+                            ),
+                            None => (BTreeSet::new(), Color::Green),
+                        };
+                        self.state = DailaState::CalendarPopup {
+                            state: CalendarPopupState::new(self.active_date, completed)
+                                .color(color),
+                        };
+                    }
+                    GotoDate => {
+                        self.state = DailaState::GotoDatePopup {
+                            state: GotoDatePopupState::new(self.today()),
+                        };
+                    }
+                    LogOnDate => {
+                        let mut activity_types = self.activity_types.activity_types();
+                        activity_types
+                            .sort_by_key(|activity_type| activity_type.name.to_lowercase());
+                        let activities = activity_types
+                            .into_iter()
+                            .map(|activity_type| (activity_type.id, activity_type.name.clone()))
+                            .collect();
+                        let mut state =
+                            LogOnDatePopupState::new(self.active_date, self.today(), activities);
+                        if let Some(activity_option) = self.selected_activity_option() {
+                            state = state.select(activity_option.activity_id());
+                        }
+                        self.state = DailaState::LogOnDatePopup { state };
+                    }
+                    FreezeDays => {
+                        self.state = DailaState::FreezePopup {
+                            state: FreezePopupState::new(self.active_date, self.today()),
+                        };
+                    }
+                    EditNote => {
+                        let title = format!("Note on {}", self.active_date.format("%-d %b %Y"));
+                        let note = self.activities.note(self.active_date).unwrap_or_default();
+                        self.state = DailaState::NoteEditorPopup {
+                            date: self.active_date,
+                            state: NoteEditorPopupState::new(&title, note),
+                        };
+                    }
+                    OpenSearch => {
+                        self.state = DailaState::SearchPopup {
+                            state: SearchPopupState::new(self.search_candidates()),
+                        };
+                    }
+                    IncreaseHeatMapRows => {
+                        if self.heatmap_rows < MAX_HEATMAP_ROWS {
+                            self.heatmap_rows += 1;
+                        } else {
+                            self.set_status_message("Heatmap already has the most rows");
+                        }
+                    }
+                    DecreaseHeatMapRows => {
+                        if self.heatmap_rows > MIN_HEATMAP_ROWS {
+                            self.heatmap_rows -= 1;
+                        } else {
+                            self.set_status_message("Heatmap already has the fewest rows");
+                        }
+                    }
+                    ToggleMonthZoom => {
+                        self.month_zoom = !self.month_zoom;
+                    }
+                    ToggleAllActivitiesHeatMap => {
+                        self.all_activities_heatmap = !self.all_activities_heatmap;
+                        self.set_status_message(if self.all_activities_heatmap {
+                            "Heatmap of all activities"
+                        } else {
+                            "Heatmap of the selected activity"
+                        });
+                    }
+                    ToggleIncompleteFirst => {
+                        let incomplete_first = !self.activity_selector_state.incomplete_first();
+                        self.update_selector_options(|state| {
+                            state.set_incomplete_first(incomplete_first)
+                        });
+                    }
+                    CycleSortMode => {
+                        let sort_mode = self.activity_selector_state.sort_mode().next();
+                        self.update_selector_options(|state| state.set_sort_mode(sort_mode));
+                        self.set_status_message(&format!("Sorted {}", sort_mode.label()));
+                    }
+                    CycleHeatMapWindow => {
+                        self.heatmap_range = match self.heatmap_range {
+                            HeatMapRange::Days(days) => HEATMAP_WINDOWS
+                                .into_iter()
+                                .find(|&d| d > days)
+                                .map_or(HeatMapRange::SelectedYear, HeatMapRange::Days),
+                            HeatMapRange::SelectedYear => HeatMapRange::Days(HEATMAP_WINDOWS[0]),
+                            // Back to the start of the cycle.
+                            HeatMapRange::AllTime | HeatMapRange::Custom(..) => {
+                                HeatMapRange::SelectedYear
+                            }
+                        };
+                        self.set_status_message(&match self.heatmap_range {
+                            HeatMapRange::Days(days) => {
+                                format!("Heatmap of the last {} days", days)
+                            }
+                            _ => String::from("Heatmap of the selected day's year"),
+                        });
+                    }
+                    ChooseHeatMapRange => {
+                        self.state = DailaState::HeatMapRangePopup {
+                            state: HeatMapRangePopupState::new(
+                                self.heatmap_range,
+                                self.heatmap_date_range(),
+                                self.today(),
+                            ),
+                        };
+                    }
+                    ToggleCompactSelector => {
+                        self.compact_selector = !self.compact_selector;
+                    }
+                    OpenHelp => {
+                        self.state = DailaState::HelpPopup {
+                            state: HelpPopupState::new(self.help_sections()),
+                        };
+                    }
+                    ToggleReminders => {
+                        self.reminders = !self.reminders;
+                        self.set_status_message(if self.reminders {
+                            "Reminders on"
+                        } else {
+                            "Reminders off"
+                        });
+                    }
+                    SearchActivities => {
+                        self.state = DailaState::Searching;
+                    }
+                    CycleBarChart => {
+                        self.bar_chart = match self.bar_chart {
+                            None => Some(Chart::Daily(BarChartPeriod::Week)),
+                            Some(Chart::Daily(BarChartPeriod::Week)) => {
+                                Some(Chart::Daily(BarChartPeriod::Month))
+                            }
+                            Some(Chart::Daily(BarChartPeriod::Month)) => Some(Chart::Weekdays),
+                            Some(Chart::Weekdays) => None,
+                        };
+                    }
+                    ToggleWeekNumbers => {
+                        self.week_numbers = !self.week_numbers;
+                    }
+                    TogglePinnedActivity => {
+                        if self.pinned_activity.take().is_some() {
+                            self.set_status_message("Unpinned");
+                        } else if let Some(activity_option) = self.selected_activity_option() {
+                            self.pinned_activity = Some(activity_option.activity_id());
+                            self.set_status_message(&format!(
+                                "Pinned {}, select another activity to compare",
+                                activity_option.name()
+                            ));
+                        } else {
+                            self.set_status_message("No activity selected");
+                        }
+                    }
+                    ToggleOverview => {
+                        self.state = DailaState::Overview { scroll: 0 };
                     }
-                    GotoPreviousDay => self.active_date = self.active_date.pred_opt().unwrap(),
-                    GotoNextDay => self.active_date = self.active_date.succ_opt().unwrap(),
-                    GotoToday => self.active_date = chrono::Local::now().date_naive(),
                     ActivityLeft => self.activity_selector_state.select_left(),
                     ActivityRight => self.activity_selector_state.select_right(),
+                    SelectNextActivity => self.activity_selector_state.select_next(),
+                    SelectPreviousActivity => self.activity_selector_state.select_previous(),
                     ActivityUp => self.activity_selector_state.select_up(),
                     ActivityDown => self.activity_selector_state.select_down(),
                 }
             }
             DailaState::ActivityPopup { ref mut state } => {
                 let action = ActivityPopup::handle_event(&event, state)?;
-                self.refresh = true;
                 match action {
+                    ActivityPopupAction::Exit if state.modified() => {
+                        self.open_popup(DailaState::ConfirmationPopup {
+                            action: ConfirmationAction::DiscardActivityChanges,
+                            state: ConfirmationPopupState::new(String::from(
+                                "Discard unsaved changes?",
+                            )),
+                        });
+                    }
                     ActivityPopupAction::Exit => {
-                        self.state = DailaState::Default;
+                        self.close_popup();
+                    }
+                    ActivityPopupAction::Save(draft) => {
+                        self.close_popup();
+                        self.save_activity_draft(draft);
+                        self.update_activity_count();
+                    }
+                    ActivityPopupAction::SaveCompleted(draft) => {
+                        self.close_popup();
+                        let id = self.save_activity_draft(draft);
+                        self.activities
+                            .add_activity(self.completed_activity(id, self.active_date));
+                        self.update_activity_count();
+                    }
+                    ActivityPopupAction::EditTags(id, tags) => {
+                        self.close_popup();
+                        self.activity_types.update_tags(id, tags);
+                        self.update_activity_count();
+                    }
+                    ActivityPopupAction::AddTags(ids, tags) => {
+                        self.close_popup();
+                        for id in ids {
+                            let Some(activity_type) = self.activity_types.activity_type(id) else {
+                                continue;
+                            };
+                            let mut activity_tags = activity_type.tags.clone();
+                            activity_tags.extend(tags.iter().cloned());
+                            self.activity_types.update_tags(id, activity_tags);
+                        }
+                        self.update_activity_count();
+                    }
+                    ActivityPopupAction::EditHeatMax(id, heat_max) => {
+                        self.close_popup();
+                        self.activity_types.update_heat_max(id, heat_max);
+                    }
+                    ActivityPopupAction::EditSchedule(id, schedule) => {
+                        self.close_popup();
+                        self.activity_types.update_schedule(id, schedule);
+                    }
+                }
+            }
+            DailaState::CalendarPopup { ref mut state } => {
+                let action = CalendarPopup::handle_event(&event, state)?;
+                if let CalendarPopupAction::Select(date) = action {
+                    self.active_date = date;
+                }
+                self.close_popup();
+            }
+            DailaState::SearchPopup { ref mut state } => {
+                let action = SearchPopup::handle_event(&event, state)?;
+                self.close_popup();
+                match action {
+                    SearchPopupAction::SelectActivity(activity_id) => {
+                        self.reveal_activity(activity_id)
                     }
-                    ActivityPopupAction::CreateActivity(title) => {
-                        self.state = DailaState::Default;
-                        self.activity_types.create_new_activity(title);
-                        self.activity_selector_state =
-                            ActivitySelectorState::new(self.activity_types.activity_types().len());
+                    SearchPopupAction::GotoDate(date) => self.active_date = date,
+                    SearchPopupAction::Exit => (),
+                }
+            }
+            DailaState::HelpPopup { ref mut state } => {
+                let HelpPopupAction::Exit = HelpPopup::handle_event(&event, state)?;
+                self.close_popup();
+            }
+            DailaState::GotoDatePopup { ref mut state } => {
+                let action = GotoDatePopup::handle_event(&event, state)?;
+                if let GotoDatePopupAction::Goto(date) = action {
+                    self.active_date = date;
+                }
+                self.close_popup();
+            }
+            DailaState::HeatMapRangePopup { ref mut state } => {
+                let action = HeatMapRangePopup::handle_event(&event, state)?;
+                self.close_popup();
+                if let HeatMapRangePopupAction::Select(range) = action {
+                    self.heatmap_range = range;
+                }
+            }
+            DailaState::NumberPromptPopup {
+                ref activity,
+                ref mut state,
+            } => {
+                let action = NumberPromptPopup::handle_event(&event, state)?;
+                if let NumberPromptPopupAction::Accept(value) = action {
+                    self.activities.add_activity(activity.clone().value(value));
+                }
+                self.close_popup();
+            }
+            DailaState::LogOnDatePopup { ref mut state } => {
+                let action = LogOnDatePopup::handle_event(&event, state)?;
+                self.close_popup();
+                if let LogOnDatePopupAction::Log(activity_id, date) = action {
+                    self.activities
+                        .add_activity(self.completed_activity(activity_id, date));
+                    let name = self
+                        .activity_types
+                        .activity_type(activity_id)
+                        .map_or("Activity", |activity_type| activity_type.name.as_str());
+                    let message = format!("Logged {} on {}", name, date.format("%a %-d %b %Y"));
+                    self.set_status_message(&message);
+                }
+            }
+            DailaState::MergeActivityPopup {
+                activity_id,
+                ref mut state,
+            } => {
+                let Some(into) = ActivityPickerPopup::handle_event(&event, state)? else {
+                    self.close_popup();
+                    return Some(());
+                };
+                let name = |id| {
+                    self.activity_types
+                        .activity_type(id)
+                        .map_or("", |activity_type| activity_type.name.as_str())
+                };
+                let prompt = format!("Merge {} into {}?", name(activity_id), name(into));
+                let details = vec![format!(
+                    "Its completions move to {}, and {} is deleted",
+                    name(into),
+                    name(activity_id)
+                )];
+                // Over the picker, so that declining returns to it.
+                self.open_popup(DailaState::ConfirmationPopup {
+                    action: ConfirmationAction::MergeActivity(activity_id, into),
+                    state: ConfirmationPopupState::new(prompt).details(details),
+                });
+            }
+            DailaState::FreezePopup { ref mut state } => {
+                let action = FreezePopup::handle_event(&event, state)?;
+                self.close_popup();
+                let (message, start, end) = match action {
+                    FreezePopupAction::Freeze(start, end) => {
+                        self.activities.freeze(start, end);
+                        ("Froze", start, end)
                     }
-                    ActivityPopupAction::EditActivity(title, id) => {
-                        self.state = DailaState::Default;
-                        self.activity_types.update_activity(id, title);
+                    FreezePopupAction::Unfreeze(start, end) => {
+                        self.activities.unfreeze(start, end);
+                        ("Unfroze", start, end)
                     }
+                    FreezePopupAction::Exit => return Some(()),
+                };
+                let message = if start == end {
+                    format!("{} {}", message, start.format("%a %-d %b %Y"))
+                } else {
+                    format!(
+                        "{} {} to {}",
+                        message,
+                        start.format("%-d %b %Y"),
+                        end.format("%-d %b %Y")
+                    )
+                };
+                self.set_status_message(&message);
+            }
+            DailaState::NoteEditorPopup {
+                date,
+                ref mut state,
+            } => {
+                let action = NoteEditorPopup::handle_event(&event, state)?;
+                self.close_popup();
+                if let NoteEditorPopupAction::Save(note) = action {
+                    let message = if note.trim().is_empty() {
+                        "Removed the note"
+                    } else {
+                        "Saved the note"
+                    };
+                    self.activities.set_note(date, note);
+                    self.set_status_message(message);
                 }
             }
             DailaState::ConfirmationPopup {
@@ -276,26 +1528,460 @@ impl Daila {
                         }
                         ConfirmationAction::DeleteActivity(id) => {
                             self.activity_types.delete_activity_type(id);
-                            self.activity_selector_state = ActivitySelectorState::new(
-                                self.activity_types.activity_types().len(),
+                            if self.pinned_activity == Some(*id) {
+                                self.pinned_activity = None;
+                            }
+                            self.update_activity_count();
+                        }
+                        ConfirmationAction::DeleteActivities(ids) => {
+                            for id in ids {
+                                self.activity_types.delete_activity_type(id);
+                                if self.pinned_activity == Some(*id) {
+                                    self.pinned_activity = None;
+                                }
+                            }
+                            self.activity_selector_state.set_marking(false);
+                            self.update_activity_count();
+                        }
+                        ConfirmationAction::MergeActivity(from, into) => {
+                            let (from, into) = (*from, *into);
+                            let message = format!(
+                                "Merged {} into {}",
+                                self.activity_types
+                                    .activity_type(from)
+                                    .map_or("", |activity_type| activity_type.name.as_str()),
+                                self.activity_types
+                                    .activity_type(into)
+                                    .map_or("", |activity_type| activity_type.name.as_str()),
                             );
+                            let moved = self.activities.reassign(from, into);
+                            self.activity_types.delete_activity_type(&from);
+                            if self.pinned_activity == Some(from) {
+                                self.pinned_activity = Some(into);
+                            }
+                            self.update_activity_count();
+                            self.select_activity(into);
+                            self.set_status_message(&format!(
+                                "{} ({} completions moved)",
+                                message,
+                                format_count(moved)
+                            ));
+                            // The picker is closed along with the confirmation.
+                            self.close_popup();
+                        }
+                        ConfirmationAction::CopyPreviousDay => {
+                            for activity_id in self.activities_to_copy_from_previous_day() {
+                                self.activities.add_activity(
+                                    self.completed_activity(activity_id, self.active_date),
+                                );
+                            }
                         }
+                        // Closed along with the confirmation.
+                        ConfirmationAction::DiscardActivityChanges => self.close_popup(),
                     },
                     ConfirmationPopupAction::Decline => (),
                 }
-                self.state = DailaState::Default;
+                self.close_popup();
+            }
+            DailaState::Overview { ref mut scroll } => {
+                let activity_types_len = self.activity_types.len();
+                let Event::Key(key_event) = event else {
+                    return None;
+                };
+                match key_event.code {
+                    KeyCode::Esc | KeyCode::Char('o') => {
+                        self.close_popup();
+                    }
+                    KeyCode::Up => *scroll = scroll.saturating_sub(1),
+                    KeyCode::Down if *scroll + 1 < activity_types_len => *scroll += 1,
+                    _ => (),
+                }
+            }
+            DailaState::Searching => {
+                let Event::Key(key_event) = event else {
+                    return None;
+                };
+                match key_event.code {
+                    KeyCode::Enter => self.close_popup(),
+                    KeyCode::Esc => {
+                        self.close_popup();
+                        self.update_selector_options(|state| state.set_name_filter(String::new()));
+                    }
+                    KeyCode::Backspace => {
+                        let mut name_filter = self.activity_selector_state.name_filter().to_owned();
+                        name_filter.pop();
+                        self.set_name_filter(name_filter);
+                    }
+                    KeyCode::Char(c) => {
+                        let name_filter =
+                            format!("{}{}", self.activity_selector_state.name_filter(), c);
+                        self.set_name_filter(name_filter);
+                    }
+                    KeyCode::Left => self.activity_selector_state.select_left(),
+                    KeyCode::Right => self.activity_selector_state.select_right(),
+                    KeyCode::Up => self.activity_selector_state.select_up(),
+                    KeyCode::Down => self.activity_selector_state.select_down(),
+                    _ => (),
+                }
             }
         };
 
         Some(())
     }
 
+    /**
+     * Clicking an activity selects it, or toggles it if it is already
+     * selected, and clicking a day of the heatmap goes to that day.
+     */
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        let (x, y) = (mouse_event.column, mouse_event.row);
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(index) = self.activity_selector_state.index_at(x, y) {
+                    if self.activity_selector_state.selected(index) {
+                        if !self.readonly {
+                            self.toggle_selected_activity();
+                        }
+                    } else {
+                        self.activity_selector_state.select(index);
+                    }
+                } else if let Some(date) = self.heatmap_area.and_then(|area| {
+                    let date_range = self.heatmap_date_range();
+                    HeatMap::default()
+                        .date_range(date_range.0, date_range.1)
+                        .rows(self.heatmap_rows)
+                        .date_at(x, y, &area)
+                }) {
+                    self.active_date = date;
+                }
+            }
+            MouseEventKind::ScrollUp if self.activity_selector_state.index_at(x, y).is_some() => {
+                self.activity_selector_state.select_up();
+            }
+            MouseEventKind::ScrollDown if self.activity_selector_state.index_at(x, y).is_some() => {
+                self.activity_selector_state.select_down();
+            }
+            _ => (),
+        }
+    }
+
+    /**
+     * Create the drafted activity type, or update it if it already exists.
+     */
+    fn save_activity_draft(&mut self, draft: ActivityDraft) -> ActivityId {
+        let id = match draft.id {
+            Some(id) => {
+                self.activity_types.update_activity(draft.name, id);
+                id
+            }
+            None => self.activity_types.create_new_activity(draft.name),
+        };
+        self.activity_types.update_color(id, draft.color);
+        self.activity_types.update_tags(id, draft.tags);
+        self.activity_types
+            .update_weekly_target(id, draft.weekly_target);
+        self.activity_types
+            .update_default_value(id, draft.default_value);
+        id
+    }
+
+    /**
+     * A completion of an activity on a date, with the activity's default
+     * value if it has one. Only toggling the selected activity asks for the
+     * value instead.
+     */
+    fn completed_activity(&self, activity_id: ActivityId, date: NaiveDate) -> Activity {
+        self.activity_types.activity_type(activity_id).map_or_else(
+            || Activity::new(activity_id, date),
+            |activity_type| activity_type.completion(date),
+        )
+    }
+
+    fn toggle_selected_activity(&mut self) {
+        if let Some(activity_option) = self.selected_activity_option() {
+            let activity = Activity::new(activity_option.activity_id(), self.active_date);
+            if activity_option.completed() {
+                self.activities.remove_activity(activity);
+            } else if let Some(default_value) = activity_option.activity_type().default_value {
+                // Activities with a value ask for the quantity done.
+                let label = format!(
+                    "Value of {} on {}",
+                    activity_option.name(),
+                    self.active_date.format("%-d %b %Y")
+                );
+                self.state = DailaState::NumberPromptPopup {
+                    activity: activity.clone(),
+                    state: NumberPromptPopupState::new(label, Some(default_value)).min(0.0),
+                };
+            } else {
+                self.activities.add_activity(activity);
+            }
+        } else {
+            self.set_status_message("No activity selected");
+        }
+    }
+
+    /**
+     * The marked activity types, ordered by id.
+     */
+    fn marked_activity_types(&self) -> Vec<&ActivityType> {
+        let mut marked: Vec<&ActivityType> = self
+            .activity_selector_state
+            .marked()
+            .into_iter()
+            .filter_map(|id| self.activity_types.activity_type(id))
+            .collect();
+        marked.sort_by_key(|activity_type| activity_type.id);
+        marked
+    }
+
+    /**
+     * Mark every activity (or every marked activity, if any are marked) as
+     * completed (or not completed) on the active date. Activities already in
+     * the requested state are left untouched. Returns the number of
+     * activities that changed.
+     */
+    fn set_all_activities_completed(&mut self, completed: bool) -> usize {
+        let marked = self.activity_selector_state.marked();
+        let mut changed = 0;
+        for activity_option in self.activity_selector_options() {
+            if !marked.is_empty() && !marked.contains(&activity_option.activity_id()) {
+                continue;
+            }
+            if activity_option.completed() == completed {
+                continue;
+            }
+            let activity_id = activity_option.activity_id();
+            if completed {
+                self.activities
+                    .add_activity(self.completed_activity(activity_id, self.active_date));
+            } else {
+                self.activities
+                    .remove_activity(Activity::new(activity_id, self.active_date));
+            }
+            changed += 1;
+        }
+
+        changed
+    }
+
+    /**
+     * What deleting an activity type would lose: the days it was done on,
+     * the first and last of them, and its current streak.
+     */
+    fn deletion_details(&mut self, activity_id: ActivityId) -> Vec<String> {
+        // Every completion is lost, including archived ones.
+        self.activities.load_archive();
+        let Some(activity_type) = self.activity_types.activity_type(activity_id) else {
+            return vec![];
+        };
+        let dates: Vec<NaiveDate> = self
+            .activities
+            .activities_with_type(activity_type)
+            .into_iter()
+            .map(HeatMapValue::heat_map_date)
+            .collect();
+        let (Some(first), Some(last)) = (dates.iter().min(), dates.iter().max()) else {
+            return vec![String::from("Never done")];
+        };
+        let days = self
+            .activities
+            .days_completed_between(activity_id, *first, *last);
+        let mut details = vec![
+            format!("Done on {}", format_days(days)),
+            format!(
+                "First done {}, last done {}",
+                first.format("%-d %b %Y"),
+                last.format("%-d %b %Y")
+            ),
+        ];
+        let streak = self.activities.streak(activity_type, self.today());
+        if streak > 0 {
+            details.push(format!(
+                "A streak of {} would be lost",
+                format_days(streak as usize)
+            ));
+        }
+        details
+    }
+
+    /**
+     * Activities completed on the day before the active date which have not
+     * been completed on the active date.
+     */
+    fn activities_to_copy_from_previous_day(&self) -> Vec<ActivityId> {
+        let previous_date = match self.active_date.pred_opt() {
+            Some(date) => date,
+            None => return vec![],
+        };
+        let previous_options =
+            activites::activity_options(&self.activity_types, &self.activities, previous_date);
+
+        // Both sets of options are for the same activity types, in the same order.
+        previous_options
+            .iter()
+            .zip(
+                activites::activity_options(
+                    &self.activity_types,
+                    &self.activities,
+                    self.active_date,
+                )
+                .iter(),
+            )
+            .filter(|(previous, current)| previous.completed() && !current.completed())
+            .map(|(previous, _)| previous.activity_id())
+            .collect()
+    }
+
+    /**
+     * Options for the activities displayed in the selector, narrowed to the
+     * tag and name filters, in the selector's order.
+     */
     fn activity_selector_options(&self) -> Vec<ActivityOption> {
-        activites::activity_options(
-            &self.activity_types,
+        let mut options =
+            activites::activity_options(&self.activity_types, &self.activities, self.active_date);
+        activites::sort_activity_options(
+            &mut options,
+            self.activity_selector_state.sort_mode(),
             &self.activities,
-            self.active_date.clone(),
-        )
+            self.active_date,
+        );
+        if self.activity_selector_state.incomplete_first() {
+            // Stable, so each group keeps the sort order.
+            options.sort_by_key(|option| option.completed());
+        }
+        let name_filter = self.activity_selector_state.name_filter();
+        let mut scored_options: Vec<(u32, ActivityOption)> = options
+            .into_iter()
+            .filter(|option| match self.activity_selector_state.tag_filter() {
+                Some(tag) => option.tags().iter().any(|t| t == tag),
+                None => true,
+            })
+            .filter_map(|option| Some((fuzzy_score(name_filter, option.name())?, option)))
+            .collect();
+        // Stable, so equally good matches keep the sort order.
+        scored_options.sort_by_key(|(score, _)| Reverse(*score));
+        scored_options
+            .into_iter()
+            .map(|(_, option)| option)
+            .collect()
+    }
+
+    /**
+     * Filter the selector's activities by name, selecting the best match.
+     */
+    fn set_name_filter(&mut self, name_filter: String) {
+        self.update_selector_options(|state| state.set_name_filter(name_filter));
+        self.activity_selector_state.select(0);
+    }
+
+    /**
+     * Whether each option is part of the routine (done on most recent days
+     * it was scheduled on) but not yet done today. Only today has reminders,
+     * and only for activities scheduled today.
+     */
+    fn reminders(&self, options: &[ActivityOption]) -> Vec<bool> {
+        let today = self.today();
+        if !self.reminders || self.active_date != today {
+            return vec![false; options.len()];
+        }
+        let (Some(start), Some(end)) = (
+            today.checked_sub_days(Days::new(ROUTINE_DAYS)),
+            today.pred_opt(),
+        ) else {
+            return vec![false; options.len()];
+        };
+        options
+            .iter()
+            .map(|option| {
+                let days_completed =
+                    self.activities
+                        .days_completed_between(option.activity_id(), start, end);
+                let days_scheduled = option.activity_type().scheduled_days_between(start, end);
+                !option.completed()
+                    && option.scheduled()
+                    && days_completed as f32 >= days_scheduled as f32 * ROUTINE_COMPLETION_RATE
+            })
+            .collect()
+    }
+
+    /**
+     * Change how the selector's options are filtered or ordered, keeping the
+     * selected activity selected if it is still displayed.
+     */
+    fn update_selector_options(&mut self, update: impl FnOnce(&mut ActivitySelectorState)) {
+        let selected_activity = self
+            .selected_activity_option()
+            .map(|option| option.activity_id());
+        update(&mut self.activity_selector_state);
+        self.update_activity_count();
+        if let Some(activity_id) = selected_activity {
+            self.select_activity(activity_id);
+        }
+    }
+
+    /**
+     * Select an activity, clearing the filters if they hide it.
+     */
+    fn reveal_activity(&mut self, activity_id: ActivityId) {
+        let displayed = self
+            .activity_selector_options()
+            .iter()
+            .any(|option| option.activity_id() == activity_id);
+        if !displayed {
+            self.update_selector_options(|state| {
+                state.set_tag_filter(None);
+                state.set_name_filter(String::new());
+            });
+        }
+        self.select_activity(activity_id);
+    }
+
+    /**
+     * Everything the search popup searches: each activity type by name, then
+     * each recent day, most recent first.
+     */
+    fn search_candidates(&self) -> Vec<SearchCandidate> {
+        let mut activity_types = self.activity_types.activity_types();
+        activity_types.sort_by_key(|activity_type| activity_type.name.to_lowercase());
+        let activities = activity_types.into_iter().map(|activity_type| {
+            SearchCandidate::Activity(activity_type.id, activity_type.name.clone())
+        });
+        let dates = self
+            .today()
+            .iter_days()
+            .rev()
+            .take(SEARCH_DAYS)
+            .map(|date| {
+                let completed = self.activities.activities_between(date, date).len();
+                SearchCandidate::Date(
+                    date,
+                    format!("{} · {} done", date.format("%a %-d %b %Y"), completed),
+                )
+            });
+        activities.chain(dates).collect()
+    }
+
+    /**
+     * Select an activity, if it is displayed in the selector.
+     */
+    fn select_activity(&mut self, activity_id: ActivityId) {
+        if let Some(index) = self
+            .activity_selector_options()
+            .iter()
+            .position(|option| option.activity_id() == activity_id)
+        {
+            self.activity_selector_state.select(index);
+        }
+    }
+
+    /**
+     * Keep the selector in sync with the number of activities it displays.
+     */
+    fn update_activity_count(&mut self) {
+        let activity_count = self.activity_selector_options().len();
+        self.activity_selector_state
+            .set_activity_count(activity_count);
     }
 
     fn selected_activity_option(&self) -> Option<ActivityOption> {
@@ -306,46 +1992,519 @@ impl Daila {
         }
     }
 
-    fn heatmap_values(&self) -> Vec<&Activity> {
-        if self.activity_selector_state.selected_index().is_none() {
-            return vec![];
+    /**
+     * Load the archived activities once a date before them is displayed, or
+     * counted towards the active date's streaks and completion rates.
+     */
+    fn load_archive_if_displayed(&mut self) {
+        let Some(cutoff) = self.activities.archived_before() else {
+            return;
+        };
+        // The options' completion rates are computed over the days before the
+        // active date.
+        let completion_rate_start = self
+            .active_date
+            .checked_sub_days(Days::new(activites::COMPLETION_RATE_DAYS - 1))
+            .unwrap_or(self.active_date);
+        let streak_reaches_archive = || {
+            self.activity_types
+                .activity_types()
+                .into_iter()
+                .any(|activity_type| {
+                    self.activities
+                        .streak_reaches_archive(activity_type, self.active_date)
+                })
+        };
+        if completion_rate_start < cutoff
+            || self.heatmap_range == HeatMapRange::AllTime
+            || self.heatmap_date_range().0 < cutoff
+            || streak_reaches_archive()
+        {
+            self.activities.load_archive();
+        }
+    }
+
+    /**
+     * Recompute the heats of the selected and pinned activities if the
+     * activities, or the heatmap's date range changed since they were last
+     * computed.
+     */
+    fn update_heatmap_cache(&mut self) {
+        let source = self.heatmap_source();
+        if let Some(cache) = self.updated_heatmap_cache(self.heatmap_cache.as_ref(), source) {
+            self.heatmap_cache = Some(cache);
+        }
+        if let Some(cache) =
+            self.updated_heatmap_cache(self.comparison_cache.as_ref(), self.pinned_activity.into())
+        {
+            self.comparison_cache = Some(cache);
+        }
+    }
+
+    /**
+     * Range of dates displayed in the heatmap. Without a rolling window, it is
+     * the year of the active date, so that every year recorded can be viewed
+     * by going to it.
+     */
+    fn heatmap_date_range(&self) -> HeatMapDateRange {
+        let today = self.today();
+        match self.heatmap_range {
+            HeatMapRange::SelectedYear => HeatMapDateRange::year_of(self.active_date),
+            HeatMapRange::Days(days) => HeatMapDateRange::days_ending_on(days, today),
+            HeatMapRange::AllTime => {
+                let first = self.activities.first_tracked_date().unwrap_or(today);
+                HeatMapDateRange(first.min(today), today)
+            }
+            HeatMapRange::Custom(start, end) => HeatMapDateRange(start, end),
+        }
+    }
+
+    /**
+     * Description of the heatmap's dates, drawn below it.
+     */
+    fn heatmap_caption(&self) -> String {
+        match self.heatmap_range {
+            HeatMapRange::SelectedYear => format!("Year: {}", self.active_date.year()),
+            HeatMapRange::AllTime => {
+                let HeatMapDateRange(start, _) = self.heatmap_date_range();
+                format!("All time, since {}", start.format("%-d %b %Y"))
+            }
+            range => range.label(),
+        }
+    }
+
+    fn heatmap_source(&self) -> HeatMapSource {
+        if self.all_activities_heatmap {
+            return HeatMapSource::AllActivities;
+        }
+        self.selected_activity_option()
+            .map(|option| option.activity_id())
+            .into()
+    }
+
+    /**
+     * The heats of a source over the heatmap's dates, or None if they are
+     * already cached.
+     */
+    fn updated_heatmap_cache(
+        &self,
+        cache: Option<&HeatMapCache>,
+        source: HeatMapSource,
+    ) -> Option<HeatMapCache> {
+        let date_range = self.heatmap_date_range();
+        if cache.is_some_and(|cache| self.cache_is_current(cache, source, date_range)) {
+            return None;
+        }
+        Some(self.heatmap_cache(source, date_range))
+    }
+
+    /**
+     * Recompute the heats of the activities in the overview that changed
+     * since they were last computed, if the overview is displayed.
+     */
+    fn update_overview_cache(&mut self) {
+        if !matches!(self.state, DailaState::Overview { .. }) {
+            self.overview_cache.clear();
+            return;
+        }
+        let date_range = self.overview_date_range();
+        let mut previous = std::mem::take(&mut self.overview_cache);
+        self.overview_cache = self
+            .activity_types
+            .activity_types()
+            .into_iter()
+            .map(|activity_type| {
+                let source = HeatMapSource::Activity(activity_type.id);
+                previous
+                    .iter()
+                    .position(|cache| cache.source == source)
+                    .map(|index| previous.swap_remove(index))
+                    .filter(|cache| self.cache_is_current(cache, source, date_range))
+                    .unwrap_or_else(|| self.heatmap_cache(source, date_range))
+            })
+            .collect();
+    }
+
+    /**
+     * Range of dates displayed by each heatmap in the overview.
+     */
+    fn overview_date_range(&self) -> HeatMapDateRange {
+        let today = self.today();
+        let start = today
+            .checked_sub_days(Days::new(OVERVIEW_WEEKS * 7 - 1))
+            .unwrap();
+        HeatMapDateRange(start, today)
+    }
+
+    /**
+     * Whether cached heats are those of a source over a date range,
+     * computed from the current activities.
+     */
+    fn cache_is_current(
+        &self,
+        cache: &HeatMapCache,
+        source: HeatMapSource,
+        date_range: HeatMapDateRange,
+    ) -> bool {
+        cache.source == source
+            && cache.revision == self.activities.revision()
+            && cache.date_range == date_range
+    }
+
+    /**
+     * The heats of a source over a date range.
+     */
+    fn heatmap_cache(&self, source: HeatMapSource, date_range: HeatMapDateRange) -> HeatMapCache {
+        let heats = match source {
+            HeatMapSource::Activity(activity_id) => heatmap::dense_heats(
+                date_range.0,
+                self.activities.activities_with_type_between(
+                    activity_id,
+                    date_range.0,
+                    date_range.1,
+                ),
+            ),
+            // Each day's heat is the total of its completions, counting those
+            // with a quantity as the quantity.
+            HeatMapSource::AllActivities => heatmap::dense_heats(
+                date_range.0,
+                self.activities
+                    .activities_between(date_range.0, date_range.1),
+            ),
+            HeatMapSource::Empty => vec![],
+        };
+        HeatMapCache {
+            source,
+            revision: self.activities.revision(),
+            date_range,
+            heats,
+        }
+    }
+
+    fn heatmap_heats(&self) -> &[Option<f32>] {
+        match &self.heatmap_cache {
+            Some(cache) => &cache.heats,
+            None => &[],
+        }
+    }
+
+    /**
+     * The heats displayed as the coldest and hottest colors, so that the
+     * gradient spans the heats recorded. The hottest is the activity's heat
+     * maximum, or else the hottest of its heats (at least one). The coldest
+     * is a quarter of the spread of the heats below the coldest of them (at
+     * least zero), so that the least heat is still drawn warmer than zero.
+     */
+    fn heat_range(&self, activity_id: Option<ActivityId>, heats: &[Option<f32>]) -> (f32, f32) {
+        let (min, max) = heats
+            .iter()
+            .flatten()
+            .fold((f32::INFINITY, 1.0_f32), |(min, max), &heat| {
+                (min.min(heat), max.max(heat))
+            });
+        let high = activity_id
+            .and_then(|activity_id| self.activity_types.activity_type(activity_id))
+            .and_then(|activity_type| activity_type.heat_max)
+            .unwrap_or(max);
+        let low = (min - (max - min) / 4.0).max(0.0);
+        // Without heats, or when every heat is as hot as the hottest color.
+        if low < high {
+            (low, high)
+        } else {
+            (0.0, high)
+        }
+    }
+
+    /**
+     * The pinned activity and its heats, if it is being compared against a
+     * different selected activity.
+     */
+    fn comparison(&self) -> Option<(&ActivityType, &[Option<f32>])> {
+        if self.all_activities_heatmap {
+            return None;
+        }
+        let pinned_activity = self.pinned_activity?;
+        let selected_activity = self.selected_activity_option()?.activity_id();
+        if pinned_activity == selected_activity {
+            return None;
+        }
+        let activity_type = self.activity_types.activity_type(pinned_activity)?;
+        let heats = self
+            .comparison_cache
+            .as_ref()
+            .map_or(&[][..], |cache| &cache.heats);
+        Some((activity_type, heats))
+    }
+
+    fn bar_chart(&self, chart: Chart) -> BarChart {
+        match chart {
+            Chart::Daily(period) => BarChart::Daily(self.daily_bar_chart(period)),
+            Chart::Weekdays => BarChart::Weekdays(self.weekday_bar_chart()),
+        }
+    }
+
+    /**
+     * Bar chart of the selected activity's completions in the week or month
+     * of the active date.
+     */
+    fn daily_bar_chart(&self, period: BarChartPeriod) -> DailyBarChart {
+        let bar_chart = DailyBarChart::default()
+            .date(self.active_date)
+            .period(period);
+        match self.selected_activity_option() {
+            Some(activity_option) => {
+                let values = self.activities.activities_with_type_between(
+                    activity_option.activity_id(),
+                    bar_chart.first_day(),
+                    bar_chart.last_day(),
+                );
+                bar_chart.values(values)
+            }
+            None => bar_chart,
+        }
+    }
+
+    /**
+     * Bar chart of the share of each day of the week the selected activity
+     * was done on, over the heatmap's dates up to today.
+     */
+    fn weekday_bar_chart(&self) -> WeekdayBarChart {
+        let HeatMapDateRange(start, end) = self.heatmap_date_range();
+        // Days yet to come can't have been done on.
+        let end = end.min(self.today());
+        let bar_chart = WeekdayBarChart::default().range(start, end);
+        match self.selected_activity_option() {
+            Some(activity_option) => bar_chart.rates(self.activities.weekday_completion_rates(
+                activity_option.activity_type(),
+                start,
+                end,
+            )),
+            None => bar_chart,
+        }
+    }
+
+    fn month_view(&self) -> MonthView {
+        let month_view = MonthView::default().date(self.active_date);
+        match self.selected_activity_option() {
+            Some(activity_option) => {
+                let values = self.activities.activities_with_type_between(
+                    activity_option.activity_id(),
+                    month_view.first_day(),
+                    month_view.last_day(),
+                );
+                month_view.values(values)
+            }
+            None => month_view,
+        }
+    }
+
+    /**
+     * Render a compact heatmap of recent weeks for each activity type, stacked
+     * vertically, starting from the activity type at index `scroll`.
+     */
+    fn render_overview<B: Backend>(&self, frame: &mut Frame<B>, area: Rect, scroll: usize) {
+        let date_range = self.overview_date_range();
+
+        let border = Block::default()
+            .title(" Overview (up/down: scroll, o/esc: back) ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded);
+        let inner = border.inner(area);
+        frame.render_widget(border, area);
+
+        let mut y = inner.y;
+        for cache in self.overview_cache.iter().skip(scroll) {
+            let HeatMapSource::Activity(activity_id) = cache.source else {
+                continue;
+            };
+            let Some(activity_type) = self.activity_types.activity_type(activity_id) else {
+                continue;
+            };
+            let heatmap = HeatMap::default()
+                .date_range(date_range.0, date_range.1)
+                .heats(date_range.0, &cache.heats)
+                .today(self.today())
+                .rows(OVERVIEW_ROWS);
+            let heatmap_height = heatmap.height();
+            // +1: Activity name.
+            if y + heatmap_height + 1 > inner.bottom() {
+                break;
+            }
+
+            let name = Paragraph::new(Text::raw(activity_type.name.as_str()))
+                .style(Style::default().fg(Color::Yellow));
+            frame.render_widget(name, Rect::new(inner.x, y, inner.width, 1));
+            frame.render_widget(
+                heatmap,
+                Rect::new(inner.x, y + 1, inner.width, heatmap_height),
+            );
+            y += heatmap_height + 1;
         }
-        let activity_types = self.activity_types.activity_types();
-        let selected_activity =
-            activity_types[self.activity_selector_state.selected_index().unwrap()];
-        self.activities.activities_with_type(&selected_activity)
     }
 
     pub fn run_daila<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<(), io::Error> {
         self.running = true;
         while self.running {
-            if self.refresh {
-                terminal.clear().unwrap();
-                self.refresh = false;
-            }
+            // Each frame is drawn in full and only the cells that changed are
+            // written to the terminal, so it is never cleared (which flickers).
+            self.load_archive_if_displayed();
+            self.update_heatmap_cache();
+            self.update_overview_cache();
+            self.heatmap_area = None;
             terminal.draw(|frame| {
-                let heatmap_values = self.heatmap_values();
-                let heatmap = HeatMap::default().values(heatmap_values);
+                if let DailaState::Overview { scroll } = self.state {
+                    self.render_overview(frame, frame.size(), scroll);
+                    return;
+                }
+
+                let selected_activity = self.selected_activity_option();
+                let date_range = self.heatmap_date_range();
+                let heat_range = self.heat_range(
+                    match self.heatmap_source() {
+                        HeatMapSource::Activity(activity_id) => Some(activity_id),
+                        _ => None,
+                    },
+                    self.heatmap_heats(),
+                );
+                let mut heatmap = HeatMap::default()
+                    .date_range(date_range.0, date_range.1)
+                    .heats(date_range.0, self.heatmap_heats())
+                    .heat_range(heat_range.0, heat_range.1)
+                    .rows(self.heatmap_rows)
+                    .shade_weekends(true)
+                    .week_numbers(self.week_numbers)
+                    .highlight_date(self.active_date)
+                    .today(self.today())
+                    .caption(self.heatmap_caption())
+                    .frozen(self.activities.frozen().to_vec())
+                    .summary(true);
+                if let (HeatMapSource::Activity(_), Some(selected_activity)) =
+                    (self.heatmap_source(), &selected_activity)
+                {
+                    heatmap = heatmap.schedule(selected_activity.schedule().to_vec());
+                }
+                if let (Some(selected_activity), Some((pinned_activity, pinned_heats))) =
+                    (&selected_activity, self.comparison())
+                {
+                    let pinned_heat_range = self.heat_range(Some(pinned_activity.id), pinned_heats);
+                    heatmap = heatmap
+                        .compare(date_range.0, pinned_heats)
+                        .comparison_heat_range(pinned_heat_range.0, pinned_heat_range.1)
+                        .legend(selected_activity.name(), &pinned_activity.name);
+                }
                 let selector_options = self.activity_selector_options();
                 let frame_size = frame.size();
-                let selector = ActivitySelector::<ActivityOption>::default()
-                    .values(selector_options.iter().map(|o| o).collect())
-                    .title(if self.active_date == chrono::Local::now().date_naive() {
-                        self.active_date
-                            .format("(Today) %A, %-d %B, %C%y")
-                            .to_string()
-                    } else {
-                        self.active_date.format("%A, %-d %B, %C%y").to_string()
-                    });
-
+                let mut title = if self.active_date == self.today() {
+                    self.active_date
+                        .format("(Today) %A, %-d %B, %C%y")
+                        .to_string()
+                } else {
+                    self.active_date.format("%A, %-d %B, %C%y").to_string()
+                };
+                let completed = selector_options
+                    .iter()
+                    .filter(|option| option.completed())
+                    .count();
+                let all_completed =
+                    !selector_options.is_empty() && completed == selector_options.len();
+                if !selector_options.is_empty() {
+                    title.push_str(&format!(" — {}/{} done", completed, selector_options.len()));
+                }
+                if self.activities.note(self.active_date).is_some() {
+                    title.push_str(" [note]");
+                }
+                if let Some(tag) = self.activity_selector_state.tag_filter() {
+                    title.push_str(&format!(" #{}", tag));
+                }
+                let sort_mode = self.activity_selector_state.sort_mode();
+                if sort_mode != ActivitySortMode::Id {
+                    title.push_str(&format!(" [{}]", sort_mode.label()));
+                }
+                if self.activity_selector_state.incomplete_first() {
+                    title.push_str(" [not done first]");
+                }
+                if self.readonly {
+                    title.push_str(" [read-only]");
+                }
+                if self.activity_selector_state.marking() {
+                    title.push_str(&format!(
+                        " · {} marked",
+                        self.activity_selector_state.marked().len()
+                    ));
+                }
+                let name_filter = self.activity_selector_state.name_filter();
+                if let DailaState::Searching = self.state {
+                    title.push_str(&format!(" /{}▏", name_filter));
+                } else if !name_filter.is_empty() {
+                    title.push_str(&format!(" /{}", name_filter));
+                }
                 let display_size = Rect {
                     x: frame_size.x,
                     y: frame_size.y,
                     width: std::cmp::min(heatmap.width(), frame.size().width),
                     height: frame_size.height,
                 };
+                let selector = ActivitySelector::<ActivityOption>::default()
+                    .values(selector_options.iter().collect())
+                    .reminders(self.reminders(&selector_options))
+                    .title(title)
+                    .title_color(if all_completed {
+                        Color::Green
+                    } else {
+                        Color::Yellow
+                    })
+                    .width(display_size.width)
+                    .compact(self.compact_selector)
+                    .divider(if self.activity_selector_state.incomplete_first() {
+                        selector_options
+                            .iter()
+                            .position(|option| option.completed())
+                    } else {
+                        None
+                    });
+                let selector = if self.activity_selector_state.marking() {
+                    selector.marks(
+                        selector_options
+                            .iter()
+                            .map(|option| {
+                                self.activity_selector_state.is_marked(option.activity_id())
+                            })
+                            .collect(),
+                    )
+                } else {
+                    selector
+                };
 
-                let required_height = selector.height() + heatmap.height();
+                let month_view = self.month_view();
+                let calendar_height = if self.month_zoom {
+                    month_view.height()
+                } else {
+                    heatmap.height()
+                };
+
+                let bar_chart = self.bar_chart.map(|chart| self.bar_chart(chart));
+                let bar_chart_height = bar_chart.as_ref().map_or(0, |chart| chart.height());
+
+                // The selector is compacted, and then scrolled, when there
+                // isn't room for all of its rows.
+                let selector_room = frame_size.height.saturating_sub(
+                    bar_chart_height + calendar_height + FOOTER_HEIGHT + HELP_HINT_HEIGHT,
+                );
+                let selector = if selector.height() > selector_room {
+                    selector.compact(true)
+                } else {
+                    selector
+                };
+                let selector_height = selector
+                    .height()
+                    .min(selector_room)
+                    .max(selector.min_height());
+                let required_height = selector_height
+                    + bar_chart_height
+                    + calendar_height
+                    + FOOTER_HEIGHT
+                    + HELP_HINT_HEIGHT;
                 let required_width = heatmap.width();
                 if required_height > frame_size.height || required_width > frame_size.width {
                     // Display notice to make the terminal bigger.
@@ -370,48 +2529,291 @@ impl Daila {
                     .direction(Direction::Vertical)
                     .constraints(
                         [
-                            Constraint::Length(selector.height()),
-                            Constraint::Length(heatmap.height()),
-                            Constraint::Length(10),
+                            Constraint::Length(selector_height),
+                            Constraint::Length(bar_chart_height),
+                            Constraint::Length(calendar_height),
+                            Constraint::Length(FOOTER_HEIGHT),
+                            Constraint::Length(HELP_HINT_HEIGHT),
                         ]
                         .as_ref(),
                     )
-                    .split(display_size.clone());
+                    .split(display_size);
+
+                if let Some(bar_chart) = bar_chart {
+                    // Center the bar chart horizontally.
+                    let bar_chart_area = Rect {
+                        x: chunks[1].x + chunks[1].width.saturating_sub(bar_chart.width()) / 2,
+                        width: bar_chart.width().min(chunks[1].width),
+                        ..chunks[1]
+                    };
+                    frame.render_widget(bar_chart, bar_chart_area);
+                }
 
-                frame.render_widget(heatmap, chunks[1]);
-                frame.render_widget(self.instructions_block(), chunks[2]);
+                if self.month_zoom {
+                    // Center the month horizontally.
+                    let month_area = Rect {
+                        x: chunks[2].x + chunks[2].width.saturating_sub(month_view.width()) / 2,
+                        width: month_view.width().min(chunks[2].width),
+                        ..chunks[2]
+                    };
+                    frame.render_widget(month_view, month_area);
+                } else {
+                    frame.render_widget(heatmap, chunks[2]);
+                    self.heatmap_area = Some(chunks[2]);
+                }
+                frame.render_widget(self.tracking_footer(), chunks[3]);
+                frame.render_widget(self.help_hint(), chunks[4]);
                 frame.render_stateful_widget(
                     selector,
                     chunks[0],
                     &mut self.activity_selector_state,
                 );
 
-                match &mut self.state {
-                    DailaState::ActivityPopup { ref mut state } => popup::render_in_frame(
-                        frame,
-                        &display_size,
-                        50,
-                        70,
-                        ActivityPopup::default(),
-                        state,
-                    ),
-                    DailaState::ConfirmationPopup {
-                        action: _action,
-                        ref mut state,
-                    } => popup::render_in_frame(
-                        frame,
-                        &display_size,
-                        50,
-                        70,
-                        ConfirmationPopup::default(),
-                        state,
-                    ),
-                    _ => (),
+                // Each popup is inset further than the one below it, so that
+                // the popups below remain visible around it.
+                let states = self.popup_stack.iter_mut().chain([&mut self.state]);
+                for (depth, state) in states.enumerate() {
+                    render_popup(frame, &popup::inset(&display_size, depth as u16), state);
+                }
+
+                // Overlay the status message on the bottom line of the frame.
+                if let Some(status_message) = self.status_message_block() {
+                    let status_area = Rect {
+                        x: display_size.x,
+                        y: frame_size.bottom() - 1,
+                        width: display_size.width,
+                        height: 1,
+                    };
+                    frame.render_widget(Clear, status_area);
+                    frame.render_widget(status_message, status_area);
                 }
             })?;
-            self.handle_event(event::read());
+            // Poll so that the display is redrawn (e.g. to clear the status message)
+            // without waiting for input.
+            if event::poll(EVENT_POLL_TIMEOUT)? {
+                match event::read() {
+                    Ok(event) => self.handle_event(event),
+                    // A read interrupted by a signal is retried on the next poll.
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                    Err(e) => return Err(e),
+                }
+            }
+            self.clear_expired_status_message();
+            self.decline_expired_confirmation();
         }
 
         Ok(())
     }
 }
+
+/**
+ * Render the popup of a state, if it has one, in the center of the area.
+ */
+fn render_popup<B: Backend>(frame: &mut Frame<B>, area: &Rect, state: &mut DailaState) {
+    match state {
+        DailaState::ActivityPopup { state } => {
+            popup::render_in_frame(frame, area, ActivityPopup::default(), state)
+        }
+        DailaState::ConfirmationPopup { state, .. } => {
+            popup::render_in_frame(frame, area, ConfirmationPopup::default(), state)
+        }
+        DailaState::CalendarPopup { state } => {
+            popup::render_in_frame(frame, area, CalendarPopup::default(), state)
+        }
+        DailaState::GotoDatePopup { state } => {
+            popup::render_in_frame(frame, area, GotoDatePopup::default(), state)
+        }
+        DailaState::HeatMapRangePopup { state } => {
+            popup::render_in_frame(frame, area, HeatMapRangePopup::default(), state)
+        }
+        DailaState::NumberPromptPopup { state, .. } => {
+            popup::render_in_frame(frame, area, NumberPromptPopup::default(), state)
+        }
+        DailaState::LogOnDatePopup { state } => {
+            popup::render_in_frame(frame, area, LogOnDatePopup::default(), state)
+        }
+        DailaState::FreezePopup { state } => {
+            popup::render_in_frame(frame, area, FreezePopup::default(), state)
+        }
+        DailaState::NoteEditorPopup { state, .. } => {
+            popup::render_in_frame(frame, area, NoteEditorPopup::default(), state)
+        }
+        DailaState::MergeActivityPopup { state, .. } => {
+            popup::render_in_frame(frame, area, ActivityPickerPopup::default(), state)
+        }
+        DailaState::HelpPopup { state } => {
+            popup::render_in_frame(frame, area, HelpPopup::default(), state)
+        }
+        DailaState::SearchPopup { state } => {
+            popup::render_in_frame(frame, area, SearchPopup::default(), state)
+        }
+        _ => (),
+    }
+}
+
+/**
+ * The current day, when each day ends `day_rollover_hours` past midnight.
+ */
+pub fn today(day_rollover_hours: u32) -> NaiveDate {
+    let rollover = chrono::Duration::hours(day_rollover_hours.into());
+    (chrono::Local::now() - rollover).date_naive()
+}
+
+/**
+ * A number of days, e.g. "1 day" or "12 days".
+ */
+fn format_days(days: usize) -> String {
+    match days {
+        1 => String::from("1 day"),
+        days => format!("{} days", days),
+    }
+}
+
+/**
+ * A count with thousands separators (e.g. 1,203).
+ */
+fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    use super::*;
+
+    /**
+     * Daila with a single activity, done every day of the last five years.
+     */
+    fn daila_with_five_years_of_activities() -> Daila {
+        let mut activity_types = ActivityTypesStore::default();
+        let activity_id = activity_types.create_new_activity(String::from("Read"));
+        let mut activities = ActivitiesStore::default();
+        let today = chrono::Local::now().date_naive();
+        let mut date = today.checked_sub_days(Days::new(5 * 365)).unwrap();
+        while date <= today {
+            activities.add_activity(Activity::new(activity_id, date));
+            date = date.succ_opt().unwrap();
+        }
+        Daila::with_stores(activity_types, activities, 0)
+    }
+
+    #[test]
+    fn reuses_cached_heats_until_the_activities_change() {
+        let mut daila = daila_with_five_years_of_activities();
+        let today = chrono::Local::now().date_naive();
+        // The last 100 days.
+        daila.heatmap_range = HeatMapRange::Days(99);
+
+        daila.update_heatmap_cache();
+        let cache = daila.heatmap_cache.as_ref().unwrap();
+        // Only the displayed days are computed, not every day recorded.
+        assert_eq!(cache.heats.len(), 100);
+        let heats = cache.heats.as_ptr();
+
+        daila.update_heatmap_cache();
+        assert_eq!(daila.heatmap_cache.as_ref().unwrap().heats.as_ptr(), heats);
+
+        let activity_id = daila.activity_types.activity_types()[0].id;
+        daila
+            .activities
+            .remove_activity(Activity::new(activity_id, today));
+        daila.update_heatmap_cache();
+        let cache = daila.heatmap_cache.as_ref().unwrap();
+        assert_eq!(cache.revision, daila.activities.revision());
+        assert_eq!(cache.heats.len(), 99);
+    }
+
+    #[test]
+    fn reuses_cached_overview_heats() {
+        let mut daila = daila_with_five_years_of_activities();
+        daila.update_overview_cache();
+        assert!(daila.overview_cache.is_empty());
+
+        daila.state = DailaState::Overview { scroll: 0 };
+        daila.update_overview_cache();
+        assert_eq!(daila.overview_cache.len(), 1);
+        let heats = &daila.overview_cache[0].heats;
+        assert_eq!(heats.len(), OVERVIEW_WEEKS as usize * 7);
+        let heats = heats.as_ptr();
+
+        daila.update_overview_cache();
+        assert_eq!(daila.overview_cache[0].heats.as_ptr(), heats);
+    }
+
+    fn press(daila: &mut Daila, code: KeyCode) {
+        daila.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn closes_popups_with_esc() {
+        let mut daila = daila_with_five_years_of_activities();
+        daila.running = true;
+        daila.state = DailaState::ActivityPopup {
+            state: Box::new(ActivityPopupState::new_creator()),
+        };
+        press(&mut daila, KeyCode::Esc);
+        assert!(matches!(daila.state, DailaState::Default));
+        assert_eq!(daila.activity_types.len(), 1);
+
+        daila.state = DailaState::ConfirmationPopup {
+            action: ConfirmationAction::SaveWithoutQuitting,
+            state: ConfirmationPopupState::new(String::from("Quit without saving?")),
+        };
+        press(&mut daila, KeyCode::Esc);
+        assert!(matches!(daila.state, DailaState::Default));
+        assert!(daila.running);
+    }
+
+    #[test]
+    fn ignores_esc_without_a_popup() {
+        let mut daila = daila_with_five_years_of_activities();
+        daila.running = true;
+        let revision = daila.activities.revision();
+        press(&mut daila, KeyCode::Esc);
+        assert!(matches!(daila.state, DailaState::Default));
+        assert!(daila.running);
+        assert_eq!(daila.activities.revision(), revision);
+        assert_eq!(daila.activity_selector_state.selected_index(), Some(0));
+    }
+
+    #[test]
+    fn confirms_discarding_activity_changes_over_the_activity_popup() {
+        let mut daila = daila_with_five_years_of_activities();
+        daila.running = true;
+        daila.state = DailaState::ActivityPopup {
+            state: Box::new(ActivityPopupState::new_creator()),
+        };
+        press(&mut daila, KeyCode::Char('R'));
+        press(&mut daila, KeyCode::Esc);
+        assert!(matches!(daila.state, DailaState::ConfirmationPopup { .. }));
+        assert!(matches!(
+            daila.popup_stack[..],
+            [DailaState::ActivityPopup { .. }]
+        ));
+
+        // Declining returns to the changes.
+        press(&mut daila, KeyCode::Char('n'));
+        assert!(matches!(
+            &daila.state,
+            DailaState::ActivityPopup { state } if state.modified()
+        ));
+        assert!(daila.popup_stack.is_empty());
+
+        // Accepting closes both popups.
+        press(&mut daila, KeyCode::Esc);
+        press(&mut daila, KeyCode::Char('y'));
+        assert!(matches!(daila.state, DailaState::Default));
+        assert!(daila.popup_stack.is_empty());
+        assert_eq!(daila.activity_types.len(), 1);
+        assert!(daila.running);
+    }
+}
@@ -0,0 +1,124 @@
+/*!
+ * Dates as they are typed, in popups and on the command line.
+ */
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+use crate::heatmap::CalendarDate;
+
+/**
+ * Parse a date, which is one of:
+ * - a full date, e.g. "2024-03-05";
+ * - a day of a month this year, e.g. "5 mar" or "mar 5";
+ * - a number of days from today, e.g. "-14", "-14 days" or "+1 day";
+ * - the most recent day of the week, e.g. "mon" (today if it is a Monday);
+ * - "today" or "yesterday".
+ */
+pub fn parse_date(input: &str, today: CalendarDate) -> Result<CalendarDate, String> {
+    let input = input.trim().to_lowercase();
+    let invalid = || format!("Not a date: {}", input);
+    if input.is_empty() {
+        return Err(String::from("Enter a date"));
+    }
+    match input.as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return today.pred_opt().ok_or_else(invalid),
+        _ => (),
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    if input.starts_with(['-', '+']) {
+        let number = input
+            .strip_suffix("days")
+            .or_else(|| input.strip_suffix("day"))
+            .unwrap_or(&input)
+            .trim_end();
+        let days: i64 = number.parse().map_err(|_| invalid())?;
+        let date = if days < 0 {
+            today.checked_sub_days(Days::new(days.unsigned_abs()))
+        } else {
+            today.checked_add_days(Days::new(days as u64))
+        };
+        return date.ok_or_else(invalid);
+    }
+    if let Ok(weekday) = input.parse::<Weekday>() {
+        let days_since =
+            (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+        return today
+            .checked_sub_days(Days::new(days_since.into()))
+            .ok_or_else(invalid);
+    }
+    let with_year = format!("{} {}", input, today.year());
+    ["%d %b %Y", "%b %d %Y"]
+        .into_iter()
+        .find_map(|format| NaiveDate::parse_from_str(&with_year, format).ok())
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> CalendarDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    // A Wednesday.
+    fn today() -> CalendarDate {
+        date(2024, 3, 20)
+    }
+
+    #[test]
+    fn parses_full_dates() {
+        assert_eq!(parse_date("2024-03-05", today()), Ok(date(2024, 3, 5)));
+        assert_eq!(parse_date(" 2023-12-31 ", today()), Ok(date(2023, 12, 31)));
+    }
+
+    #[test]
+    fn parses_days_of_a_month_this_year() {
+        assert_eq!(parse_date("5 mar", today()), Ok(date(2024, 3, 5)));
+        assert_eq!(parse_date("Mar 5", today()), Ok(date(2024, 3, 5)));
+        assert_eq!(parse_date("29 feb", today()), Ok(date(2024, 2, 29)));
+    }
+
+    #[test]
+    fn parses_days_from_today() {
+        assert_eq!(parse_date("-14", today()), Ok(date(2024, 3, 6)));
+        assert_eq!(parse_date("+3", today()), Ok(date(2024, 3, 23)));
+        assert_eq!(parse_date("-0", today()), Ok(today()));
+    }
+
+    #[test]
+    fn parses_days_from_today_with_a_unit() {
+        // As shown in the popup's hint.
+        assert_eq!(parse_date("-14 days", today()), Ok(date(2024, 3, 6)));
+        assert_eq!(parse_date("-14days", today()), Ok(date(2024, 3, 6)));
+        assert_eq!(parse_date("+1 day", today()), Ok(date(2024, 3, 21)));
+    }
+
+    #[test]
+    fn parses_the_most_recent_day_of_the_week() {
+        assert_eq!(parse_date("mon", today()), Ok(date(2024, 3, 18)));
+        assert_eq!(parse_date("Monday", today()), Ok(date(2024, 3, 18)));
+        assert_eq!(parse_date("wed", today()), Ok(today()));
+        assert_eq!(parse_date("thu", today()), Ok(date(2024, 3, 14)));
+    }
+
+    #[test]
+    fn parses_today_and_yesterday() {
+        assert_eq!(parse_date("today", today()), Ok(today()));
+        assert_eq!(parse_date("Yesterday", today()), Ok(date(2024, 3, 19)));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(parse_date("", today()), Err(String::from("Enter a date")));
+        assert_eq!(
+            parse_date("soon", today()),
+            Err(String::from("Not a date: soon"))
+        );
+        for input in ["-x", "-14 weeks", "+", "31 feb", "2024-02-30", "5 mar 2024"] {
+            assert!(parse_date(input, today()).is_err(), "{}", input);
+        }
+    }
+}
@@ -1,10 +1,37 @@
 use std::{fs::create_dir_all, io::ErrorKind, path::PathBuf};
 
+#[cfg(not(debug_assertions))]
+use directories::ProjectDirs;
 use serde::{de::DeserializeOwned, Serialize};
 
+/**
+ * The directory every file is kept in.
+ */
+#[cfg(not(debug_assertions))]
+pub fn data_dir() -> PathBuf {
+    ProjectDirs::from("com", "dleamy", "daila")
+        .unwrap()
+        .data_dir()
+        .to_path_buf()
+}
+
+/**
+ * The directory every file is kept in. Debug builds keep their files in the
+ * crate, apart from those of release builds.
+ */
+#[cfg(debug_assertions)]
+pub fn data_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data")
+}
+
 pub trait File: Serialize + DeserializeOwned + Default {
     fn path() -> PathBuf;
 
+    /**
+     * Bring data saved by an older version up to date after it is loaded.
+     */
+    fn migrate(&mut self) {}
+
     fn load() -> Self {
         let path = Self::path();
         let file = if let Ok(file) = std::fs::File::open(path) {
@@ -14,10 +41,19 @@ pub trait File: Serialize + DeserializeOwned + Default {
         };
 
         let reader = std::io::BufReader::new(file);
-        serde_json::from_reader(reader).unwrap()
+        let mut value: Self = serde_json::from_reader(reader).unwrap();
+        value.migrate();
+        value
     }
 
     fn save(&self) {
+        self.write();
+    }
+
+    /**
+     * Write the value to its path, replacing what was there.
+     */
+    fn write(&self) {
         let path = Self::path();
         let file = match std::fs::File::create(&path) {
             Ok(file) => file,
@@ -30,9 +66,10 @@ pub trait File: Serialize + DeserializeOwned + Default {
 
     fn create_file(&self) -> std::fs::File {
         let path = Self::path();
-        create_dir_all(&path.parent().unwrap()).unwrap();
+        create_dir_all(path.parent().unwrap()).unwrap();
         let file = std::fs::File::options()
             .create(true)
+            .truncate(true)
             .write(true)
             .open(path)
             .unwrap();
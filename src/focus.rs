@@ -0,0 +1,73 @@
+/*!
+ * Moving the focus of a popup through its focusable elements (e.g. inputs,
+ * then buttons), in a fixed order that wraps around.
+ */
+
+/**
+ * The element after `current` in `order`, or the first if `current` is the
+ * last or isn't in `order`. Panics if `order` is empty.
+ */
+pub fn next<T: Copy + PartialEq>(order: &[T], current: T) -> T {
+    match order.iter().position(|element| *element == current) {
+        Some(index) => order[(index + 1) % order.len()],
+        None => order[0],
+    }
+}
+
+/**
+ * The element before `current` in `order`, or the last if `current` is the
+ * first or isn't in `order`. Panics if `order` is empty.
+ */
+pub fn previous<T: Copy + PartialEq>(order: &[T], current: T) -> T {
+    match order.iter().position(|element| *element == current) {
+        Some(index) => order[(index + order.len() - 1) % order.len()],
+        None => order[order.len() - 1],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORDER: [char; 3] = ['a', 'b', 'c'];
+
+    #[test]
+    fn moves_to_the_next_element() {
+        assert_eq!(next(&ORDER, 'a'), 'b');
+        assert_eq!(next(&ORDER, 'b'), 'c');
+    }
+
+    #[test]
+    fn wraps_from_the_last_element_to_the_first() {
+        assert_eq!(next(&ORDER, 'c'), 'a');
+    }
+
+    #[test]
+    fn moves_to_the_previous_element() {
+        assert_eq!(previous(&ORDER, 'c'), 'b');
+        assert_eq!(previous(&ORDER, 'b'), 'a');
+    }
+
+    #[test]
+    fn wraps_from_the_first_element_to_the_last() {
+        assert_eq!(previous(&ORDER, 'a'), 'c');
+    }
+
+    #[test]
+    fn starts_at_either_end_from_an_element_not_in_the_order() {
+        assert_eq!(next(&ORDER, 'x'), 'a');
+        assert_eq!(previous(&ORDER, 'x'), 'c');
+    }
+
+    #[test]
+    fn stays_on_a_single_element() {
+        assert_eq!(next(&['a'], 'a'), 'a');
+        assert_eq!(previous(&['a'], 'a'), 'a');
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_without_elements() {
+        previous::<char>(&[], 'a');
+    }
+}
@@ -0,0 +1,227 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    button_row::{ButtonRow, ButtonRowEvent},
+    dates::parse_date,
+    focus,
+    heatmap::CalendarDate,
+    popup::Popup,
+    text_input::{TextInput, TextInputState},
+};
+
+// Width of the popup, unless the hint or an error needs more room.
+const FREEZE_POPUP_WIDTH: u16 = 50;
+const HINT: &str = "(frozen days don't break or extend streaks)";
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum CursorPosition {
+    StartInput,
+    EndInput,
+    FreezeButton,
+    UnfreezeButton,
+}
+
+// Elements in the order Tab moves the focus through them.
+const FOCUS_ORDER: [CursorPosition; 4] = [
+    CursorPosition::StartInput,
+    CursorPosition::EndInput,
+    CursorPosition::FreezeButton,
+    CursorPosition::UnfreezeButton,
+];
+
+// Buttons from left to right, and their labels.
+const BUTTONS: [CursorPosition; 2] = [CursorPosition::FreezeButton, CursorPosition::UnfreezeButton];
+const BUTTON_LABELS: [&str; 2] = ["freeze", "unfreeze"];
+
+#[derive(Default)]
+pub struct FreezePopup {}
+
+pub enum FreezePopupAction {
+    Freeze(CalendarDate, CalendarDate),
+    Unfreeze(CalendarDate, CalendarDate),
+    Exit,
+}
+
+/**
+ * State for a popup freezing or unfreezing a range of dates, e.g. while on
+ * vacation.
+ */
+pub struct FreezePopupState {
+    cursor_position: CursorPosition,
+    start_input: TextInputState,
+    end_input: TextInputState,
+    // Date relative dates are from.
+    today: CalendarDate,
+    // Error displayed when the range isn't valid.
+    error: Option<String>,
+}
+
+impl FreezePopupState {
+    /**
+     * Initialize the popup with the range of a single date entered.
+     */
+    pub fn new(date: CalendarDate, today: CalendarDate) -> Self {
+        let date_input =
+            || TextInputState::new(date.format("%Y-%m-%d").to_string()).placeholder("Enter a date");
+        Self {
+            cursor_position: CursorPosition::StartInput,
+            start_input: date_input(),
+            end_input: date_input(),
+            today,
+            error: None,
+        }
+    }
+
+    /**
+     * The range entered, or None (setting an error) if it isn't valid.
+     */
+    fn validated_range(&mut self) -> Option<(CalendarDate, CalendarDate)> {
+        let range = parse_date(self.start_input.text(), self.today).and_then(|start| {
+            let end = parse_date(self.end_input.text(), self.today)?;
+            if start > end {
+                return Err(String::from("The range must start before it ends"));
+            }
+            Ok((start, end))
+        });
+        match range {
+            Ok(range) => Some(range),
+            Err(error) => {
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+
+    fn button_row(&self) -> ButtonRow<'static> {
+        ButtonRow::new(BUTTON_LABELS.to_vec()).selected(
+            BUTTONS
+                .iter()
+                .position(|button| *button == self.cursor_position),
+        )
+    }
+
+    fn input_mut(&mut self, position: CursorPosition) -> Option<&mut TextInputState> {
+        match position {
+            CursorPosition::StartInput => Some(&mut self.start_input),
+            CursorPosition::EndInput => Some(&mut self.end_input),
+            _ => None,
+        }
+    }
+}
+
+impl Popup<FreezePopupState> for FreezePopup {
+    type Action = FreezePopupAction;
+
+    fn handle_event(event: &Event, state: &mut FreezePopupState) -> Option<Self::Action> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        let position = state.cursor_position;
+        match key_event.code {
+            KeyCode::Esc => return Some(FreezePopupAction::Exit),
+            KeyCode::Enter => {
+                let (start, end) = state.validated_range()?;
+                return Some(match position {
+                    CursorPosition::UnfreezeButton => FreezePopupAction::Unfreeze(start, end),
+                    _ => FreezePopupAction::Freeze(start, end),
+                });
+            }
+            KeyCode::Tab | KeyCode::Down => {
+                state.cursor_position = focus::next(&FOCUS_ORDER, position)
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                state.cursor_position = focus::previous(&FOCUS_ORDER, position)
+            }
+            KeyCode::Left | KeyCode::Right if state.input_mut(position).is_none() => {
+                if let Some(ButtonRowEvent::Focus(index)) =
+                    state.button_row().handle_key(key_event.code)
+                {
+                    state.cursor_position = BUTTONS[index];
+                }
+            }
+            _ => {
+                if let Some(input) = state.input_mut(position) {
+                    if input.handle_key(*key_event) {
+                        state.error = None;
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn desired_size(&self, state: &FreezePopupState, _area: &Rect) -> (u16, u16) {
+        let message_width = state.error.as_deref().unwrap_or(HINT).width() as u16;
+        // +6: Borders, and padding of two columns on each side.
+        let width = (message_width + 6).max(FREEZE_POPUP_WIDTH);
+        // Borders, both inputs, the hint and the buttons, with a blank line
+        // above the inputs, the hint and the buttons.
+        (width, 9)
+    }
+}
+
+impl StatefulWidget for FreezePopup {
+    type State = FreezePopupState;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title("  Freeze Days  ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title_alignment(Alignment::Center)
+            .style(Style::default());
+        block.render(area, buffer);
+        if area.height < 9 || area.width < 12 {
+            return;
+        }
+
+        // Below the top border and a blank line.
+        let inputs = [
+            ("from", CursorPosition::StartInput),
+            ("to", CursorPosition::EndInput),
+        ];
+        for (row, (label, position)) in inputs.into_iter().enumerate() {
+            let y = area.y + 2 + row as u16;
+            buffer.set_string(area.x + 2, y, label, Style::default().fg(Color::Gray));
+            // +1: Space after the widest label.
+            let input_x = area.x + 2 + "from".width() as u16 + 1;
+            let input_area = Rect::new(input_x, y, area.right() - 2 - input_x, 1);
+            let focused = state.cursor_position == position;
+            buffer.set_style(
+                input_area,
+                Style::default().bg(if focused {
+                    Color::Black
+                } else {
+                    Color::DarkGray
+                }),
+            );
+            let text_area = Rect::new(input_area.x + 1, y, input_area.width.saturating_sub(2), 1);
+            if let Some(input) = state.input_mut(position) {
+                TextInput::default()
+                    .focused(focused)
+                    .render(text_area, buffer, input);
+            }
+        }
+
+        let (message, style) = match &state.error {
+            Some(error) => (error.as_str(), Style::default().fg(Color::Red)),
+            None => (HINT, Style::default().fg(Color::Gray)),
+        };
+        Block::default()
+            .title(message)
+            .title_alignment(Alignment::Center)
+            .style(style)
+            .render(Rect::new(area.x + 2, area.y + 5, area.width - 4, 1), buffer);
+
+        // Above the bottom border, below a blank line.
+        let buttons_area = Rect::new(area.x + 2, area.bottom() - 2, area.width - 4, 1);
+        state.button_row().render(buttons_area, buffer);
+    }
+}
@@ -0,0 +1,117 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    dates::parse_date,
+    heatmap::CalendarDate,
+    popup::Popup,
+    text_input::{TextInput, TextInputState},
+};
+
+// Width of the popup, unless the hint or an error needs more room.
+const GOTO_DATE_POPUP_WIDTH: u16 = 50;
+const HINT: &str = "(2024-03-05, 5 mar, -14 days, or mon)";
+
+#[derive(Default)]
+pub struct GotoDatePopup {}
+
+pub enum GotoDatePopupAction {
+    Goto(CalendarDate),
+    Exit,
+}
+
+/**
+ * State for a popup going to a date typed into it.
+ */
+pub struct GotoDatePopupState {
+    input: TextInputState,
+    // Date relative dates are from.
+    today: CalendarDate,
+    // Error displayed when the input isn't a date.
+    error: Option<String>,
+}
+
+impl GotoDatePopupState {
+    pub fn new(today: CalendarDate) -> Self {
+        Self {
+            input: TextInputState::default().placeholder("Enter a date"),
+            today,
+            error: None,
+        }
+    }
+}
+
+impl Popup<GotoDatePopupState> for GotoDatePopup {
+    type Action = GotoDatePopupAction;
+
+    fn handle_event(event: &Event, state: &mut GotoDatePopupState) -> Option<Self::Action> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        match key_event.code {
+            KeyCode::Esc => Some(GotoDatePopupAction::Exit),
+            KeyCode::Enter => match parse_date(state.input.text(), state.today) {
+                Ok(date) => Some(GotoDatePopupAction::Goto(date)),
+                Err(error) => {
+                    state.error = Some(error);
+                    None
+                }
+            },
+            _ => {
+                if state.input.handle_key(*key_event) {
+                    state.error = None;
+                }
+                None
+            }
+        }
+    }
+
+    fn desired_size(&self, state: &GotoDatePopupState, _area: &Rect) -> (u16, u16) {
+        let message_width = state.error.as_deref().unwrap_or(HINT).width() as u16;
+        // +6: Borders, and padding of two columns on each side.
+        let width = (message_width + 6).max(GOTO_DATE_POPUP_WIDTH);
+        // Borders, and the input and hint with a blank line above each.
+        (width, 6)
+    }
+}
+
+impl StatefulWidget for GotoDatePopup {
+    type State = GotoDatePopupState;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title("  Go to Date  ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title_alignment(Alignment::Center)
+            .style(Style::default());
+        block.render(area, buffer);
+        if area.height < 6 || area.width < 6 {
+            return;
+        }
+
+        // Below the top border and a blank line.
+        let input_area = Rect::new(area.x + 2, area.y + 2, area.width - 4, 1);
+        buffer.set_style(input_area, Style::default().bg(Color::Black));
+        let text_area = Rect::new(input_area.x + 1, input_area.y, input_area.width - 2, 1);
+        TextInput::default()
+            .focused(true)
+            .render(text_area, buffer, &mut state.input);
+
+        let (message, style) = match &state.error {
+            Some(error) => (error.as_str(), Style::default().fg(Color::Red)),
+            None => (HINT, Style::default().fg(Color::Gray)),
+        };
+        Block::default()
+            .title(message)
+            .title_alignment(Alignment::Center)
+            .style(style)
+            .render(Rect::new(area.x + 2, area.y + 4, area.width - 4, 1), buffer);
+    }
+}
@@ -1,18 +1,26 @@
 #![allow(dead_code)]
-use std::collections::HashMap;
+use std::{borrow::Cow, io, path::Path};
 
-use chrono::{Datelike, Days, NaiveDate};
+use chrono::{Datelike, Days, NaiveDate, Weekday};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::Color,
+    style::{Color, Modifier, Style},
     symbols::{bar::HALF, line::VERTICAL},
     text::Text,
     widgets::{Paragraph, Widget},
 };
+use unicode_width::UnicodeWidthStr;
 
 pub type CalendarDate = NaiveDate;
 
+const UPPER_HALF: &str = "▀";
+const LEFT_HALF: &str = "▌";
+// Background of frozen days without heat.
+const FROZEN_COLOR: Color = Color::Indexed(24);
+// Drawn either side of today's cell.
+const TODAY_MARKERS: (&str, &str) = ("[", "]");
+
 // TODO: It would be nice to have HeatMapCell(u16, u16) and then functions:
 // HeatMapCell -> (x, y)
 // HeatMapCell -> CalendarDate
@@ -28,15 +36,127 @@ pub enum HeatMapTileScale {
     Day,
 }
 
+/**
+ * How many days are drawn in each terminal cell of the heatmap.
+ */
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum HeatMapDensity {
+    // One day per cell, with borders between months.
+    Single,
+    // Two vertically adjacent days per cell, drawn with the foreground and
+    // background colors of an upper half block. Month borders are not drawn.
+    Double,
+}
+
 /**
  * The range of colors displayed in the heatmap.
  */
-pub struct HeatMapColorRange(Color, Color);
+#[derive(Clone, Copy)]
+pub struct HeatMapColorRange(pub Color, pub Color);
+
+impl HeatMapColorRange {
+    /**
+     * The color of a heat, linearly interpolated between the low and high
+     * colors across the heat range. Heats outside of the range are clamped.
+     *
+     * This is shared by all renderings of the heatmap, so that they agree.
+     */
+    pub fn color(&self, heat: f32, heat_range: &HeatMapHeatRange) -> Color {
+        let span = heat_range.1 - heat_range.0;
+        let t = if span > 0.0 {
+            ((heat - heat_range.0) / span).clamp(0.0, 1.0)
+        } else if heat > heat_range.0 {
+            1.0
+        } else {
+            0.0
+        };
+
+        // The exact endpoints are kept so that named colors follow the terminal's theme.
+        if t == 0.0 {
+            return self.0;
+        }
+        if t == 1.0 {
+            return self.1;
+        }
+        let (low, high) = (color_to_rgb(self.0), color_to_rgb(self.1));
+        let lerp = |low: u8, high: u8| (low as f32 + (high as f32 - low as f32) * t).round() as u8;
+        Color::Rgb(
+            lerp(low.0, high.0),
+            lerp(low.1, high.1),
+            lerp(low.2, high.2),
+        )
+    }
+}
+
+/**
+ * An approximate RGB value for a terminal color.
+ */
+pub fn color_to_rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Reset | Color::Black => (0, 0, 0),
+        Color::Red => (128, 0, 0),
+        Color::Green => (0, 128, 0),
+        Color::Yellow => (128, 128, 0),
+        Color::Blue => (0, 0, 128),
+        Color::Magenta => (128, 0, 128),
+        Color::Cyan => (0, 128, 128),
+        Color::Gray => (192, 192, 192),
+        Color::DarkGray => (128, 128, 128),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (0, 0, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(index) => indexed_color_to_rgb(index),
+    }
+}
+
+/**
+ * RGB value of a color in the xterm 256 color palette.
+ */
+fn indexed_color_to_rgb(index: u8) -> (u8, u8, u8) {
+    const ANSI: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::Gray,
+        Color::DarkGray,
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+        Color::LightMagenta,
+        Color::LightCyan,
+        Color::White,
+    ];
+    match index {
+        0..=15 => color_to_rgb(ANSI[index as usize]),
+        16..=231 => {
+            // 6x6x6 color cube.
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        232..=255 => {
+            // Grayscale ramp.
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
 
 /**
  * The range of dates displayed in the heatmap.
  */
-struct HeatMapDateRange(CalendarDate, CalendarDate);
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct HeatMapDateRange(pub CalendarDate, pub CalendarDate);
 
 impl HeatMapDateRange {
     /**
@@ -50,10 +170,24 @@ impl HeatMapDateRange {
         Self(one_year_ago, today)
     }
 
+    /**
+     * The given number of days before `end`, up to and including `end`.
+     */
+    pub fn days_ending_on(days: u64, end: CalendarDate) -> Self {
+        let start = end.checked_sub_days(Days::new(days)).unwrap();
+        Self(start, end)
+    }
+
     pub fn current_year() -> Self {
-        let today = chrono::Local::now().date_naive();
-        let start_of_year = NaiveDate::from_ymd_opt(today.year(), 1, 1).unwrap();
-        let end_of_year = NaiveDate::from_ymd_opt(today.year(), 12, 31).unwrap();
+        Self::year_of(chrono::Local::now().date_naive())
+    }
+
+    /**
+     * The year containing a date.
+     */
+    pub fn year_of(date: CalendarDate) -> Self {
+        let start_of_year = NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap();
+        let end_of_year = NaiveDate::from_ymd_opt(date.year(), 12, 31).unwrap();
         Self(start_of_year, end_of_year)
     }
 }
@@ -61,7 +195,8 @@ impl HeatMapDateRange {
 /**
  * The range of heat values displayed in the heatmap.
  */
-struct HeatMapHeatRange(f32, f32);
+#[derive(Clone, Copy)]
+pub struct HeatMapHeatRange(pub f32, pub f32);
 
 pub trait HeatMapValue {
     /**
@@ -75,36 +210,155 @@ pub trait HeatMapValue {
     fn heat_map_value(&self) -> f32;
 }
 
-pub struct HeatMap<'a, T: HeatMapValue> {
+/**
+ * The heat of each day from `start` onwards, or None for days without a
+ * value. Indexed by the number of days since `start`.
+ */
+pub fn dense_heats<'a, T: HeatMapValue + 'a>(
+    start: CalendarDate,
+    values: impl IntoIterator<Item = &'a T>,
+) -> Vec<Option<f32>> {
+    let mut heats = vec![];
+    for value in values {
+        let days_from_start = value
+            .heat_map_date()
+            .signed_duration_since(start)
+            .num_days();
+        if days_from_start < 0 {
+            continue;
+        }
+        let index = days_from_start as usize;
+        if heats.len() <= index {
+            heats.resize(index + 1, None);
+        }
+        // Multiple values on the same day accumulate.
+        *heats[index].get_or_insert(0.0) += value.heat_map_value();
+    }
+
+    heats
+}
+
+/**
+ * Totals over the days of the heatmap's date range that have passed.
+ */
+#[derive(Debug, PartialEq, Eq)]
+pub struct HeatMapSummary {
+    // Days with a positive heat.
+    pub active_days: u32,
+    // Days in the date range, up to and including today.
+    pub days: u32,
+    // Most consecutive active days.
+    pub longest_streak: u32,
+}
+
+impl HeatMapSummary {
+    pub fn percentage(&self) -> u32 {
+        if self.days == 0 {
+            return 0;
+        }
+        (self.active_days as f32 / self.days as f32 * 100.0).round() as u32
+    }
+}
+
+impl std::fmt::Display for HeatMapSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} active days / {} ({}%) · longest streak {}",
+            self.active_days,
+            self.days,
+            self.percentage(),
+            self.longest_streak
+        )
+    }
+}
+
+/**
+ * A second set of heats drawn alongside the heatmap's own, to compare two
+ * activities.
+ */
+struct HeatMapComparison<'a> {
+    heats: &'a [Option<f32>],
+    heats_start: CalendarDate,
+    heat_range: HeatMapHeatRange,
+    color_range: HeatMapColorRange,
+}
+
+pub struct HeatMap<'a> {
     // The range of dates displayed in the heatmap.
     date_range: HeatMapDateRange,
     // The range of heat values displayed in the heatmap.
     heat_range: HeatMapHeatRange,
     // The range of colors displayed in the heatmap.
     color_range: HeatMapColorRange,
-    // The number of rows in the heatmap.
+    // The color of days without a recorded value.
+    empty_color: Color,
+    // The symbols drawn for days with and without a recorded value.
+    filled_symbol: &'a str,
+    empty_symbol: &'a str,
+    // The number of rows (days per column) in the heatmap.
     rows: u16,
-    // Values to display in the heatmap.
-    values: HashMap<CalendarDate, &'a T>,
+    // The number of days drawn per cell.
+    density: HeatMapDensity,
+    // Heat of each day to display in the heatmap, starting at `heats_start`.
+    heats: Cow<'a, [Option<f32>]>,
+    heats_start: CalendarDate,
     // Flag for year display label.
     draw_year_label: bool,
+    // Drawn in place of the year label, e.g. to describe the date range.
+    caption: Option<String>,
+    // Flag for the summary line below the heatmap.
+    draw_summary: bool,
+    // Flag for the ISO week number row below the days.
+    draw_week_numbers: bool,
+    // Background color of weekend days without heat, if weekends are shaded.
+    weekend_color: Option<Color>,
+    // Heats compared against, drawn in the right half of each day.
+    comparison: Option<HeatMapComparison<'a>>,
+    // Names of the heats and the compared heats, shown in a legend.
+    legend: Option<(&'a str, &'a str)>,
+    // Date drawn highlighted, e.g. the date being viewed.
+    highlight_date: Option<CalendarDate>,
+    // Current date, which the summary counts up to and which is marked.
+    today: CalendarDate,
+    // Days of the week counted in the summary, or empty for every day.
+    schedule: Vec<Weekday>,
+    // Date ranges (inclusive) drawn as frozen, which the summary's streaks
+    // skip over.
+    frozen: Vec<(CalendarDate, CalendarDate)>,
 }
 
-impl<'a, T: HeatMapValue> Default for HeatMap<'a, T> {
+impl<'a> Default for HeatMap<'a> {
     fn default() -> Self {
+        let date_range = HeatMapDateRange::current_year();
         Self {
-            date_range: HeatMapDateRange::current_year(),
-            heat_range: HeatMapHeatRange(0.0, 255.0),
-            color_range: HeatMapColorRange(Color::Black, Color::Green),
+            heats_start: date_range.0,
+            date_range,
+            heat_range: HeatMapHeatRange(0.0, 1.0),
+            color_range: HeatMapColorRange(Color::DarkGray, Color::Green),
+            empty_color: Color::Black,
+            filled_symbol: HALF,
+            empty_symbol: HALF,
             rows: 7,
-            values: HashMap::new(),
+            density: HeatMapDensity::Single,
+            heats: Cow::Owned(vec![]),
             draw_year_label: false,
+            caption: None,
+            draw_summary: false,
+            draw_week_numbers: false,
+            weekend_color: None,
+            comparison: None,
+            legend: None,
+            highlight_date: None,
+            today: chrono::Local::now().date_naive(),
+            schedule: vec![],
+            frozen: vec![],
         }
     }
 }
 
 // Constructor helpers.
-impl<'a, T: HeatMapValue> HeatMap<'a, T> {
+impl<'a> HeatMap<'a> {
     pub fn new() -> Self {
         Self::default()
     }
@@ -124,113 +378,728 @@ impl<'a, T: HeatMapValue> HeatMap<'a, T> {
         self
     }
 
+    pub fn empty_color(mut self, empty_color: Color) -> Self {
+        self.empty_color = empty_color;
+        self
+    }
+
+    /**
+     * The symbol drawn for days with a recorded value. Symbols that aren't
+     * one column wide are ignored, keeping the previous symbol.
+     */
+    pub fn filled_symbol(mut self, symbol: &'a str) -> Self {
+        if symbol.width() == 1 {
+            self.filled_symbol = symbol;
+        }
+        self
+    }
+
+    /**
+     * The symbol drawn for days without a recorded value, e.g. "·". Symbols
+     * that aren't one column wide are ignored, keeping the previous symbol.
+     */
+    pub fn empty_symbol(mut self, symbol: &'a str) -> Self {
+        if symbol.width() == 1 {
+            self.empty_symbol = symbol;
+        }
+        self
+    }
+
+    /**
+     * Shade the background of weekend days that have no heat.
+     */
+    pub fn shade_weekends(mut self, shade_weekends: bool) -> Self {
+        self.weekend_color = if shade_weekends {
+            Some(Color::Indexed(236))
+        } else {
+            None
+        };
+        self
+    }
+
+    pub fn weekend_color(mut self, weekend_color: Color) -> Self {
+        self.weekend_color = Some(weekend_color);
+        self
+    }
+
     pub fn rows(mut self, rows: u16) -> Self {
-        self.rows = rows;
+        // At least one row is needed to lay out the days.
+        self.rows = rows.max(1);
+        self
+    }
+
+    pub fn density(mut self, density: HeatMapDensity) -> Self {
+        self.density = density;
+        self
+    }
+
+    /**
+     * Draw the day at the date highlighted, if it is in the date range.
+     */
+    pub fn highlight_date(mut self, date: CalendarDate) -> Self {
+        self.highlight_date = Some(date);
+        self
+    }
+
+    /**
+     * The current date, for when it differs from the calendar date.
+     */
+    pub fn today(mut self, today: CalendarDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /**
+     * Label the columns with the ISO week number of their first day, in a row
+     * below the days. The labels line up with weeks when there are seven rows
+     * and the date range starts on a Monday.
+     */
+    pub fn week_numbers(mut self, draw_week_numbers: bool) -> Self {
+        self.draw_week_numbers = draw_week_numbers;
+        self
+    }
+
+    /**
+     * Draw a caption at the bottom left of the heatmap, in place of the year
+     * label.
+     */
+    pub fn caption(mut self, caption: String) -> Self {
+        self.caption = Some(caption);
+        self.draw_year_label = true;
+        self
+    }
+
+    /**
+     * Draw a line of totals for the date range below the heatmap.
+     */
+    pub fn summary(mut self, draw_summary: bool) -> Self {
+        self.draw_summary = draw_summary;
+        self
+    }
+
+    /**
+     * Only count days on the given days of the week in the summary, unless
+     * they have heat. Other days don't break streaks.
+     */
+    pub fn schedule(mut self, schedule: Vec<Weekday>) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /**
+     * Draw days in the given ranges without heat as frozen (e.g. while on
+     * vacation). They neither break nor extend streaks in the summary.
+     */
+    pub fn frozen(mut self, frozen: Vec<(CalendarDate, CalendarDate)>) -> Self {
+        self.frozen = frozen;
+        self
+    }
+
+    fn is_frozen(&self, date: CalendarDate) -> bool {
+        self.frozen
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&date))
+    }
+
+    pub fn values<T: HeatMapValue>(mut self, values: Vec<&T>) -> Self {
+        if let Some(start) = values.iter().map(|value| value.heat_map_date()).min() {
+            self.heats_start = start;
+            self.heats = Cow::Owned(dense_heats(start, values));
+        }
         self
     }
 
-    pub fn values(mut self, values: Vec<&'a T>) -> Self {
-        self.values = values.into_iter().map(|v| (v.heat_map_date(), v)).collect();
+    /**
+     * Precomputed heats (see `dense_heats`) for the days starting at `start`.
+     */
+    pub fn heats(mut self, start: CalendarDate, heats: &'a [Option<f32>]) -> Self {
+        self.heats_start = start;
+        self.heats = Cow::Borrowed(heats);
+        self
+    }
+
+    /**
+     * Compare against precomputed heats for the days starting at `start`. Each
+     * day is split in two: the left half shows the heatmap's heats and the
+     * right half the compared heats. Only drawn in single density.
+     */
+    pub fn compare(mut self, start: CalendarDate, heats: &'a [Option<f32>]) -> Self {
+        self.comparison = Some(HeatMapComparison {
+            heats,
+            heats_start: start,
+            heat_range: HeatMapHeatRange(0.0, 1.0),
+            color_range: HeatMapColorRange(Color::DarkGray, Color::Magenta),
+        });
+        self
+    }
+
+    pub fn comparison_heat_range(mut self, low_heat: f32, high_heat: f32) -> Self {
+        if let Some(comparison) = &mut self.comparison {
+            comparison.heat_range = HeatMapHeatRange(low_heat, high_heat);
+        }
+        self
+    }
+
+    pub fn comparison_color_range(mut self, low_heat_color: Color, high_heat_color: Color) -> Self {
+        if let Some(comparison) = &mut self.comparison {
+            comparison.color_range = HeatMapColorRange(low_heat_color, high_heat_color);
+        }
+        self
+    }
+
+    /**
+     * Names of the heats and the compared heats, shown in a legend below the
+     * heatmap when comparing.
+     */
+    pub fn legend(mut self, name: &'a str, comparison_name: &'a str) -> Self {
+        self.legend = Some((name, comparison_name));
         self
     }
 }
 
-impl<'a, T: HeatMapValue> HeatMap<'a, T> {
-    fn draw_month_labels(&self, area: &Rect, buffer: &mut Buffer) {
+impl<'a> HeatMap<'a> {
+    /**
+     * The month labels of the heatmap, paired with the x offset (relative to
+     * the left of the heatmap) of the leftmost column starting in that month.
+     *
+     * Labels are shortened so that they don't run into the next label, and
+     * are dropped when there is no room for them at all.
+     */
+    fn month_labels(&self) -> Vec<(u16, String)> {
+        let labels = self.unfitted_month_labels();
+        labels
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (x, month_name))| {
+                let room = match labels.get(i + 1) {
+                    // Leave a space before the next label.
+                    Some((next_x, _)) => next_x.saturating_sub(x + 1) as usize,
+                    None => month_name.len(),
+                };
+                if room == 0 {
+                    return None;
+                }
+                Some((*x, month_name.chars().take(room).collect()))
+            })
+            .collect()
+    }
+
+    fn unfitted_month_labels(&self) -> Vec<(u16, String)> {
+        let mut labels = vec![];
         let mut date = self.date_range.0;
         let mut last_display_month = -1;
-        while date < self.date_range.1 {
+        // Step through the first day of each column.
+        while date <= self.date_range.1 {
             let month = date.month() as i32;
 
             if last_display_month != month {
-                /*
-                 * Display the current month starting at the top of the
-                 * heatmap starting at the leftmost column starting at that
-                 * month.
-                 */
-                let (x, _) = self.date_to_position(date, area);
-                let y = area.y;
-
-                let month_name = date.format("%b").to_string();
-                let month_text = Paragraph::new(Text::raw(&month_name));
-                month_text.render(
-                    Rect::new(x, y, month_name.len().try_into().unwrap(), 1),
-                    buffer,
-                );
+                let days_from_start =
+                    date.signed_duration_since(self.date_range.0).num_days() as u16;
+                labels.push((
+                    days_from_start / self.rows * self.column_width(),
+                    date.format("%b").to_string(),
+                ));
                 last_display_month = month;
             }
 
             date = date.checked_add_days(Days::new(self.rows.into())).unwrap();
         }
+
+        labels
     }
 
-    /**
-     * Draw the starting year and ending year, if the ending year is different,
-     * at the bottom left of the heatmap.
-     */
-    fn draw_year_labels(&self, area: &Rect, buffer: &mut Buffer) {
+    fn draw_month_labels(&self, area: &Rect, buffer: &mut Buffer) {
+        for (x, month_name) in self.month_labels() {
+            /*
+             * Display the current month starting at the top of the
+             * heatmap starting at the leftmost column starting at that
+             * month.
+             */
+            // Labels running past the right of the area are cut off.
+            let clip = area.intersection(*buffer.area());
+            let x = area.x + x;
+            let width = (month_name.len() as u16).min(clip.right().saturating_sub(x));
+            let label_area = Rect::new(x, area.y, width, 1);
+            if !fits(&label_area, area, buffer) {
+                continue;
+            }
+            let month_text = Paragraph::new(Text::raw(&month_name));
+            month_text.render(label_area, buffer);
+        }
+    }
+
+    fn year_label(&self) -> String {
+        if let Some(caption) = &self.caption {
+            return caption.clone();
+        }
         let start_year = self.date_range.0.year();
         let end_year = self.date_range.1.year();
 
-        let year_text_str = if start_year == end_year {
+        if start_year == end_year {
             format!("Year: {}", start_year)
         } else {
             format!("Years: {} - {}", start_year, end_year)
-        };
+        }
+    }
+
+    /**
+     * The ISO week numbers of the heatmap, paired with the x offset (relative
+     * to the left of the heatmap) of the column they label. Each column is
+     * labelled with the week of its first day, and labels that would run into
+     * the previous label are dropped.
+     */
+    fn week_number_labels(&self) -> Vec<(u16, String)> {
+        let mut labels: Vec<(u16, String)> = vec![];
+        let mut date = self.date_range.0;
+        let mut x = 0;
+        while date <= self.date_range.1 {
+            // Leave a space after the previous label.
+            let room = labels
+                .last()
+                .is_none_or(|(last_x, label)| x > last_x + label.len() as u16);
+            if room {
+                labels.push((x, date.iso_week().week().to_string()));
+            }
+            x += self.column_width();
+            date = date.checked_add_days(Days::new(self.rows.into())).unwrap();
+        }
+
+        labels
+    }
+
+    fn draw_week_number_labels(&self, area: &Rect, buffer: &mut Buffer) {
+        // +1: Month labels.
+        let y = area.y + self.lines() + 1;
+        for (x, week_number) in self.week_number_labels() {
+            let label_area = Rect::new(area.x + x, y, week_number.len() as u16, 1);
+            if !fits(&label_area, area, buffer) {
+                continue;
+            }
+            buffer.set_string(
+                label_area.x,
+                y,
+                &week_number,
+                Style::default().fg(Color::DarkGray),
+            );
+        }
+    }
+
+    /**
+     * Offset of the first line below the days (and week numbers), relative to
+     * the top of the heatmap.
+     */
+    fn footer_y(&self) -> u16 {
+        // +1: Month labels.
+        self.lines() + 1 + u16::from(self.draw_week_numbers)
+    }
+
+    /**
+     * Draw the starting year and ending year, if the ending year is different,
+     * at the bottom left of the heatmap.
+     */
+    fn draw_year_labels(&self, area: &Rect, buffer: &mut Buffer) {
+        let year_text_str = self.year_label();
+
+        let label_area = Rect::new(
+            area.x,
+            area.y + self.footer_y(),
+            year_text_str.width() as u16,
+            1,
+        );
+        if !fits(&label_area, area, buffer) {
+            return;
+        }
 
         let year_text = Paragraph::new(Text::raw(&year_text_str));
-        year_text.render(
-            Rect::new(
-                area.x,
-                area.y + self.height() - 1,
-                year_text_str.len().try_into().unwrap(),
-                1,
+        year_text.render(label_area, buffer);
+    }
+
+    /**
+     * Totals over the days of the date range up to and including `today`.
+     */
+    pub fn summary_until(&self, today: CalendarDate) -> HeatMapSummary {
+        let mut summary = HeatMapSummary {
+            active_days: 0,
+            days: 0,
+            longest_streak: 0,
+        };
+        let mut streak = 0;
+        let mut date = self.date_range.0;
+        while date <= self.date_range.1.min(today) {
+            let scheduled = self.schedule.is_empty() || self.schedule.contains(&date.weekday());
+            let frozen = self.is_frozen(date);
+            if self.heat_at_date(date) > 0.0 {
+                summary.days += 1;
+                summary.active_days += 1;
+                if !frozen {
+                    streak += 1;
+                    summary.longest_streak = summary.longest_streak.max(streak);
+                }
+            } else if scheduled && !frozen {
+                summary.days += 1;
+                streak = 0;
+            }
+            date = date.checked_add_days(Days::new(1)).unwrap();
+        }
+
+        summary
+    }
+
+    /**
+     * The legend of a comparison, drawn after the year label, as the text of
+     * each entry paired with its color.
+     */
+    fn legend_entries(&self) -> Option<[(String, Color); 2]> {
+        let comparison = self.comparison.as_ref()?;
+        let (name, comparison_name) = self.legend?;
+        Some([
+            (format!("{} {}", LEFT_HALF, name), self.color_range.1),
+            (
+                format!("{} {}", LEFT_HALF, comparison_name),
+                comparison.color_range.1,
             ),
-            buffer,
+        ])
+    }
+
+    fn legend_x(&self) -> u16 {
+        if self.draw_year_label {
+            self.year_label().width() as u16 + 2
+        } else {
+            0
+        }
+    }
+
+    fn legend_width(&self) -> u16 {
+        match self.legend_entries() {
+            // +2: Space between the entries.
+            Some([first, second]) => {
+                self.legend_x() + first.0.width() as u16 + 2 + second.0.width() as u16
+            }
+            None => 0,
+        }
+    }
+
+    fn draw_legend(&self, area: &Rect, buffer: &mut Buffer) {
+        let entries = match self.legend_entries() {
+            Some(entries) => entries,
+            None => return,
+        };
+        let y = area.y + self.footer_y();
+        let mut x = area.x + self.legend_x();
+        for (text, color) in entries {
+            let label_area = Rect::new(x, y, text.width() as u16, 1);
+            if !fits(&label_area, area, buffer) {
+                return;
+            }
+            buffer.set_string(x, y, &text, Style::default());
+            buffer.get_mut(x, y).set_fg(color);
+            // +2: Space between the entries.
+            x += text.width() as u16 + 2;
+        }
+    }
+
+    fn summary_label(&self) -> String {
+        self.summary_until(self.today).to_string()
+    }
+
+    fn draw_summary_label(&self, area: &Rect, buffer: &mut Buffer) {
+        let summary = self.summary_label();
+        // +1: Year label.
+        let label_area = Rect::new(
+            area.x,
+            area.y + self.footer_y() + 1,
+            summary.width() as u16,
+            1,
         );
+        if !fits(&label_area, area, buffer) {
+            return;
+        }
+
+        let summary_text = Paragraph::new(Text::raw(&summary));
+        summary_text.render(label_area, buffer);
+    }
+
+    /**
+     * The heat at a given date, or None if no value was recorded on that date.
+     */
+    fn maybe_heat_at_date(&self, date: CalendarDate) -> Option<f32> {
+        heat_at_date(&self.heats, self.heats_start, date)
     }
 
     fn heat_at_date(&self, date: CalendarDate) -> f32 {
-        match self.values.get(&date) {
-            Some(value) => value.heat_map_value(),
-            None => 0.0,
-        }
+        self.maybe_heat_at_date(date).unwrap_or(0.0)
     }
 
     fn color_from_heat(&self, heat: f32) -> Color {
-        // TODO: LERP between the low and high colors.
-        if heat == 0.0 {
-            self.color_range.0
-        } else {
-            self.color_range.1
+        self.color_range.color(heat, &self.heat_range)
+    }
+
+    /**
+     * Render the heatmap as an SVG image, with one square per day, month
+     * labels, and a legend of the heat range. The output is deterministic.
+     */
+    pub fn to_svg(&self) -> String {
+        const CELL: u16 = 12;
+        const STEP: u16 = 14;
+        const LABEL_HEIGHT: u16 = 16;
+        const LEGEND_STEPS: u16 = 5;
+
+        let width = (self.columns() * STEP).max(LEGEND_STEPS * STEP + 80);
+        let grid_height = self.rows * STEP;
+        let height = LABEL_HEIGHT + grid_height + STEP + LABEL_HEIGHT;
+        let hex = |color: Color| {
+            let (r, g, b) = color_to_rgb(color);
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        };
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"10\">\n"
+        );
+
+        for (x, month_name) in self.month_labels() {
+            let x = x / self.column_width() * STEP;
+            svg.push_str(&format!(
+                "  <text x=\"{x}\" y=\"10\" fill=\"#808080\">{month_name}</text>\n"
+            ));
+        }
+
+        let mut date = self.date_range.0;
+        while date <= self.date_range.1 {
+            let days_from_start = date.signed_duration_since(self.date_range.0).num_days() as u16;
+            let x = days_from_start / self.rows * STEP;
+            let y = LABEL_HEIGHT + days_from_start % self.rows * STEP;
+            let heat = self.maybe_heat_at_date(date);
+            let color = match heat {
+                Some(heat) => self.color_from_heat(heat),
+                None => self.empty_color,
+            };
+            svg.push_str(&format!(
+                "  <rect x=\"{x}\" y=\"{y}\" width=\"{CELL}\" height=\"{CELL}\" rx=\"2\" \
+                 fill=\"{}\"><title>{}: {}</title></rect>\n",
+                hex(color),
+                date.format("%Y-%m-%d"),
+                heat.unwrap_or(0.0),
+            ));
+            date = date.checked_add_days(Days::new(1)).unwrap();
         }
+
+        // Legend: swatches spanning the heat range, from low to high.
+        let legend_y = LABEL_HEIGHT + grid_height + STEP;
+        svg.push_str(&format!(
+            "  <text x=\"0\" y=\"{}\" fill=\"#808080\">Less</text>\n",
+            legend_y + 10
+        ));
+        for i in 0..LEGEND_STEPS {
+            let heat = self.heat_range.0
+                + (self.heat_range.1 - self.heat_range.0) * i as f32 / (LEGEND_STEPS - 1) as f32;
+            svg.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{legend_y}\" width=\"{CELL}\" height=\"{CELL}\" rx=\"2\" fill=\"{}\"/>\n",
+                30 + i * STEP,
+                hex(self.color_from_heat(heat)),
+            ));
+        }
+        svg.push_str(&format!(
+            "  <text x=\"{}\" y=\"{}\" fill=\"#808080\">More</text>\n",
+            30 + LEGEND_STEPS * STEP + 4,
+            legend_y + 10
+        ));
+
+        svg.push_str("</svg>\n");
+        svg
     }
 
-    fn date_to_position(&self, date: CalendarDate, area: &Rect) -> (u16, u16) {
-        // Does not have spaces between days.
+    /**
+     * Write the heatmap to a file as an SVG image.
+     */
+    pub fn write_svg(&self, path: &Path) -> io::Result<()> {
+        std::fs::write(path, self.to_svg())
+    }
+
+    /**
+     * Number of days drawn in each cell.
+     */
+    fn days_per_cell(&self) -> u16 {
+        match self.density {
+            HeatMapDensity::Single => 1,
+            HeatMapDensity::Double => 2,
+        }
+    }
+
+    /**
+     * Number of cells each column of days occupies horizontally.
+     */
+    fn column_width(&self) -> u16 {
+        match self.density {
+            // Each day is followed by a cell for the month border.
+            HeatMapDensity::Single => 2,
+            HeatMapDensity::Double => 1,
+        }
+    }
+
+    /**
+     * Number of lines occupied by the days in a column.
+     */
+    fn lines(&self) -> u16 {
+        self.rows.div_ceil(self.days_per_cell())
+    }
+
+    /**
+     * The position of a date, or None if it is outside of the date range.
+     */
+    fn date_to_position(&self, date: CalendarDate, area: &Rect) -> Option<(u16, u16)> {
+        if date < self.date_range.0 || date > self.date_range.1 {
+            return None;
+        }
         let days_from_start = date.signed_duration_since(self.date_range.0).num_days() as u16;
-        let x = area.x + days_from_start / self.rows;
+        let x = area.x + days_from_start / self.rows * self.column_width();
         // We add one to the y coordinate to account for the month labels.
-        let y = area.y + 1 + days_from_start % self.rows;
-        assert!(self.position_to_date(2 * x, y, area) == date);
-        (x * 2, y)
+        let y = area.y + 1 + days_from_start % self.rows / self.days_per_cell();
+        debug_assert!(
+            self.density != HeatMapDensity::Single
+                || self.position_to_date(x, y, area) == Some(date)
+        );
+        Some((x, y))
     }
 
-    fn position_to_date(&self, x: u16, y: u16, area: &Rect) -> CalendarDate {
-        let days_from_start = (x - area.x) / 2 * self.rows + (y - area.y - 1); // -1 for month labels.
+    /**
+     * The date displayed at (x, y), or None if the position is left of or above
+     * the heatmap's days. When there are multiple days in a cell, the first is
+     * returned.
+     */
+    fn position_to_date(&self, x: u16, y: u16, area: &Rect) -> Option<CalendarDate> {
+        let column = x.checked_sub(area.x)? / self.column_width();
+        // -1 for month labels.
+        let row = y.checked_sub(area.y + 1)? * self.days_per_cell();
+        if row >= self.rows {
+            return None;
+        }
+        let days_from_start = column * self.rows + row;
         self.date_range
             .0
             .checked_add_days(Days::new(days_from_start.into()))
-            .unwrap()
+    }
+
+    /**
+     * The date of the day drawn at (x, y) when the heatmap is rendered in
+     * `area`, or None if there is no day there.
+     */
+    pub fn date_at(&self, x: u16, y: u16, area: &Rect) -> Option<CalendarDate> {
+        if x >= area.right() || y >= area.bottom() {
+            return None;
+        }
+        let date = self.position_to_date(x, y, area)?;
+        (date <= self.date_range.1).then_some(date)
     }
 
     fn draw_date(&self, date: CalendarDate, buffer: &mut Buffer, area: &Rect) {
-        let color = self.color_from_heat(self.heat_at_date(date));
-        let (x, y) = self.date_to_position(date, area);
+        let Some((x, y)) = self.date_to_position(date, area) else {
+            return;
+        };
+        if !visible(x, y, area, buffer) {
+            return;
+        }
+        // Days without a record are drawn distinctly from days recorded with zero heat.
+        let heat = self.maybe_heat_at_date(date);
+        let color = match heat {
+            Some(heat) => self.color_from_heat(heat),
+            None => self.empty_color,
+        };
         let cell = buffer.get_mut(x, y);
 
+        if self.density == HeatMapDensity::Double {
+            let days_from_start = date.signed_duration_since(self.date_range.0).num_days() as u16;
+            if (days_from_start % self.rows).is_multiple_of(2) {
+                // The first day of the cell is the upper half. Until the second
+                // day is drawn, the lower half is left blank.
+                cell.set_symbol(UPPER_HALF)
+                    .set_fg(color)
+                    .set_bg(Color::Reset);
+            } else {
+                cell.set_bg(color);
+            }
+            return;
+        }
+
+        if let Some(comparison) = &self.comparison {
+            // The heat is the left half block, and the compared heat the background.
+            let compared_heat = heat_at_date(comparison.heats, comparison.heats_start, date);
+            let compared_color = match compared_heat {
+                Some(heat) => comparison.color_range.color(heat, &comparison.heat_range),
+                None => self.empty_color,
+            };
+            cell.set_symbol(LEFT_HALF)
+                .set_fg(color)
+                .set_bg(compared_color);
+            return;
+        }
+
         cell.set_fg(color);
-        cell.set_symbol(HALF);
+        cell.set_symbol(match heat {
+            Some(_) => self.filled_symbol,
+            None => self.empty_symbol,
+        });
+
+        // Frozen days with heat keep their heat color.
+        if heat.unwrap_or(0.0) == 0.0 && self.is_frozen(date) {
+            cell.set_bg(FROZEN_COLOR);
+            return;
+        }
+
+        // Weekend days with heat keep their heat color.
+        if let Some(weekend_color) = self.weekend_color {
+            let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+            if weekend && heat.unwrap_or(0.0) == 0.0 {
+                cell.set_bg(weekend_color);
+            }
+        }
+    }
+
+    /**
+     * Invert the colors of the cell of a date. Only the day is highlighted,
+     * not the month border beside it.
+     */
+    fn draw_highlight(&self, date: CalendarDate, buffer: &mut Buffer, area: &Rect) {
+        let Some((x, y)) = self.date_to_position(date, area) else {
+            return;
+        };
+        if !visible(x, y, area, buffer) {
+            return;
+        }
+        let cell = buffer.get_mut(x, y);
+        cell.modifier.insert(Modifier::REVERSED);
+    }
+
+    /**
+     * Mark today's cell, whether or not it has heat, with brackets in the
+     * cells either side of it. A bracket isn't drawn over a month border.
+     * When days have no cells between them, today's cell is underlined
+     * instead.
+     */
+    fn draw_today_marker(&self, buffer: &mut Buffer, area: &Rect) {
+        let Some((x, y)) = self.date_to_position(self.today, area) else {
+            return;
+        };
+        if self.density == HeatMapDensity::Double {
+            if visible(x, y, area, buffer) {
+                buffer.get_mut(x, y).modifier.insert(Modifier::UNDERLINED);
+            }
+            return;
+        }
+        let (left, right) = TODAY_MARKERS;
+        let sides = [(x.checked_sub(1), left), (Some(x + 1), right)];
+        for (marker_x, marker) in sides {
+            let Some(marker_x) = marker_x else {
+                continue;
+            };
+            if !visible(marker_x, y, area, buffer) {
+                continue;
+            }
+            let cell = buffer.get_mut(marker_x, y);
+            if cell.symbol == " " {
+                cell.set_symbol(marker).set_fg(Color::Gray);
+            }
+        }
     }
 
     /**
@@ -240,9 +1109,20 @@ impl<'a, T: HeatMapValue> HeatMap<'a, T> {
      *       full character worth of space - you need to "merge" the characters).
      */
     fn draw_date_month_border(&self, date: CalendarDate, buffer: &mut Buffer, area: &Rect) {
-        let (x, y) = self.date_to_position(date, area);
+        if self.density == HeatMapDensity::Double {
+            return;
+        }
+        let Some((x, y)) = self.date_to_position(date, area) else {
+            return;
+        };
+        if !visible(x + 1, y, area, buffer) {
+            return;
+        }
         let current_month = date.month();
-        let next_col_day = self.position_to_date(x + 2, y, area);
+        let next_col_day = match self.position_to_date(x + 2, y, area) {
+            Some(next_col_day) => next_col_day,
+            None => return,
+        };
 
         if current_month != next_col_day.month() && next_col_day <= self.date_range.1 {
             let cell = buffer.get_mut(x + 1, y).set_fg(Color::Gray);
@@ -254,39 +1134,492 @@ impl<'a, T: HeatMapValue> HeatMap<'a, T> {
         }
     }
 
-    pub fn width(&self) -> u16 {
+    /**
+     * Number of columns of days, including a trailing partial column.
+     */
+    fn columns(&self) -> u16 {
         let days = self
             .date_range
             .1
             .signed_duration_since(self.date_range.0)
             .num_days() as u16
             + 1;
-        ((days + (self.rows - 1)) / self.rows) * 2
+        days.div_ceil(self.rows)
+    }
+
+    pub fn width(&self) -> u16 {
+        let grid_width = self.columns() * self.column_width();
+        // The last month label can run past the final column.
+        let month_labels_width = self
+            .month_labels()
+            .last()
+            .map_or(0, |(x, month_name)| x + month_name.len() as u16);
+        let year_label_width = if self.draw_year_label {
+            self.year_label().width() as u16
+        } else {
+            0
+        };
+        // The last week number can run past the final column.
+        let week_numbers_width = match self.draw_week_numbers {
+            true => self
+                .week_number_labels()
+                .last()
+                .map_or(0, |(x, week_number)| x + week_number.len() as u16),
+            false => 0,
+        };
+        let summary_width = if self.draw_summary {
+            self.summary_label().width() as u16
+        } else {
+            0
+        };
+
+        grid_width
+            .max(month_labels_width)
+            .max(year_label_width)
+            .max(self.legend_width())
+            .max(summary_width)
+            .max(week_numbers_width)
     }
 
     pub fn height(&self) -> u16 {
-        self.rows + 2
+        // +1: Year label.
+        self.footer_y() + 1 + u16::from(self.draw_summary)
     }
 }
 
-impl<'a, T: HeatMapValue> Widget for HeatMap<'a, T> {
+impl<'a> Widget for HeatMap<'a> {
     /**
      * Draw the heatmap.
      */
     fn render(self, area: Rect, buffer: &mut Buffer) {
-        assert!(area.width >= self.width());
-        assert!(area.height >= self.height());
-
+        // Anything that falls outside of the area is clipped.
         let mut date = self.date_range.0;
         while date <= self.date_range.1 {
             self.draw_date(date, buffer, &area);
             self.draw_date_month_border(date, buffer, &area);
             date = date.checked_add_days(Days::new(1)).unwrap();
         }
+        self.draw_today_marker(buffer, &area);
+
+        if let Some(date) = self.highlight_date {
+            self.draw_highlight(date, buffer, &area);
+        }
 
         self.draw_month_labels(&area, buffer);
+        if self.draw_week_numbers {
+            self.draw_week_number_labels(&area, buffer);
+        }
         if self.draw_year_label {
             self.draw_year_labels(&area, buffer);
         }
+        self.draw_legend(&area, buffer);
+        if self.draw_summary {
+            self.draw_summary_label(&area, buffer);
+        }
+    }
+}
+
+/**
+ * The heat at a given date in heats starting at `start`, or None if no value
+ * was recorded on that date.
+ */
+fn heat_at_date(heats: &[Option<f32>], start: CalendarDate, date: CalendarDate) -> Option<f32> {
+    let days_from_start = date.signed_duration_since(start).num_days();
+    if days_from_start < 0 {
+        return None;
+    }
+    heats.get(days_from_start as usize).copied().flatten()
+}
+
+/**
+ * Whether the cell at (x, y) lies within both the area and the buffer.
+ */
+fn visible(x: u16, y: u16, area: &Rect, buffer: &Buffer) -> bool {
+    let clip = area.intersection(*buffer.area());
+    x >= clip.left() && x < clip.right() && y >= clip.top() && y < clip.bottom()
+}
+
+/**
+ * Whether the label area lies entirely within both the area and the buffer.
+ */
+fn fits(label_area: &Rect, area: &Rect, buffer: &Buffer) -> bool {
+    label_area.width > 0
+        && label_area.height > 0
+        && visible(label_area.left(), label_area.top(), area, buffer)
+        && visible(
+            label_area.right() - 1,
+            label_area.bottom() - 1,
+            area,
+            buffer,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Heat(CalendarDate, f32);
+
+    impl HeatMapValue for Heat {
+        fn heat_map_date(&self) -> CalendarDate {
+            self.0
+        }
+
+        fn heat_map_value(&self) -> f32 {
+            self.1
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> CalendarDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /**
+     * Render a heatmap into a buffer of exactly its size.
+     */
+    fn render(heatmap: HeatMap) -> Buffer {
+        let area = Rect::new(0, 0, heatmap.width(), heatmap.height());
+        let mut buffer = Buffer::empty(area);
+        heatmap.render(area, &mut buffer);
+        buffer
+    }
+
+    #[test]
+    fn includes_the_final_partial_column() {
+        // Ten days are a full column of seven and a partial column of three.
+        let last = Heat(date(2024, 1, 10), 1.0);
+        let heatmap = HeatMap::default()
+            .date_range(date(2024, 1, 1), date(2024, 1, 10))
+            .values(vec![&last]);
+        assert_eq!(heatmap.width(), 4);
+
+        let buffer = render(heatmap);
+        // The tenth day is the third of the second column.
+        let cell = buffer.get(2, 3);
+        assert_eq!(cell.symbol, HALF);
+        assert_eq!(cell.fg, Color::Green);
+    }
+
+    #[test]
+    fn includes_a_month_label_past_the_final_column() {
+        // The third column starts on the 3rd of February.
+        let heatmap = HeatMap::default().date_range(date(2024, 1, 20), date(2024, 2, 5));
+        assert_eq!(heatmap.width(), 7);
+
+        let buffer = render(heatmap);
+        let label: String = (4..7).map(|x| buffer.get(x, 0).symbol.as_str()).collect();
+        assert_eq!(label, "Feb");
+    }
+
+    #[test]
+    fn includes_the_year_label() {
+        let mut heatmap = HeatMap::default().date_range(date(2024, 1, 1), date(2024, 1, 10));
+        heatmap.draw_year_label = true;
+        assert_eq!(heatmap.width(), "Year: 2024".len() as u16);
+
+        let buffer = render(heatmap);
+        let label: String = (0..10).map(|x| buffer.get(x, 8).symbol.as_str()).collect();
+        assert_eq!(label, "Year: 2024");
+    }
+
+    #[test]
+    fn clips_to_an_undersized_area() {
+        let heatmap = HeatMap::default().date_range(date(2024, 1, 1), date(2024, 12, 31));
+        let area = Rect::new(0, 0, 10, 4);
+        let mut buffer = Buffer::empty(area);
+        heatmap.render(area, &mut buffer);
+
+        // The first days are drawn, those below and right of the area aren't.
+        assert_eq!(buffer.get(0, 1).symbol, HALF);
+        assert_eq!(buffer.get(8, 3).symbol, HALF);
+        let label: String = (0..3).map(|x| buffer.get(x, 0).symbol.as_str()).collect();
+        assert_eq!(label, "Jan");
+    }
+
+    #[test]
+    fn clips_to_a_buffer_smaller_than_the_area() {
+        let heatmap = HeatMap::default().date_range(date(2024, 1, 1), date(2024, 12, 31));
+        let mut buffer = Buffer::empty(Rect::new(2, 1, 3, 2));
+        heatmap.render(Rect::new(0, 0, 200, 20), &mut buffer);
+
+        // The first days of the second and third columns.
+        assert_eq!(buffer.get(2, 1).symbol, HALF);
+        assert_eq!(buffer.get(3, 1).symbol, " ");
+        assert_eq!(buffer.get(4, 2).symbol, HALF);
+    }
+
+    #[test]
+    fn clips_labels_that_dont_fit() {
+        let mut heatmap = HeatMap::default().date_range(date(2024, 1, 20), date(2024, 2, 5));
+        heatmap.draw_year_label = true;
+        // "Feb" starts at x = 4, and the year label is 10 wide.
+        let area = Rect::new(0, 0, 6, 9);
+        let mut buffer = Buffer::empty(area);
+        heatmap.render(area, &mut buffer);
+
+        // Month labels are cut off at the right of the area.
+        let top: String = (0..6).map(|x| buffer.get(x, 0).symbol.as_str()).collect();
+        assert_eq!(top, "Jan Fe");
+        let bottom: String = (0..6).map(|x| buffer.get(x, 8).symbol.as_str()).collect();
+        assert_eq!(bottom, "      ");
+    }
+
+    #[test]
+    fn has_no_date_outside_the_grid() {
+        let heatmap = HeatMap::default().date_range(date(2024, 1, 1), date(2024, 12, 31));
+        let area = Rect::new(10, 5, 20, 9);
+        assert_eq!(
+            heatmap.position_to_date(12, 6, &area),
+            Some(date(2024, 1, 8))
+        );
+        // Left of the area, on the month labels, and below the last row.
+        assert_eq!(heatmap.position_to_date(9, 6, &area), None);
+        assert_eq!(heatmap.position_to_date(0, 6, &area), None);
+        assert_eq!(heatmap.position_to_date(12, 5, &area), None);
+        assert_eq!(heatmap.position_to_date(12, 0, &area), None);
+        assert_eq!(heatmap.position_to_date(12, 13, &area), None);
+    }
+
+    #[test]
+    fn shades_only_weekend_rows() {
+        // The 1st of January 2024 is a Monday, so Saturday and Sunday are
+        // the last two rows.
+        let saturday = Heat(date(2024, 1, 6), 200.0);
+        let heatmap = HeatMap::default()
+            .date_range(date(2024, 1, 1), date(2024, 1, 14))
+            .values(vec![&saturday])
+            .weekend_color(Color::Blue);
+        let heat_color = heatmap.color_from_heat(200.0);
+        let buffer = render(heatmap);
+
+        for column in 0..2 {
+            for row in 0..7 {
+                let cell = buffer.get(column * 2, row + 1);
+                let completed_saturday = column == 0 && row == 5;
+                if row >= 5 && !completed_saturday {
+                    assert_eq!(cell.bg, Color::Blue);
+                } else {
+                    assert_eq!(cell.bg, Color::Reset);
+                }
+            }
+        }
+        // The completed weekend day keeps its heat color.
+        assert_eq!(buffer.get(0, 6).fg, heat_color);
+    }
+
+    #[test]
+    fn draws_two_days_per_cell_in_double_density() {
+        let heats = [
+            Heat(date(2024, 1, 1), 200.0),
+            Heat(date(2024, 1, 4), 100.0),
+            Heat(date(2024, 1, 7), 50.0),
+            Heat(date(2024, 1, 9), 0.0),
+        ];
+        let heatmap = HeatMap::default()
+            .date_range(date(2024, 1, 1), date(2024, 1, 14))
+            .values(heats.iter().collect())
+            .density(HeatMapDensity::Double);
+        assert_eq!((heatmap.width(), heatmap.height()), (3, 6));
+        let color = |heat| heatmap.color_from_heat(heat);
+        let (high, medium, low, zero) = (color(200.0), color(100.0), color(50.0), color(0.0));
+        let empty = heatmap.empty_color;
+        let buffer = render(heatmap);
+
+        // Each cell is the first day of a pair in the upper half (the
+        // foreground), and the second in the lower half (the background).
+        let cell = |x, y| {
+            let cell = buffer.get(x, y);
+            assert_eq!(cell.symbol, UPPER_HALF);
+            (cell.fg, cell.bg)
+        };
+        // 1st and 2nd, 3rd and 4th of January.
+        assert_eq!(cell(0, 1), (high, empty));
+        assert_eq!(cell(0, 2), (empty, medium));
+        // The 7th is the last day of the first column, alone in its cell.
+        assert_eq!(cell(0, 4), (low, Color::Reset));
+        // 8th and 9th of January, in the next column.
+        assert_eq!(cell(1, 1), (empty, zero));
+    }
+
+    #[test]
+    fn matches_the_svg_golden_file() {
+        let heats = [
+            Heat(date(2024, 1, 29), 0.0),
+            Heat(date(2024, 1, 31), 5.0),
+            Heat(date(2024, 2, 1), 10.0),
+            Heat(date(2024, 2, 6), 2.5),
+        ];
+        let heatmap = HeatMap::default()
+            .date_range(date(2024, 1, 29), date(2024, 2, 11))
+            .values(heats.iter().collect())
+            .heat_range(0.0, 10.0);
+        assert_eq!(heatmap.to_svg(), include_str!("../testdata/heatmap.svg"));
+    }
+
+    #[test]
+    fn shortens_month_labels_that_would_overlap() {
+        // The first column holds the 30th and 31st of January and the 1st of
+        // February, so February's label starts in the second column.
+        let heatmap = HeatMap::default()
+            .date_range(date(2024, 1, 30), date(2024, 4, 30))
+            .rows(3);
+        let width = heatmap.width();
+        let area = Rect::new(0, 0, width, heatmap.height());
+        // The buffer extends past the right of the area.
+        let mut buffer = Buffer::empty(Rect::new(0, 0, width + 10, area.height));
+        heatmap.render(area, &mut buffer);
+
+        let labels: String = (0..width)
+            .map(|x| buffer.get(x, 0).symbol.as_str())
+            .collect();
+        assert!(labels.starts_with("J Feb"));
+        // Each label is (the start of) a single month's name.
+        let months = ["Jan", "Feb", "Mar", "Apr"];
+        for label in labels.split_whitespace() {
+            assert!(
+                months.iter().any(|month| month.starts_with(label)),
+                "{label}"
+            );
+        }
+        assert_eq!(labels.split_whitespace().count(), 4);
+        for x in width..width + 10 {
+            assert_eq!(buffer.get(x, 0).symbol, " ");
+        }
+    }
+
+    #[test]
+    fn symbols_one_column_wide_are_used() {
+        let heatmap = HeatMap::new().filled_symbol("■").empty_symbol("·");
+        assert_eq!(heatmap.filled_symbol, "■");
+        assert_eq!(heatmap.empty_symbol, "·");
+    }
+
+    #[test]
+    fn symbols_not_one_column_wide_keep_the_previous_symbol() {
+        let heatmap = HeatMap::new()
+            .filled_symbol("■")
+            .filled_symbol("🔥")
+            .empty_symbol("··")
+            .empty_symbol("");
+        assert_eq!(heatmap.filled_symbol, "■");
+        assert_eq!(heatmap.empty_symbol, HALF);
+    }
+
+    #[test]
+    fn wide_symbols_render_without_panicking() {
+        let start = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let heats = [Some(1.0)];
+        let heatmap = HeatMap::new()
+            .date_range(start, end)
+            .heats(start, &heats)
+            .filled_symbol("🔥")
+            .empty_symbol("🌑");
+        let area = Rect::new(0, 0, heatmap.width(), heatmap.height());
+        let mut buffer = Buffer::empty(area);
+        heatmap.render(area, &mut buffer);
+        // The first day, below the month labels.
+        assert_eq!(buffer.get(0, 1).symbol, HALF);
+    }
+
+    #[test]
+    fn summarizes_a_leap_year() {
+        // A streak across the 29th of February.
+        let heats: Vec<Heat> = [(2, 27), (2, 28), (2, 29), (3, 1), (12, 31)]
+            .into_iter()
+            .map(|(month, day)| Heat(date(2024, month, day), 1.0))
+            .collect();
+        let heatmap = HeatMap::default()
+            .date_range(date(2024, 1, 1), date(2024, 12, 31))
+            .values(heats.iter().collect());
+
+        let summary = heatmap.summary_until(date(2025, 1, 1));
+        assert_eq!(
+            summary,
+            HeatMapSummary {
+                active_days: 5,
+                days: 366,
+                longest_streak: 4,
+            }
+        );
+        assert_eq!(summary.percentage(), 1);
+    }
+
+    #[test]
+    fn summarizes_the_elapsed_part_of_a_year() {
+        // Every day of January, and the days either side of the 1st of July.
+        let mut heats: Vec<Heat> = (1..=31).map(|day| Heat(date(2023, 1, day), 1.0)).collect();
+        heats.push(Heat(date(2023, 6, 30), 0.0));
+        heats.push(Heat(date(2023, 7, 1), 2.0));
+        heats.push(Heat(date(2023, 7, 2), 1.0));
+        let heatmap = HeatMap::default()
+            .date_range(date(2023, 1, 1), date(2023, 12, 31))
+            .values(heats.iter().collect());
+
+        // Days after the 1st of July haven't passed, and zero heat isn't active.
+        let summary = heatmap.summary_until(date(2023, 7, 1));
+        assert_eq!(
+            summary,
+            HeatMapSummary {
+                active_days: 32,
+                days: 182,
+                longest_streak: 31,
+            }
+        );
+        assert_eq!(summary.percentage(), 18);
+        assert_eq!(
+            summary.to_string(),
+            "32 active days / 182 (18%) · longest streak 31"
+        );
+
+        let summary = heatmap.summary_until(date(2022, 12, 31));
+        assert_eq!(summary.days, 0);
+        assert_eq!(summary.percentage(), 0);
+    }
+
+    /**
+     * The text of a row of a heatmap rendered into a buffer of its size.
+     */
+    fn row_text(heatmap: HeatMap, y: u16) -> String {
+        let width = heatmap.width();
+        let buffer = render(heatmap);
+        (0..width)
+            .map(|x| buffer.get(x, y).symbol.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn labels_columns_with_iso_week_numbers() {
+        // Mondays, from week 2 of 2024.
+        let heatmap = HeatMap::default()
+            .date_range(date(2024, 1, 8), date(2024, 2, 4))
+            .week_numbers(true);
+        assert_eq!(heatmap.height(), 10);
+        // Labels that would run into the previous label are dropped.
+        assert_eq!(row_text(heatmap, 8), "2 3 4 5 ");
+    }
+
+    #[test]
+    fn labels_weeks_across_the_end_of_a_year() {
+        // 2020 has 53 ISO weeks: the 28th of December starts week 53, and the
+        // 4th of January 2021 starts week 1.
+        let heatmap = HeatMap::default()
+            .date_range(date(2020, 12, 21), date(2021, 1, 24))
+            .week_numbers(true);
+        assert_eq!(
+            heatmap.week_number_labels(),
+            vec![
+                (0, String::from("52")),
+                (4, String::from("1")),
+                (6, String::from("2")),
+                (8, String::from("3")),
+            ]
+        );
+        assert_eq!(row_text(heatmap, 8), "52  1 2 3 ");
+
+        let heatmap = HeatMap::default()
+            .date_range(date(2020, 12, 28), date(2021, 1, 17))
+            .week_numbers(true);
+        assert_eq!(row_text(heatmap, 8), "53  2 ");
     }
 }
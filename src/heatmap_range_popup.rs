@@ -0,0 +1,291 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    dates::parse_date,
+    focus,
+    heatmap::{CalendarDate, HeatMapDateRange},
+    popup::Popup,
+    text_input::{TextInput, TextInputState},
+};
+
+// Width of the popup, unless the hint or an error needs more room.
+const HEATMAP_RANGE_POPUP_WIDTH: u16 = 50;
+const HINT: &str = "(↑/↓ choose, tab to enter dates, enter to apply)";
+
+/**
+ * Which dates the heatmap displays.
+ */
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum HeatMapRange {
+    // The year of the selected day, so that every year recorded can be
+    // viewed by going to it.
+    SelectedYear,
+    // The given number of days before today, up to and including today.
+    Days(u64),
+    // From the first day any activity was done, up to today.
+    AllTime,
+    Custom(CalendarDate, CalendarDate),
+}
+
+impl HeatMapRange {
+    pub fn label(self) -> String {
+        match self {
+            HeatMapRange::SelectedYear => String::from("Year of the selected day"),
+            HeatMapRange::Days(365) => String::from("Last 12 months"),
+            HeatMapRange::Days(91) => String::from("Last 13 weeks"),
+            HeatMapRange::Days(days) => format!("Last {} days", days),
+            HeatMapRange::AllTime => String::from("All time"),
+            HeatMapRange::Custom(start, end) => format!(
+                "{} to {}",
+                start.format("%-d %b %Y"),
+                end.format("%-d %b %Y")
+            ),
+        }
+    }
+}
+
+// Ranges that can be chosen without entering dates, followed in the popup by
+// a custom range.
+const PRESETS: [HeatMapRange; 4] = [
+    HeatMapRange::SelectedYear,
+    HeatMapRange::Days(365),
+    HeatMapRange::Days(91),
+    HeatMapRange::AllTime,
+];
+const CUSTOM_LABEL: &str = "Custom";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Ranges,
+    StartInput,
+    EndInput,
+}
+
+// Elements in the order Tab moves the focus through them.
+const FOCUS_ORDER: [Focus; 3] = [Focus::Ranges, Focus::StartInput, Focus::EndInput];
+
+#[derive(Default)]
+pub struct HeatMapRangePopup {}
+
+pub enum HeatMapRangePopupAction {
+    Select(HeatMapRange),
+    Exit,
+}
+
+/**
+ * State for a popup choosing the dates displayed in the heatmap.
+ */
+pub struct HeatMapRangePopupState {
+    focus: Focus,
+    // Index of the selected preset, or the number of presets for a custom
+    // range.
+    selected: usize,
+    start_input: TextInputState,
+    end_input: TextInputState,
+    // Date relative dates are from.
+    today: CalendarDate,
+    // Error displayed when a custom range isn't valid.
+    error: Option<String>,
+}
+
+impl HeatMapRangePopupState {
+    /**
+     * Initialize the popup with the current range selected, and its dates
+     * entered as the custom range.
+     */
+    pub fn new(range: HeatMapRange, dates: HeatMapDateRange, today: CalendarDate) -> Self {
+        let date_input = |date: CalendarDate| {
+            TextInputState::new(date.format("%Y-%m-%d").to_string()).placeholder("Enter a date")
+        };
+        Self {
+            focus: Focus::Ranges,
+            selected: PRESETS
+                .iter()
+                .position(|preset| *preset == range)
+                .unwrap_or(PRESETS.len()),
+            start_input: date_input(dates.0),
+            end_input: date_input(dates.1),
+            today,
+            error: None,
+        }
+    }
+
+    fn custom_selected(&self) -> bool {
+        self.selected == PRESETS.len()
+    }
+
+    /**
+     * The custom range entered, or None (setting an error) if it isn't valid.
+     */
+    fn validated_custom_range(&mut self) -> Option<HeatMapRange> {
+        let dates = parse_date(self.start_input.text(), self.today).and_then(|start| {
+            let end = parse_date(self.end_input.text(), self.today)?;
+            if start > end {
+                return Err(String::from("The range must start before it ends"));
+            }
+            Ok((start, end))
+        });
+        match dates {
+            Ok((start, end)) => Some(HeatMapRange::Custom(start, end)),
+            Err(error) => {
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+
+    fn input_mut(&mut self, focus: Focus) -> Option<&mut TextInputState> {
+        match focus {
+            Focus::StartInput => Some(&mut self.start_input),
+            Focus::EndInput => Some(&mut self.end_input),
+            Focus::Ranges => None,
+        }
+    }
+}
+
+impl Popup<HeatMapRangePopupState> for HeatMapRangePopup {
+    type Action = HeatMapRangePopupAction;
+
+    fn handle_event(event: &Event, state: &mut HeatMapRangePopupState) -> Option<Self::Action> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        match key_event.code {
+            KeyCode::Esc => return Some(HeatMapRangePopupAction::Exit),
+            KeyCode::Enter if state.custom_selected() => {
+                return state
+                    .validated_custom_range()
+                    .map(HeatMapRangePopupAction::Select)
+            }
+            KeyCode::Enter => {
+                return Some(HeatMapRangePopupAction::Select(PRESETS[state.selected]))
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                state.focus = match key_event.code {
+                    KeyCode::Tab => focus::next(&FOCUS_ORDER, state.focus),
+                    _ => focus::previous(&FOCUS_ORDER, state.focus),
+                };
+                // Dates are only entered for a custom range.
+                if state.focus != Focus::Ranges {
+                    state.selected = PRESETS.len();
+                }
+            }
+            KeyCode::Up if state.focus == Focus::Ranges => {
+                state.selected = state.selected.saturating_sub(1);
+                state.error = None;
+            }
+            KeyCode::Down if state.focus == Focus::Ranges => {
+                state.selected = (state.selected + 1).min(PRESETS.len());
+                state.error = None;
+            }
+            _ => {
+                let focus = state.focus;
+                if let Some(input) = state.input_mut(focus) {
+                    if input.handle_key(*key_event) {
+                        state.error = None;
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn desired_size(&self, state: &HeatMapRangePopupState, _area: &Rect) -> (u16, u16) {
+        let message_width = state.error.as_deref().unwrap_or(HINT).width() as u16;
+        // +6: Borders, and padding of two columns on each side.
+        let width = (message_width + 6).max(HEATMAP_RANGE_POPUP_WIDTH);
+        // Borders, the ranges, both inputs, and the hint, with a blank line
+        // above the ranges, the inputs and the hint.
+        (width, PRESETS.len() as u16 + 1 + 8)
+    }
+}
+
+impl StatefulWidget for HeatMapRangePopup {
+    type State = HeatMapRangePopupState;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title("  Heatmap Range  ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title_alignment(Alignment::Center)
+            .style(Style::default());
+        block.render(area, buffer);
+        let ranges = PRESETS.len() as u16 + 1;
+        if area.height < ranges + 8 || area.width < 12 {
+            return;
+        }
+
+        // Below the top border and a blank line.
+        let list_area = Rect::new(area.x + 2, area.y + 2, area.width - 4, ranges);
+        let labels = PRESETS
+            .iter()
+            .map(|preset| preset.label())
+            .chain([String::from(CUSTOM_LABEL)]);
+        for (row, label) in labels.enumerate() {
+            let y = list_area.y + row as u16;
+            let selected = row == state.selected;
+            buffer.set_stringn(
+                list_area.x,
+                y,
+                format!("{} {}", if selected { "●" } else { "○" }, label),
+                list_area.width as usize,
+                Style::default(),
+            );
+            if selected && state.focus == Focus::Ranges {
+                buffer.set_style(
+                    Rect::new(list_area.x, y, list_area.width, 1),
+                    Style::default().add_modifier(Modifier::REVERSED),
+                );
+            }
+        }
+
+        // Below the ranges and a blank line.
+        let inputs_y = list_area.bottom() + 1;
+        for (row, (label, focus)) in [("from", Focus::StartInput), ("to", Focus::EndInput)]
+            .into_iter()
+            .enumerate()
+        {
+            let y = inputs_y + row as u16;
+            buffer.set_string(area.x + 2, y, label, Style::default().fg(Color::Gray));
+            // +1: Space after the widest label.
+            let input_x = area.x + 2 + "from".width() as u16 + 1;
+            let input_area = Rect::new(input_x, y, area.right() - 2 - input_x, 1);
+            let focused = state.focus == focus;
+            buffer.set_style(
+                input_area,
+                Style::default().bg(if focused {
+                    Color::Black
+                } else {
+                    Color::DarkGray
+                }),
+            );
+            let text_area = Rect::new(input_area.x + 1, y, input_area.width.saturating_sub(2), 1);
+            if let Some(input) = state.input_mut(focus) {
+                TextInput::default()
+                    .focused(focused)
+                    .render(text_area, buffer, input);
+            }
+        }
+
+        let (message, style) = match &state.error {
+            Some(error) => (error.as_str(), Style::default().fg(Color::Red)),
+            None => (HINT, Style::default().fg(Color::Gray)),
+        };
+        Block::default()
+            .title(message)
+            .title_alignment(Alignment::Center)
+            .style(style)
+            .render(
+                Rect::new(area.x + 2, inputs_y + 3, area.width - 4, 1),
+                buffer,
+            );
+    }
+}
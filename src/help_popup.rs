@@ -0,0 +1,179 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::popup::Popup;
+
+// Keys of the popup, displayed in its lower border.
+const HINTS: &str = " ↑/↓ scroll · esc: close ";
+const MORE_ABOVE: &str = " ▲ more ";
+const MORE_BELOW: &str = " ▼ more ";
+
+#[derive(Default)]
+pub struct HelpPopup {}
+
+pub enum HelpPopupAction {
+    Exit,
+}
+
+/**
+ * Keys listed under a heading, e.g. every key that changes the day under
+ * "Navigation".
+ */
+pub struct HelpSection {
+    pub title: String,
+    // Each key and what it does, e.g. "a: previous day".
+    pub lines: Vec<String>,
+}
+
+/**
+ * State for a popup listing every key, grouped into sections.
+ */
+pub struct HelpPopupState {
+    sections: Vec<HelpSection>,
+    // First line displayed, when there are more lines than fit.
+    scroll: usize,
+    // Number of lines displayed when the popup was last rendered.
+    visible_lines: usize,
+}
+
+impl HelpPopupState {
+    pub fn new(sections: Vec<HelpSection>) -> Self {
+        Self {
+            sections,
+            scroll: 0,
+            visible_lines: 0,
+        }
+    }
+
+    /**
+     * Every line of the popup and whether it is a heading. Sections are
+     * separated by a blank line.
+     */
+    fn lines(&self) -> Vec<(&str, bool)> {
+        let mut lines = vec![];
+        for (index, section) in self.sections.iter().enumerate() {
+            if index > 0 {
+                lines.push(("", false));
+            }
+            lines.push((section.title.as_str(), true));
+            lines.extend(section.lines.iter().map(|line| (line.as_str(), false)));
+        }
+        lines
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines().len().saturating_sub(self.visible_lines)
+    }
+
+    fn scroll_by(&mut self, lines: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(lines)
+            .min(self.max_scroll());
+    }
+}
+
+impl Popup<HelpPopupState> for HelpPopup {
+    type Action = HelpPopupAction;
+
+    fn handle_event(event: &Event, state: &mut HelpPopupState) -> Option<Self::Action> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        let page = state.visible_lines.max(1) as isize;
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => {
+                return Some(HelpPopupAction::Exit)
+            }
+            KeyCode::Up => state.scroll_by(-1),
+            KeyCode::Down => state.scroll_by(1),
+            KeyCode::PageUp => state.scroll_by(-page),
+            KeyCode::PageDown => state.scroll_by(page),
+            KeyCode::Home => state.scroll = 0,
+            KeyCode::End => state.scroll = state.max_scroll(),
+            _ => (),
+        }
+        None
+    }
+
+    fn desired_size(&self, state: &HelpPopupState, _area: &Rect) -> (u16, u16) {
+        let lines = state.lines();
+        let widest = lines
+            .iter()
+            .map(|(line, _)| line.width())
+            .max()
+            .unwrap_or(0)
+            .max(HINTS.width()) as u16;
+        // +6: Borders, and padding of two columns on each side.
+        // +4: Borders, and a blank line above and below the lines.
+        (widest + 6, lines.len() as u16 + 4)
+    }
+}
+
+impl StatefulWidget for HelpPopup {
+    type State = HelpPopupState;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title("  Help  ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title_alignment(Alignment::Center)
+            .style(Style::default());
+        block.render(area, buffer);
+        if area.width < 6 || area.height < 5 {
+            return;
+        }
+
+        // Within the borders, and a blank line above and below the lines.
+        let inner = Rect::new(area.x + 2, area.y + 2, area.width - 4, area.height - 4);
+        state.visible_lines = inner.height as usize;
+        state.scroll = state.scroll.min(state.max_scroll());
+
+        let lines = state.lines();
+        for (row, (line, heading)) in lines
+            .iter()
+            .skip(state.scroll)
+            .take(state.visible_lines)
+            .enumerate()
+        {
+            let style = if *heading {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            buffer.set_stringn(
+                inner.x,
+                inner.y + row as u16,
+                line,
+                inner.width as usize,
+                style,
+            );
+        }
+
+        let gray = Style::default().fg(Color::Gray);
+        if state.scroll > 0 {
+            buffer.set_string(area.x + 2, area.y, MORE_ABOVE, gray);
+        }
+        if state.scroll < state.max_scroll() {
+            buffer.set_string(area.x + 2, area.bottom() - 1, MORE_BELOW, gray);
+        }
+        let hints_width = HINTS.width() as u16;
+        if hints_width + MORE_BELOW.width() as u16 + 4 <= area.width {
+            buffer.set_string(
+                area.right() - hints_width - 2,
+                area.bottom() - 1,
+                HINTS,
+                gray,
+            );
+        }
+    }
+}
@@ -0,0 +1,38 @@
+/*!
+ * The stores behind Daila, and the terminal interface displaying them, so
+ * that activities can be read and recorded without the interface.
+ */
+pub mod activites;
+mod activity_picker_popup;
+mod activity_popup;
+mod activity_selector;
+pub mod backup;
+mod bar_chart;
+mod button_row;
+mod calendar_popup;
+mod confirmation_popup;
+pub mod daila;
+mod dates;
+pub mod file;
+mod focus;
+mod freeze_popup;
+mod goto_date_popup;
+pub mod heatmap;
+mod heatmap_range_popup;
+mod help_popup;
+pub mod lock;
+mod log_on_date_popup;
+pub mod markdown_import;
+mod month_view;
+mod note_editor_popup;
+mod number_prompt_popup;
+mod popup;
+pub mod preferences;
+pub mod report;
+mod search_popup;
+mod text_input;
+
+pub use activites::{ActivitiesStore, Activity, ActivityType, ActivityTypesStore};
+pub use dates::parse_date;
+pub use file::File;
+pub use heatmap::{HeatMap, HeatMapValue};
@@ -0,0 +1,56 @@
+use std::{
+    fs::{create_dir_all, File as FsFile, TryLockError},
+    io,
+    path::PathBuf,
+};
+
+use crate::file::data_dir;
+
+/**
+ * An advisory lock on the data directory, held for as long as Daila is
+ * running, so that two instances don't overwrite each other's changes.
+ *
+ * The lock is released by the OS when the process exits, even if it crashes,
+ * so a lock file left behind is never stale.
+ */
+pub struct InstanceLock {
+    file: FsFile,
+}
+
+impl InstanceLock {
+    pub fn path() -> PathBuf {
+        data_dir().join("daila.lock")
+    }
+
+    /**
+     * Acquire the lock. Fails with `ErrorKind::WouldBlock` if another instance
+     * of Daila holds it.
+     */
+    pub fn acquire() -> io::Result<Self> {
+        let path = Self::path();
+        create_dir_all(path.parent().unwrap())?;
+        let file = FsFile::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+
+        match file.try_lock() {
+            Ok(()) => Ok(Self { file }),
+            Err(TryLockError::WouldBlock) => Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                format!(
+                    "another instance of daila is already running (lock held on {})",
+                    path.display()
+                ),
+            )),
+            Err(TryLockError::Error(e)) => Err(e),
+        }
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
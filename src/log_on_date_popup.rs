@@ -0,0 +1,196 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    activites::ActivityId,
+    dates::parse_date,
+    heatmap::CalendarDate,
+    popup::Popup,
+    text_input::{TextInput, TextInputState},
+};
+
+// Width of the popup, unless the hint or an error needs more room.
+const LOG_ON_DATE_POPUP_WIDTH: u16 = 50;
+// Most activities listed at once.
+const MAX_LISTED: usize = 8;
+const HINT: &str = "(↑/↓ choose the activity, enter to log it)";
+
+#[derive(Default)]
+pub struct LogOnDatePopup {}
+
+pub enum LogOnDatePopupAction {
+    Log(ActivityId, CalendarDate),
+    Exit,
+}
+
+/**
+ * State for a popup completing an activity on a date typed into it.
+ */
+pub struct LogOnDatePopupState {
+    input: TextInputState,
+    // Each activity that can be logged, and its name.
+    activities: Vec<(ActivityId, String)>,
+    // Index of the selected activity.
+    selected: usize,
+    // Date relative dates are from.
+    today: CalendarDate,
+    // Error displayed when the input isn't a date.
+    error: Option<String>,
+}
+
+impl LogOnDatePopupState {
+    pub fn new(
+        date: CalendarDate,
+        today: CalendarDate,
+        activities: Vec<(ActivityId, String)>,
+    ) -> Self {
+        Self {
+            input: TextInputState::new(date.format("%Y-%m-%d").to_string())
+                .placeholder("Enter a date"),
+            activities,
+            selected: 0,
+            today,
+            error: None,
+        }
+    }
+
+    /**
+     * Select an activity, if it is listed.
+     */
+    pub fn select(mut self, activity_id: ActivityId) -> Self {
+        if let Some(index) = self
+            .activities
+            .iter()
+            .position(|(id, _)| *id == activity_id)
+        {
+            self.selected = index;
+        }
+        self
+    }
+
+    fn listed(&self) -> usize {
+        self.activities.len().min(MAX_LISTED)
+    }
+}
+
+impl Popup<LogOnDatePopupState> for LogOnDatePopup {
+    type Action = LogOnDatePopupAction;
+
+    fn handle_event(event: &Event, state: &mut LogOnDatePopupState) -> Option<Self::Action> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        match key_event.code {
+            KeyCode::Esc => return Some(LogOnDatePopupAction::Exit),
+            KeyCode::Up => state.selected = state.selected.saturating_sub(1),
+            KeyCode::Down => {
+                state.selected = (state.selected + 1).min(state.activities.len().saturating_sub(1))
+            }
+            KeyCode::Enter => {
+                let Some((activity_id, _)) = state.activities.get(state.selected) else {
+                    state.error = Some(String::from("No activities to log"));
+                    return None;
+                };
+                match parse_date(state.input.text(), state.today) {
+                    Ok(date) => return Some(LogOnDatePopupAction::Log(*activity_id, date)),
+                    Err(error) => state.error = Some(error),
+                }
+            }
+            _ => {
+                if state.input.handle_key(*key_event) {
+                    state.error = None;
+                }
+            }
+        }
+        None
+    }
+
+    fn desired_size(&self, state: &LogOnDatePopupState, _area: &Rect) -> (u16, u16) {
+        let message_width = state.error.as_deref().unwrap_or(HINT).width() as u16;
+        // +6: Borders, and padding of two columns on each side.
+        let width = (message_width + 6).max(LOG_ON_DATE_POPUP_WIDTH);
+        // Borders, the input, hint and activities with a blank line above
+        // each, and a blank line below the activities.
+        (width, state.listed().max(1) as u16 + 8)
+    }
+}
+
+impl StatefulWidget for LogOnDatePopup {
+    type State = LogOnDatePopupState;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title("  Log on Date  ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title_alignment(Alignment::Center)
+            .style(Style::default());
+        block.render(area, buffer);
+        if area.height < 9 || area.width < 6 {
+            return;
+        }
+
+        // Below the top border and a blank line.
+        let input_area = Rect::new(area.x + 2, area.y + 2, area.width - 4, 1);
+        buffer.set_style(input_area, Style::default().bg(Color::Black));
+        let text_area = Rect::new(input_area.x + 1, input_area.y, input_area.width - 2, 1);
+        TextInput::default()
+            .focused(true)
+            .render(text_area, buffer, &mut state.input);
+
+        let (message, style) = match &state.error {
+            Some(error) => (error.as_str(), Style::default().fg(Color::Red)),
+            None => (HINT, Style::default().fg(Color::Gray)),
+        };
+        Block::default()
+            .title(message)
+            .title_alignment(Alignment::Center)
+            .style(style)
+            .render(Rect::new(area.x + 2, area.y + 4, area.width - 4, 1), buffer);
+
+        // Below the hint and a blank line, above a blank line and the bottom
+        // border.
+        let list_area = Rect::new(area.x + 2, area.y + 6, area.width - 4, area.height - 8);
+        if state.activities.is_empty() {
+            buffer.set_stringn(
+                list_area.x,
+                list_area.y,
+                "No activities",
+                list_area.width as usize,
+                Style::default().fg(Color::Gray),
+            );
+            return;
+        }
+        let visible = (list_area.height as usize).min(MAX_LISTED);
+        // Scroll so that the selected activity is listed.
+        let first = (state.selected + 1).saturating_sub(visible);
+        for (row, (_, name)) in state
+            .activities
+            .iter()
+            .skip(first)
+            .take(visible)
+            .enumerate()
+        {
+            let y = list_area.y + row as u16;
+            buffer.set_stringn(
+                list_area.x,
+                y,
+                name,
+                list_area.width as usize,
+                Style::default(),
+            );
+            if first + row == state.selected {
+                buffer.set_style(
+                    Rect::new(list_area.x, y, list_area.width, 1),
+                    Style::default().add_modifier(Modifier::REVERSED),
+                );
+            }
+        }
+    }
+}
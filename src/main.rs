@@ -1,35 +1,281 @@
 use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
-use daila::Daila;
-use std::io;
+use daila::{
+    backup::Backup,
+    daila::{today, Daila},
+    file::data_dir,
+    heatmap::CalendarDate,
+    lock::InstanceLock,
+    markdown_import, parse_date,
+    preferences::Preferences,
+    report::{Report, ReportPeriod},
+    ActivitiesStore, ActivityTypesStore, File,
+};
+use std::{io, path::PathBuf};
 
 use ratatui::{backend::CrosstermBackend, Terminal};
 
-mod activites;
-mod activity_popup;
-mod activity_selector;
-mod confirmation_popup;
-mod daila;
-mod file;
-mod heatmap;
-mod popup;
+const USAGE: &str =
+    "usage: daila [--readonly | --export [PATH] | --restore PATH | --archive DAYS | \
+                     --unarchive | --print-data-dir | --import-markdown PATTERN | \
+                     --report week|month [--date DATE] | toggle NAME [--date DATE]]";
+
+fn exit_with_error(message: impl std::fmt::Display) -> ! {
+    eprintln!("daila: {}", message);
+    std::process::exit(1);
+}
+
+/**
+ * Write a backup of both stores to `path`, or a timestamped file in the
+ * current directory.
+ */
+fn export(path: Option<PathBuf>) {
+    let path = path.unwrap_or_else(Backup::default_path);
+    if let Err(e) = Backup::from_saved().write(&path) {
+        exit_with_error(format!("failed to write {}: {}", path.display(), e));
+    }
+    println!("Exported to {}", path.display());
+}
+
+/**
+ * Replace both stores with the contents of the backup at `path`.
+ */
+fn restore(path: PathBuf) {
+    // Don't overwrite the stores under a running instance.
+    let _lock = InstanceLock::acquire().unwrap_or_else(|e| exit_with_error(e));
+    let backup = Backup::read(&path)
+        .unwrap_or_else(|e| exit_with_error(format!("failed to read {}: {}", path.display(), e)));
+    println!(
+        "Restoring backup from {} (daila {})",
+        backup.created_at().format("%Y-%m-%d %H:%M:%S"),
+        backup.version()
+    );
+    backup.restore();
+}
+
+/**
+ * Move the activities done more than `days` days ago to the archive file.
+ */
+fn archive(days: u64) {
+    let _lock = InstanceLock::acquire().unwrap_or_else(|e| exit_with_error(e));
+    let cutoff = chrono::Local::now()
+        .date_naive()
+        .checked_sub_days(chrono::Days::new(days))
+        .unwrap_or_else(|| exit_with_error(format!("{} days ago is too long ago", days)));
+    let mut activities = ActivitiesStore::load();
+    let archived = activities.archive_before(cutoff);
+    activities.save();
+    println!(
+        "Archived {} activities done before {}",
+        archived,
+        cutoff.format("%Y-%m-%d")
+    );
+}
+
+/**
+ * Move every archived activity back to the activities file.
+ */
+fn unarchive() {
+    let _lock = InstanceLock::acquire().unwrap_or_else(|e| exit_with_error(e));
+    let mut activities = ActivitiesStore::load();
+    activities.unarchive();
+    activities.save();
+    println!("Moved every archived activity back to the activities file");
+}
+
+/**
+ * Complete the checked items of the markdown checklists matching `pattern`.
+ */
+fn import_markdown(pattern: PathBuf) {
+    let _lock = InstanceLock::acquire().unwrap_or_else(|e| exit_with_error(e));
+    let paths = markdown_import::find_files(&pattern).unwrap_or_else(|e| {
+        exit_with_error(format!("failed to read {}: {}", pattern.display(), e))
+    });
+    let mut activity_types = ActivityTypesStore::load();
+    let mut activities = ActivitiesStore::load();
+    let report = markdown_import::import(&paths, &mut activity_types, &mut activities);
+    activity_types.save();
+    activities.save();
+
+    for skipped in &report.skipped {
+        match skipped.line_number {
+            0 => eprintln!("{}: skipped: {}", skipped.path.display(), skipped.reason),
+            line_number => eprintln!(
+                "{}:{}: skipped: {}",
+                skipped.path.display(),
+                line_number,
+                skipped.reason
+            ),
+        }
+    }
+    if !report.created.is_empty() {
+        println!("Created activities: {}", report.created.join(", "));
+    }
+    println!(
+        "Imported {} completions from {} files",
+        report.imported,
+        paths.len()
+    );
+}
+
+/**
+ * The value of an optional trailing `--date DATE`.
+ */
+fn date_arg(args: &mut impl Iterator<Item = String>) -> Option<String> {
+    match args.next().as_deref() {
+        None => None,
+        Some("--date") => Some(args.next().unwrap_or_else(|| exit_with_error(USAGE))),
+        Some(_) => exit_with_error(USAGE),
+    }
+}
+
+/**
+ * The date entered with `--date`, or today.
+ */
+fn date_or_today(date: Option<String>) -> CalendarDate {
+    let today = today(Preferences::load().day_rollover_hours);
+    match date {
+        Some(date) => parse_date(&date, today).unwrap_or_else(|e| exit_with_error(e)),
+        None => today,
+    }
+}
+
+/**
+ * Mark the activity named `name` (ignoring case) done on a date, or today,
+ * unless it already is.
+ */
+fn toggle(name: &str, date: Option<String>) {
+    let _lock = InstanceLock::acquire().unwrap_or_else(|e| exit_with_error(e));
+    let date = date_or_today(date);
+    let activity_types = ActivityTypesStore::load();
+    let activity_type = activity_types
+        .activity_types()
+        .into_iter()
+        .find(|activity_type| activity_type.name.to_lowercase() == name.to_lowercase())
+        .unwrap_or_else(|| exit_with_error(format!("no activity named \"{}\"", name)));
+
+    let mut activities = ActivitiesStore::load();
+    if activities
+        .archived_before()
+        .is_some_and(|cutoff| date < cutoff)
+    {
+        activities.load_archive();
+    }
+    if activities.activity_count(date, activity_type.id) > 0 {
+        println!(
+            "{} is already done on {}",
+            activity_type.name,
+            date.format("%Y-%m-%d")
+        );
+        return;
+    }
+    // Activities with a value are done with their default quantity.
+    activities.add_activity(activity_type.completion(date));
+    activities.save();
+    println!(
+        "Marked {} done on {}",
+        activity_type.name,
+        date.format("%Y-%m-%d")
+    );
+}
+
+/**
+ * Print a report on the week or month ending on a date, or today.
+ */
+fn report(period: ReportPeriod, date: Option<String>) {
+    let end = date_or_today(date);
+    let activity_types = ActivityTypesStore::load();
+    let mut activities = ActivitiesStore::load();
+    if activities
+        .archived_before()
+        .is_some_and(|cutoff| period.start(end) < cutoff)
+    {
+        activities.load_archive();
+    }
+    print!("{}", Report::new(period, end, &activity_types, &activities));
+}
 
 fn main() -> Result<(), io::Error> {
+    let mut args = std::env::args().skip(1);
+    let mut readonly = false;
+    match args.next().as_deref() {
+        None => {}
+        Some("--readonly") => readonly = true,
+        Some("--export") => {
+            export(args.next().map(PathBuf::from));
+            return Ok(());
+        }
+        Some("--restore") => {
+            let path = args.next().unwrap_or_else(|| exit_with_error(USAGE));
+            restore(PathBuf::from(path));
+            return Ok(());
+        }
+        Some("--archive") => {
+            let days = args
+                .next()
+                .and_then(|days| days.parse().ok())
+                .unwrap_or_else(|| exit_with_error(USAGE));
+            archive(days);
+            return Ok(());
+        }
+        Some("--unarchive") => {
+            unarchive();
+            return Ok(());
+        }
+        Some("--import-markdown") => {
+            let pattern = args.next().unwrap_or_else(|| exit_with_error(USAGE));
+            import_markdown(PathBuf::from(pattern));
+            return Ok(());
+        }
+        Some("toggle") => {
+            let name = args.next().unwrap_or_else(|| exit_with_error(USAGE));
+            toggle(&name, date_arg(&mut args));
+            return Ok(());
+        }
+        Some("--report") => {
+            let period = args
+                .next()
+                .and_then(|period| ReportPeriod::parse(&period))
+                .unwrap_or_else(|| exit_with_error(USAGE));
+            report(period, date_arg(&mut args));
+            return Ok(());
+        }
+        Some("--print-data-dir") => {
+            println!("{}", data_dir().display());
+            return Ok(());
+        }
+        Some(_) => exit_with_error(USAGE),
+    }
+
+    // Refuse to start if another instance is running, before touching the
+    // terminal. Read-only instances never write, so they can run alongside
+    // another instance.
+    let _lock = if readonly {
+        None
+    } else {
+        Some(InstanceLock::acquire().unwrap_or_else(|e| exit_with_error(e)))
+    };
+
     // Setup.
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Run application.
-    Daila::new().run_daila(&mut terminal)?;
+    let result = Daila::new().readonly(readonly).run_daila(&mut terminal);
 
-    // Cleanup.
+    // Cleanup, even if the application failed, so the terminal is left usable.
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    Ok(())
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    result
 }
@@ -0,0 +1,327 @@
+/*!
+ * Importing activities from daily markdown checklists, one file per day:
+ *
+ * ```markdown
+ * # 2024-03-05
+ * - [x] Run
+ * - [ ] Read
+ * ```
+ *
+ * Each checked item completes the activity of that name on the file's date,
+ * which is taken from the file name or, failing that, its first heading.
+ */
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+
+use crate::{
+    activites::{ActivitiesStore, Activity, ActivityId, ActivityTypesStore},
+    heatmap::CalendarDate,
+};
+
+/**
+ * A line that wasn't imported, and why.
+ */
+pub struct SkippedLine {
+    pub path: PathBuf,
+    // Numbered from one, or zero for the whole file.
+    pub line_number: usize,
+    pub reason: String,
+}
+
+/**
+ * What an import changed, and what it couldn't import.
+ */
+#[derive(Default)]
+pub struct ImportReport {
+    // Number of completions added.
+    pub imported: usize,
+    // Names of the activity types created for items without one.
+    pub created: Vec<String>,
+    pub skipped: Vec<SkippedLine>,
+}
+
+/**
+ * A parsed checklist: its date, the names of its checked items, and each
+ * line that isn't a heading, item, or blank, with its number.
+ */
+struct Checklist {
+    date: CalendarDate,
+    checked: Vec<String>,
+    unparsed: Vec<(usize, String)>,
+}
+
+/**
+ * The first date (e.g. "2024-03-05") in some text.
+ */
+fn find_date(text: &str) -> Option<CalendarDate> {
+    const DATE_LENGTH: usize = "2024-03-05".len();
+    text.char_indices().find_map(|(index, _)| {
+        let candidate = text.get(index..index + DATE_LENGTH)?;
+        NaiveDate::parse_from_str(candidate, "%Y-%m-%d").ok()
+    })
+}
+
+/**
+ * A checklist item ("- [x] Run" or "* [ ] Read") as whether it is checked
+ * and its name, or None if the line isn't one.
+ */
+fn parse_item(line: &str) -> Option<(bool, &str)> {
+    let rest = line
+        .strip_prefix("- [")
+        .or_else(|| line.strip_prefix("* ["))?;
+    let mut chars = rest.chars();
+    let checked = match chars.next()? {
+        'x' | 'X' => true,
+        ' ' => false,
+        _ => return None,
+    };
+    let name = chars.as_str().strip_prefix(']')?.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some((checked, name))
+}
+
+fn parse_checklist(file_name: &str, contents: &str) -> Result<Checklist, String> {
+    let heading_date = || {
+        contents
+            .lines()
+            .find(|line| line.starts_with('#'))
+            .and_then(find_date)
+    };
+    let date = find_date(file_name)
+        .or_else(heading_date)
+        .ok_or_else(|| String::from("no date in the file name or first heading"))?;
+
+    let mut checked = vec![];
+    let mut unparsed = vec![];
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match parse_item(line) {
+            Some((true, name)) => checked.push(name.to_owned()),
+            Some((false, _)) => (),
+            None => unparsed.push((index + 1, line.to_owned())),
+        }
+    }
+    Ok(Checklist {
+        date,
+        checked,
+        unparsed,
+    })
+}
+
+/**
+ * Whether a file name matches a pattern, in which `*` matches any number of
+ * characters and `?` matches any single character.
+ */
+fn matches_pattern(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, _) => name.is_empty(),
+        (Some('*'), _) => {
+            matches_pattern(&pattern[1..], name)
+                || (!name.is_empty() && matches_pattern(pattern, &name[1..]))
+        }
+        (Some('?'), Some(_)) => matches_pattern(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => matches_pattern(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+/**
+ * The files a pattern refers to, sorted: every markdown file in a
+ * directory, or the files in a directory whose names match a pattern (e.g.
+ * "journal/2024-*.md").
+ */
+pub fn find_files(pattern: &Path) -> io::Result<Vec<PathBuf>> {
+    let (directory, file_pattern) = if pattern.is_dir() {
+        (pattern.to_path_buf(), String::from("*.md"))
+    } else {
+        let file_pattern = pattern
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let directory = match pattern.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        (directory, file_pattern)
+    };
+    let file_pattern: Vec<char> = file_pattern.chars().collect();
+
+    let mut files = vec![];
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let name: Vec<char> = name.to_string_lossy().chars().collect();
+        if path.is_file() && matches_pattern(&file_pattern, &name) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/**
+ * The activity type with a name, ignoring case, creating it if there isn't
+ * one.
+ */
+fn activity_type_named(
+    activity_types: &mut ActivityTypesStore,
+    name: &str,
+    report: &mut ImportReport,
+) -> ActivityId {
+    let existing = activity_types
+        .activity_types()
+        .into_iter()
+        .find(|activity_type| activity_type.name.to_lowercase() == name.to_lowercase())
+        .map(|activity_type| activity_type.id);
+    existing.unwrap_or_else(|| {
+        report.created.push(name.to_owned());
+        activity_types.create_new_activity(name.to_owned())
+    })
+}
+
+/**
+ * Complete the checked items of each checklist file on its date. Items
+ * already completed on the date are left as they are, so that importing a
+ * file again changes nothing. The archive is loaded if a date is archived.
+ */
+pub fn import(
+    paths: &[PathBuf],
+    activity_types: &mut ActivityTypesStore,
+    activities: &mut ActivitiesStore,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    for path in paths {
+        let skip = |line_number, reason| SkippedLine {
+            path: path.clone(),
+            line_number,
+            reason,
+        };
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                report.skipped.push(skip(0, e.to_string()));
+                continue;
+            }
+        };
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let checklist = match parse_checklist(&file_name, &contents) {
+            Ok(checklist) => checklist,
+            Err(reason) => {
+                report.skipped.push(skip(0, reason));
+                continue;
+            }
+        };
+        if activities
+            .archived_before()
+            .is_some_and(|cutoff| checklist.date < cutoff)
+        {
+            activities.load_archive();
+        }
+
+        for name in checklist.checked {
+            let activity_id = activity_type_named(activity_types, &name, &mut report);
+            if activities.activity_count(checklist.date, activity_id) == 0 {
+                let activity = activity_types.activity_type(activity_id).map_or_else(
+                    || Activity::new(activity_id, checklist.date),
+                    |activity_type| activity_type.completion(checklist.date),
+                );
+                activities.add_activity(activity);
+                report.imported += 1;
+            }
+        }
+        for (line_number, line) in checklist.unparsed {
+            report
+                .skipped
+                .push(skip(line_number, format!("not a checklist item: {}", line)));
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> CalendarDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_items() {
+        assert_eq!(parse_item("- [x] Run"), Some((true, "Run")));
+        assert_eq!(
+            parse_item("* [X]  Read a book "),
+            Some((true, "Read a book"))
+        );
+        assert_eq!(parse_item("- [ ] Read"), Some((false, "Read")));
+    }
+
+    #[test]
+    fn rejects_lines_that_arent_items() {
+        assert_eq!(parse_item("Run"), None);
+        assert_eq!(parse_item("- Run"), None);
+        assert_eq!(parse_item("- [y] Run"), None);
+        assert_eq!(parse_item("- [x Run"), None);
+        assert_eq!(parse_item("- [x]"), None);
+        assert_eq!(parse_item("- [x]   "), None);
+    }
+
+    #[test]
+    fn parses_a_checklist() {
+        let contents = "# Tuesday\n\n- [x] Run\n- [ ] Read\n* [X] Meditate\n";
+        let checklist = parse_checklist("2024-03-05.md", contents).unwrap();
+        assert_eq!(checklist.date, date(2024, 3, 5));
+        assert_eq!(checklist.checked, vec!["Run", "Meditate"]);
+        assert!(checklist.unparsed.is_empty());
+    }
+
+    #[test]
+    fn takes_the_date_from_the_first_heading() {
+        let contents = "# Journal for 2024-03-05\n- [x] Run\n";
+        let checklist = parse_checklist("tuesday.md", contents).unwrap();
+        assert_eq!(checklist.date, date(2024, 3, 5));
+        assert_eq!(checklist.checked, vec!["Run"]);
+    }
+
+    #[test]
+    fn prefers_the_date_in_the_file_name() {
+        let contents = "# 2024-03-06\n- [x] Run\n";
+        let checklist = parse_checklist("2024-03-05.md", contents).unwrap();
+        assert_eq!(checklist.date, date(2024, 3, 5));
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        let contents = "# 2024-03-05\n- [x] Run\nwent for a walk\n- [?] Read\n";
+        let checklist = parse_checklist("notes.md", contents).unwrap();
+        assert_eq!(checklist.checked, vec!["Run"]);
+        assert_eq!(
+            checklist.unparsed,
+            vec![
+                (3, String::from("went for a walk")),
+                (4, String::from("- [?] Read"))
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_a_file_without_a_date() {
+        let contents = "# Tuesday\n- [x] Run\n";
+        assert!(parse_checklist("tuesday.md", contents).is_err());
+        assert!(parse_checklist("2024-13-05.md", "- [x] Run\n").is_err());
+    }
+}
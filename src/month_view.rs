@@ -0,0 +1,156 @@
+use chrono::{Datelike, Days, Months};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::Text,
+    widgets::{Paragraph, Widget},
+};
+
+use crate::heatmap::{self, CalendarDate, HeatMapValue};
+
+// Width of each day cell.
+const CELL_WIDTH: u16 = 4;
+const WEEKDAY_LABELS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/**
+ * A calendar grid of a single month, with weeks as rows and days as columns.
+ * The heat of each day is displayed as the background of its cell.
+ */
+pub struct MonthView {
+    // Date highlighted in the calendar. The month containing it is displayed.
+    date: CalendarDate,
+    // Heat of each day of the month, starting at the first of the month.
+    heats: Vec<Option<f32>>,
+    // The colors of days with zero and non-zero heat.
+    low_heat_color: Color,
+    high_heat_color: Color,
+}
+
+impl Default for MonthView {
+    fn default() -> Self {
+        Self {
+            date: chrono::Local::now().date_naive(),
+            heats: vec![],
+            low_heat_color: Color::DarkGray,
+            high_heat_color: Color::Green,
+        }
+    }
+}
+
+// Constructor helpers.
+impl MonthView {
+    pub fn date(mut self, date: CalendarDate) -> Self {
+        self.date = date;
+        self
+    }
+
+    /**
+     * Heat of each day of the month, starting at its first day.
+     */
+    pub fn heats(mut self, heats: Vec<Option<f32>>) -> Self {
+        self.heats = heats;
+        self
+    }
+
+    pub fn values<T: HeatMapValue>(mut self, values: Vec<&T>) -> Self {
+        self.heats = heatmap::dense_heats(self.first_day(), values);
+        self
+    }
+
+    pub fn color_range(mut self, low_heat_color: Color, high_heat_color: Color) -> Self {
+        self.low_heat_color = low_heat_color;
+        self.high_heat_color = high_heat_color;
+        self
+    }
+}
+
+impl MonthView {
+    pub fn first_day(&self) -> CalendarDate {
+        self.date.with_day(1).unwrap()
+    }
+
+    pub fn last_day(&self) -> CalendarDate {
+        self.first_day()
+            .checked_add_months(Months::new(1))
+            .and_then(|date| date.pred_opt())
+            .unwrap()
+    }
+
+    /**
+     * Number of (possibly partial) weeks the month spans.
+     */
+    fn weeks(&self) -> u16 {
+        let leading_days = self.first_day().weekday().num_days_from_monday();
+        let days = self.last_day().day();
+        (leading_days + days).div_ceil(7) as u16
+    }
+
+    /**
+     * Position of the cell of a date in the month, relative to the top left
+     * of the calendar.
+     */
+    fn date_to_offset(&self, date: CalendarDate) -> (u16, u16) {
+        let leading_days = self.first_day().weekday().num_days_from_monday();
+        let index = (leading_days + date.day0()) as u16;
+        // +2: Month title and weekday labels.
+        ((index % 7) * CELL_WIDTH, index / 7 + 2)
+    }
+
+    fn heat_style(&self, date: CalendarDate) -> Style {
+        let heat = self.heats.get(date.day0() as usize).copied().flatten();
+        match heat {
+            Some(heat) if heat > 0.0 => Style::default().fg(Color::Black).bg(self.high_heat_color),
+            Some(_) => Style::default().bg(self.low_heat_color),
+            None => Style::default(),
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        7 * CELL_WIDTH
+    }
+
+    pub fn height(&self) -> u16 {
+        // +2: Month title and weekday labels.
+        self.weeks() + 2
+    }
+}
+
+impl Widget for MonthView {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let area = area.intersection(*buffer.area());
+        if area.width < self.width() || area.height < self.height() {
+            return;
+        }
+
+        let title = Paragraph::new(Text::raw(self.date.format("%B %Y").to_string()))
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::Yellow));
+        title.render(Rect::new(area.x, area.y, self.width(), 1), buffer);
+
+        for (i, label) in WEEKDAY_LABELS.iter().enumerate() {
+            buffer.set_string(
+                area.x + i as u16 * CELL_WIDTH + 1,
+                area.y + 1,
+                label,
+                Style::default().fg(Color::Gray),
+            );
+        }
+
+        let mut date = self.first_day();
+        while date <= self.last_day() {
+            let (x, y) = self.date_to_offset(date);
+            let mut style = self.heat_style(date);
+            if date == self.date {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            buffer.set_string(
+                area.x + x,
+                area.y + y,
+                format!(" {:>2} ", date.day()),
+                style,
+            );
+            date = date.checked_add_days(Days::new(1)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::popup::Popup;
+
+// Size of the popup, when there is room for it.
+const NOTE_EDITOR_POPUP_WIDTH: u16 = 64;
+const NOTE_EDITOR_POPUP_HEIGHT: u16 = 18;
+// Keys of the popup, displayed in its lower border.
+const HINTS: &str = " ctrl+s: save · esc: cancel ";
+
+#[derive(Default)]
+pub struct NoteEditorPopup {}
+
+pub enum NoteEditorPopupAction {
+    // The edited text, with lines joined by newlines.
+    Save(String),
+    Exit,
+}
+
+/**
+ * State for a popup editing text of several lines, e.g. a note about a day.
+ */
+pub struct NoteEditorPopupState {
+    title: String,
+    lines: Vec<String>,
+    // Line the cursor is on, and the byte index in it that characters are
+    // inserted at.
+    cursor_line: usize,
+    cursor: usize,
+    // First displayed row of the wrapped lines, when there are more rows than
+    // fit.
+    scroll: usize,
+}
+
+impl NoteEditorPopupState {
+    pub fn new(title: &str, text: &str) -> Self {
+        let lines: Vec<String> = text.split('\n').map(str::to_owned).collect();
+        Self {
+            title: format!("  {}  ", title),
+            cursor_line: lines.len() - 1,
+            cursor: lines[lines.len() - 1].len(),
+            lines,
+            scroll: 0,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn line(&self) -> &str {
+        &self.lines[self.cursor_line]
+    }
+
+    fn grapheme_before_cursor_len(&self) -> usize {
+        self.line()[..self.cursor]
+            .graphemes(true)
+            .next_back()
+            .map_or(0, str::len)
+    }
+
+    fn grapheme_after_cursor_len(&self) -> usize {
+        self.line()[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(0, str::len)
+    }
+
+    /**
+     * Move the cursor to a line, at the grapheme nearest the column the
+     * cursor is at on its current line.
+     */
+    fn move_to_line(&mut self, line: usize) {
+        let column = self.line()[..self.cursor].width();
+        self.cursor_line = line;
+        let mut cursor = 0;
+        for grapheme in self.line().graphemes(true) {
+            if self.line()[..cursor].width() + grapheme.width() > column {
+                break;
+            }
+            cursor += grapheme.len();
+        }
+        self.cursor = cursor;
+    }
+
+    fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char(c) => {
+                let cursor = self.cursor;
+                self.lines[self.cursor_line].insert(cursor, c);
+                self.cursor += c.len_utf8();
+            }
+            KeyCode::Enter => {
+                let cursor = self.cursor;
+                let rest = self.lines[self.cursor_line].split_off(cursor);
+                self.cursor_line += 1;
+                self.lines.insert(self.cursor_line, rest);
+                self.cursor = 0;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                let len = self.grapheme_before_cursor_len();
+                self.cursor -= len;
+                let cursor = self.cursor;
+                self.lines[self.cursor_line].replace_range(cursor..cursor + len, "");
+            }
+            // At the start of a line, join it to the line above.
+            KeyCode::Backspace if self.cursor_line > 0 => {
+                let line = self.lines.remove(self.cursor_line);
+                self.cursor_line -= 1;
+                self.cursor = self.line().len();
+                self.lines[self.cursor_line].push_str(&line);
+            }
+            KeyCode::Delete if self.cursor < self.line().len() => {
+                let len = self.grapheme_after_cursor_len();
+                let cursor = self.cursor;
+                self.lines[self.cursor_line].replace_range(cursor..cursor + len, "");
+            }
+            // At the end of a line, join the line below to it.
+            KeyCode::Delete if self.cursor_line + 1 < self.lines.len() => {
+                let line = self.lines.remove(self.cursor_line + 1);
+                self.lines[self.cursor_line].push_str(&line);
+            }
+            KeyCode::Left if self.cursor > 0 => self.cursor -= self.grapheme_before_cursor_len(),
+            KeyCode::Left if self.cursor_line > 0 => {
+                self.cursor_line -= 1;
+                self.cursor = self.line().len();
+            }
+            KeyCode::Right if self.cursor < self.line().len() => {
+                self.cursor += self.grapheme_after_cursor_len()
+            }
+            KeyCode::Right if self.cursor_line + 1 < self.lines.len() => {
+                self.cursor_line += 1;
+                self.cursor = 0;
+            }
+            KeyCode::Up if self.cursor_line > 0 => self.move_to_line(self.cursor_line - 1),
+            KeyCode::Down if self.cursor_line + 1 < self.lines.len() => {
+                self.move_to_line(self.cursor_line + 1)
+            }
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.line().len(),
+            _ => (),
+        }
+    }
+}
+
+/**
+ * Byte ranges of the rows a line is wrapped into at `width` columns, broken
+ * after the last space that fits where possible. Every byte of the line is
+ * in exactly one row, so that a cursor in the line is always displayed.
+ */
+fn wrap_line(line: &str, width: usize) -> Vec<(usize, usize)> {
+    let width = width.max(1);
+    let mut rows = vec![];
+    let mut start = 0;
+    let mut row_width = 0;
+    // End of the last space in the current row, if any.
+    let mut last_space = None;
+    for (index, grapheme) in line.grapheme_indices(true) {
+        if row_width + grapheme.width() > width && index > start {
+            let end = last_space.unwrap_or(index);
+            rows.push((start, end));
+            row_width = line[end..index].width();
+            start = end;
+            last_space = None;
+        }
+        row_width += grapheme.width();
+        if grapheme == " " {
+            last_space = Some(index + grapheme.len());
+        }
+    }
+    rows.push((start, line.len()));
+    rows
+}
+
+impl Popup<NoteEditorPopupState> for NoteEditorPopup {
+    type Action = NoteEditorPopupAction;
+
+    fn handle_event(event: &Event, state: &mut NoteEditorPopupState) -> Option<Self::Action> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        let control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        match key_event.code {
+            KeyCode::Esc => Some(NoteEditorPopupAction::Exit),
+            KeyCode::Char('s') if control => Some(NoteEditorPopupAction::Save(state.text())),
+            // Other shortcuts aren't text.
+            KeyCode::Char(_) if control || key_event.modifiers.contains(KeyModifiers::ALT) => None,
+            code => {
+                state.handle_key(code);
+                None
+            }
+        }
+    }
+
+    fn desired_size(&self, _state: &NoteEditorPopupState, _area: &Rect) -> (u16, u16) {
+        (NOTE_EDITOR_POPUP_WIDTH, NOTE_EDITOR_POPUP_HEIGHT)
+    }
+}
+
+impl StatefulWidget for NoteEditorPopup {
+    type State = NoteEditorPopupState;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title(state.title.as_str())
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title_alignment(Alignment::Center)
+            .style(Style::default());
+        block.render(area, buffer);
+        if area.width < 6 || area.height < 5 {
+            return;
+        }
+
+        // Within the borders, and a blank line above and below the text.
+        let inner = Rect::new(area.x + 2, area.y + 2, area.width - 4, area.height - 4);
+        let width = inner.width as usize;
+        // Every displayed row, as its line and byte range, and the row and
+        // column the cursor is displayed at.
+        let mut rows = vec![];
+        let mut cursor_row = 0;
+        let mut cursor_column = 0;
+        for (line_index, line) in state.lines.iter().enumerate() {
+            let wrapped = wrap_line(line, width);
+            let last = wrapped.len() - 1;
+            for (index, (start, end)) in wrapped.into_iter().enumerate() {
+                let contains_cursor = line_index == state.cursor_line
+                    && start <= state.cursor
+                    && (state.cursor < end || index == last);
+                if contains_cursor {
+                    cursor_row = rows.len();
+                    cursor_column = line[start..state.cursor].width().min(width - 1);
+                }
+                rows.push((line_index, start, end));
+            }
+        }
+
+        // Scroll so that the cursor is displayed.
+        let visible = inner.height as usize;
+        if cursor_row < state.scroll {
+            state.scroll = cursor_row;
+        } else if cursor_row >= state.scroll + visible {
+            state.scroll = cursor_row + 1 - visible;
+        }
+
+        for (y, (line_index, start, end)) in
+            rows.iter().skip(state.scroll).take(visible).enumerate()
+        {
+            buffer.set_stringn(
+                inner.x,
+                inner.y + y as u16,
+                &state.lines[*line_index][*start..*end],
+                width,
+                Style::default(),
+            );
+        }
+        let cursor_y = inner.y + (cursor_row - state.scroll) as u16;
+        buffer
+            .get_mut(inner.x + cursor_column as u16, cursor_y)
+            .set_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        let hints_width = HINTS.width() as u16;
+        if hints_width + 4 <= area.width {
+            buffer.set_string(
+                area.right() - hints_width - 2,
+                area.bottom() - 1,
+                HINTS,
+                Style::default().fg(Color::Gray),
+            );
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    button_row::{ButtonRow, ButtonRowEvent},
+    focus,
+    popup::Popup,
+    text_input::{TextInput, TextInputState},
+};
+
+// Width of the popup, unless the label or an error needs more room.
+const NUMBER_PROMPT_POPUP_WIDTH: u16 = 40;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum CursorPosition {
+    Input,
+    OkButton,
+    CancelButton,
+}
+
+// Elements in the order Tab moves the focus through them.
+const FOCUS_ORDER: [CursorPosition; 3] = [
+    CursorPosition::Input,
+    CursorPosition::OkButton,
+    CursorPosition::CancelButton,
+];
+
+// Buttons from left to right, and their labels.
+const BUTTONS: [CursorPosition; 2] = [CursorPosition::OkButton, CursorPosition::CancelButton];
+const BUTTON_LABELS: [&str; 2] = ["ok", "cancel"];
+
+#[derive(Default)]
+pub struct NumberPromptPopup {}
+
+pub enum NumberPromptPopupAction {
+    Accept(f32),
+    Cancel,
+}
+
+/**
+ * State for a popup asking for a number, e.g. the quantity of an activity
+ * done.
+ */
+pub struct NumberPromptPopupState {
+    // What the number is for, drawn above the input.
+    label: String,
+    cursor_position: CursorPosition,
+    input: TextInputState,
+    // Bounds the number must be within, if any.
+    min: Option<f32>,
+    max: Option<f32>,
+    // Error displayed when the input isn't a valid number.
+    error: Option<String>,
+}
+
+impl NumberPromptPopupState {
+    pub fn new(label: String, value: Option<f32>) -> Self {
+        Self {
+            label,
+            cursor_position: CursorPosition::Input,
+            input: TextInputState::new(value.map_or(String::new(), |value| value.to_string()))
+                .placeholder("Enter a number"),
+            min: None,
+            max: None,
+            error: None,
+        }
+    }
+
+    pub fn min(mut self, min: f32) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub fn max(mut self, max: f32) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    fn button_row(&self) -> ButtonRow<'static> {
+        ButtonRow::new(BUTTON_LABELS.to_vec()).selected(
+            BUTTONS
+                .iter()
+                .position(|button| *button == self.cursor_position),
+        )
+    }
+
+    /**
+     * The number entered, or None (setting an error) if it isn't a number
+     * within the bounds.
+     */
+    fn validated_value(&mut self) -> Option<f32> {
+        let input = self.input.text().trim();
+        let value = match input.parse::<f32>() {
+            Ok(value) if value.is_finite() => value,
+            _ if input.is_empty() => {
+                self.error = Some(String::from("Enter a number"));
+                return None;
+            }
+            _ => {
+                self.error = Some(format!("Not a number: {}", input));
+                return None;
+            }
+        };
+        if let Some(min) = self.min.filter(|min| value < *min) {
+            self.error = Some(format!("Must be at least {}", min));
+            return None;
+        }
+        if let Some(max) = self.max.filter(|max| value > *max) {
+            self.error = Some(format!("Must be at most {}", max));
+            return None;
+        }
+        Some(value)
+    }
+}
+
+impl Popup<NumberPromptPopupState> for NumberPromptPopup {
+    type Action = NumberPromptPopupAction;
+
+    fn handle_event(event: &Event, state: &mut NumberPromptPopupState) -> Option<Self::Action> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        let position = state.cursor_position;
+        match key_event.code {
+            KeyCode::Esc => return Some(NumberPromptPopupAction::Cancel),
+            KeyCode::Enter if position == CursorPosition::CancelButton => {
+                return Some(NumberPromptPopupAction::Cancel)
+            }
+            KeyCode::Enter => return state.validated_value().map(NumberPromptPopupAction::Accept),
+            KeyCode::Tab | KeyCode::Down => {
+                state.cursor_position = focus::next(&FOCUS_ORDER, position)
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                state.cursor_position = focus::previous(&FOCUS_ORDER, position)
+            }
+            KeyCode::Left | KeyCode::Right if position != CursorPosition::Input => {
+                if let Some(ButtonRowEvent::Focus(index)) =
+                    state.button_row().handle_key(key_event.code)
+                {
+                    state.cursor_position = BUTTONS[index];
+                }
+            }
+            // Only a number, with at most one decimal point, can be typed.
+            KeyCode::Char(c)
+                if !(c.is_ascii_digit() || c == '.' && !state.input.text().contains('.')) => {}
+            _ => {
+                if position == CursorPosition::Input && state.input.handle_key(*key_event) {
+                    state.error = None;
+                }
+            }
+        }
+        None
+    }
+
+    fn desired_size(&self, state: &NumberPromptPopupState, _area: &Rect) -> (u16, u16) {
+        let widest = state
+            .label
+            .width()
+            .max(state.error.as_deref().map_or(0, str::width)) as u16;
+        // +6: Borders, and padding of two columns on each side.
+        let width = (widest + 6).max(NUMBER_PROMPT_POPUP_WIDTH);
+        // Borders, and the label, input, error and buttons with a blank line
+        // above the label and the buttons.
+        (width, 8)
+    }
+}
+
+impl StatefulWidget for NumberPromptPopup {
+    type State = NumberPromptPopupState;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title("  Enter a Number  ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title_alignment(Alignment::Center)
+            .style(Style::default());
+        block.render(area, buffer);
+        if area.height < 8 || area.width < 8 {
+            return;
+        }
+
+        // Below the top border and a blank line.
+        let inner_width = area.width - 4;
+        buffer.set_stringn(
+            area.x + 2,
+            area.y + 2,
+            &state.label,
+            inner_width as usize,
+            Style::default(),
+        );
+
+        let input_area = Rect::new(area.x + 2, area.y + 3, inner_width, 1);
+        let input_focused = state.cursor_position == CursorPosition::Input;
+        buffer.set_style(
+            input_area,
+            Style::default().bg(if input_focused {
+                Color::Black
+            } else {
+                Color::DarkGray
+            }),
+        );
+        let text_area = Rect::new(input_area.x + 1, input_area.y, input_area.width - 2, 1);
+        TextInput::default()
+            .focused(input_focused)
+            .render(text_area, buffer, &mut state.input);
+
+        if let Some(error) = &state.error {
+            buffer.set_stringn(
+                area.x + 2,
+                area.y + 4,
+                error,
+                inner_width as usize,
+                Style::default().fg(Color::Red),
+            );
+        }
+
+        // Above the bottom border, below a blank line.
+        let buttons_area = Rect::new(area.x + 2, area.bottom() - 2, inner_width, 1);
+        state.button_row().render(buttons_area, buffer);
+    }
+}
@@ -1,8 +1,9 @@
 use crossterm::event::Event;
 use ratatui::{
     backend::Backend,
-    layout::{Constraint, Direction, Layout, Rect},
-    widgets::{Clear, StatefulWidget},
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Clear, Paragraph, StatefulWidget},
     Frame,
 };
 
@@ -14,48 +15,195 @@ pub trait Popup<S>: StatefulWidget<State = S> {
      * Handles an input event and update internal state.
      */
     fn handle_event(event: &Event, state: &mut S) -> Option<Self::Action>;
+
+    /**
+     * The width and height the popup needs to display its content, which may
+     * be more than the available `area`.
+     */
+    fn desired_size(&self, state: &S, area: &Rect) -> (u16, u16);
 }
 
+// Smallest size popups are rendered at. In a smaller area, a notice is
+// rendered instead.
+const MIN_POPUP_WIDTH: u16 = 30;
+const MIN_POPUP_HEIGHT: u16 = 5;
+const TOO_SMALL_NOTICE: &str = "Terminal too small";
+// Style of every cell of a popup, so that popups stand out from what is
+// behind them. Light text is set along with the dark background so that it
+// is readable whatever the terminal's colors are.
+const POPUP_STYLE: Style = Style {
+    fg: Some(Color::White),
+    bg: Some(Color::Indexed(236)),
+    add_modifier: Modifier::empty(),
+    sub_modifier: Modifier::empty(),
+};
+// Style of the cells a popup's shadow falls on, one cell below and to the
+// right of it.
+const SHADOW_STYLE: Style = Style {
+    fg: Some(Color::DarkGray),
+    bg: Some(Color::Black),
+    add_modifier: Modifier::empty(),
+    sub_modifier: Modifier::empty(),
+};
+
+/**
+ * Render a popup in the center of the area, at the size it desires, clamped
+ * to the area, on its own background and with a shadow. If the area is
+ * smaller than any popup, a one line notice is rendered in its place.
+ */
 pub fn render_in_frame<B: Backend, S, P: Popup<S>>(
     frame: &mut Frame<B>,
     area: &Rect,
-    width_percentage: u16,
-    height_percentage: u16,
     popup: P,
     state: &mut S,
 ) {
-    let area = centered_area(area, width_percentage, height_percentage);
-    frame.render_widget(Clear, area);
-    frame.render_stateful_widget(popup, area, state);
+    if area.width < MIN_POPUP_WIDTH || area.height < MIN_POPUP_HEIGHT {
+        let notice_area =
+            Rect::new(area.x, area.y + area.height / 2, area.width, 1).intersection(*area);
+        frame.render_widget(Clear, notice_area);
+        frame.render_widget(
+            Paragraph::new(TOO_SMALL_NOTICE)
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Red)),
+            notice_area,
+        );
+        return;
+    }
+    let (width, height) = popup.desired_size(state, area);
+    let width = width.max(MIN_POPUP_WIDTH).min(area.width);
+    let height = height.max(MIN_POPUP_HEIGHT).min(area.height);
+    let popup_area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    render_shadow(frame, area, &popup_area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Block::default().style(POPUP_STYLE), popup_area);
+    frame.render_stateful_widget(popup, popup_area, state);
 }
 
 /**
- * Create the frame for a centered popup.
+ * Darken the cells along the right and bottom of a popup, offset by one,
+ * that lie within the area.
  */
-pub fn centered_area(area: &Rect, width_percentage: u16, height_percentage: u16) -> Rect {
-    // Center vertically.
-    let popup_area = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(
-            [
-                Constraint::Percentage((100 - height_percentage) / 2),
-                Constraint::Percentage(height_percentage),
-                Constraint::Percentage((100 - height_percentage) / 2),
-            ]
-            .as_ref(),
-        )
-        .split(area.to_owned());
-
-    // Center horizontally.
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints(
-            [
-                Constraint::Percentage((100 - width_percentage) / 2),
-                Constraint::Percentage(width_percentage),
-                Constraint::Percentage((100 - width_percentage) / 2),
-            ]
-            .as_ref(),
-        )
-        .split(popup_area[1])[1]
+fn render_shadow<B: Backend>(frame: &mut Frame<B>, area: &Rect, popup_area: &Rect) {
+    let right = Rect::new(popup_area.right(), popup_area.y + 1, 1, popup_area.height);
+    let bottom = Rect::new(popup_area.x + 1, popup_area.bottom(), popup_area.width, 1);
+    for shadow in [right, bottom] {
+        if shadow.intersects(*area) {
+            frame.render_widget(
+                Block::default().style(SHADOW_STYLE),
+                shadow.intersection(*area),
+            );
+        }
+    }
+}
+
+// Columns and rows each popup in a stack is offset by from the one below.
+const STACK_OFFSET: (u16, u16) = (4, 2);
+
+/**
+ * The area a popup `depth` levels above the lowest in a stack is rendered in,
+ * offset down and to the right so that the popups below remain visible.
+ */
+pub fn inset(area: &Rect, depth: u16) -> Rect {
+    let x = (STACK_OFFSET.0 * depth).min(area.width);
+    let y = (STACK_OFFSET.1 * depth).min(area.height);
+    Rect::new(area.x + x, area.y + y, area.width - x, area.height - y)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use chrono::NaiveDate;
+    use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+    use super::*;
+    use crate::{
+        activity_popup::{ActivityPopup, ActivityPopupState},
+        calendar_popup::{CalendarPopup, CalendarPopupState},
+        confirmation_popup::{ConfirmationPopup, ConfirmationPopupState},
+        goto_date_popup::{GotoDatePopup, GotoDatePopupState},
+        help_popup::{HelpPopup, HelpPopupState, HelpSection},
+    };
+
+    /**
+     * The buffer of a terminal of the given size, with a popup rendered in it.
+     */
+    fn render<S, P: Popup<S>>(popup: P, state: &mut S, width: u16, height: u16) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| render_in_frame(frame, &frame.size(), popup, state))
+            .unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    fn row(buffer: &Buffer, y: u16) -> String {
+        (0..buffer.area.width)
+            .map(|x| buffer.get(x, y).symbol.as_str())
+            .collect()
+    }
+
+    /**
+     * Assert only the notice is rendered, on the middle row, for every size
+     * too small for popups.
+     */
+    fn assert_renders_notice<S, P: Popup<S> + Default>(state: &mut S) {
+        for (width, height, notice) in [
+            (10, 3, "Terminal t"),
+            (1, 1, "T"),
+            (29, 5, "     Terminal too small      "),
+            (30, 4, "      Terminal too small      "),
+        ] {
+            let buffer = render(P::default(), state, width, height);
+            for y in 0..height {
+                let expected = if y == height / 2 {
+                    notice.to_owned()
+                } else {
+                    " ".repeat(width as usize)
+                };
+                assert_eq!(row(&buffer, y), expected, "{}x{}", width, height);
+            }
+            assert_eq!(buffer.get(0, height / 2).fg, Color::Red);
+        }
+        // Large enough for a popup.
+        let buffer = render(P::default(), state, MIN_POPUP_WIDTH, MIN_POPUP_HEIGHT);
+        assert!(!row(&buffer, MIN_POPUP_HEIGHT / 2).contains(TOO_SMALL_NOTICE));
+    }
+
+    #[test]
+    fn renders_a_notice_in_place_of_activity_popups() {
+        assert_renders_notice::<_, ActivityPopup>(&mut ActivityPopupState::new_creator());
+    }
+
+    #[test]
+    fn renders_a_notice_in_place_of_calendar_popups() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let mut state = CalendarPopupState::new(date, BTreeSet::from([date]));
+        assert_renders_notice::<_, CalendarPopup>(&mut state);
+    }
+
+    #[test]
+    fn renders_a_notice_in_place_of_confirmation_popups() {
+        let mut state = ConfirmationPopupState::new(String::from("Quit without saving?"));
+        assert_renders_notice::<_, ConfirmationPopup>(&mut state);
+    }
+
+    #[test]
+    fn renders_a_notice_in_place_of_goto_date_popups() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        assert_renders_notice::<_, GotoDatePopup>(&mut GotoDatePopupState::new(today));
+    }
+
+    #[test]
+    fn renders_a_notice_in_place_of_help_popups() {
+        let mut state = HelpPopupState::new(vec![HelpSection {
+            title: String::from("Navigation"),
+            lines: vec![String::from("a: previous day")],
+        }]);
+        assert_renders_notice::<_, HelpPopup>(&mut state);
+    }
 }
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    activites::ActivityId,
+    file::{data_dir, File},
+};
+
+/**
+ * Choices remembered between sessions.
+ */
+#[derive(Serialize, Deserialize, Default)]
+pub struct Preferences {
+    // Activity selected when Daila was last saved.
+    #[serde(default)]
+    pub last_selected_activity: Option<ActivityId>,
+    // List activities that aren't done before those that are.
+    #[serde(default)]
+    pub incomplete_first: bool,
+    // Hours past midnight that each day ends at, so that activities done
+    // late at night count towards the previous day.
+    #[serde(default, deserialize_with = "deserialize_day_rollover_hours")]
+    pub day_rollover_hours: u32,
+}
+
+// Latest hour past midnight a day can end at.
+const MAX_DAY_ROLLOVER_HOURS: u32 = 23;
+
+/**
+ * Hours past midnight that each day ends at, clamped to within a day.
+ */
+fn deserialize_day_rollover_hours<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u32, D::Error> {
+    let hours = i64::deserialize(deserializer)?;
+    Ok(hours.clamp(0, MAX_DAY_ROLLOVER_HOURS.into()) as u32)
+}
+
+impl File for Preferences {
+    fn path() -> PathBuf {
+        data_dir().join("preferences.json")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day_rollover_hours(json: &str) -> u32 {
+        serde_json::from_str::<Preferences>(json)
+            .unwrap()
+            .day_rollover_hours
+    }
+
+    #[test]
+    fn loads_the_day_rollover_hours() {
+        assert_eq!(day_rollover_hours("{}"), 0);
+        assert_eq!(day_rollover_hours(r#"{"day_rollover_hours": 3}"#), 3);
+        assert_eq!(day_rollover_hours(r#"{"day_rollover_hours": 23}"#), 23);
+    }
+
+    #[test]
+    fn clamps_the_day_rollover_hours_to_a_day() {
+        assert_eq!(day_rollover_hours(r#"{"day_rollover_hours": 24}"#), 23);
+        assert_eq!(day_rollover_hours(r#"{"day_rollover_hours": 1000}"#), 23);
+        assert_eq!(day_rollover_hours(r#"{"day_rollover_hours": -2}"#), 0);
+    }
+}
@@ -0,0 +1,382 @@
+/*!
+ * Plain text reports of how activities went over a week or a month, e.g. to
+ * send as a notification:
+ *
+ * ```text
+ * Daila report: week 2024-03-04 to 2024-03-10
+ * Meditate: 5/7 (71%)
+ * Read: 2/3 (67%)
+ * Best day: 2024-03-06 (2 of 2 activities)
+ * Worst day: 2024-03-09 (0 of 2 activities)
+ * Overall: 7/10 (70%)
+ * ```
+ *
+ * Each line is a label, a colon, and a value, so that the report can be
+ * parsed. Percentages are "n/a" where there is nothing to divide by, and the
+ * best and worst day are "none" when there are no activities.
+ */
+use chrono::{Days, Months};
+
+use crate::{
+    activites::{ActivitiesStore, ActivityType, ActivityTypesStore},
+    heatmap::CalendarDate,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ReportPeriod {
+    Week,
+    Month,
+}
+
+impl ReportPeriod {
+    pub fn parse(period: &str) -> Option<Self> {
+        match period {
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    /**
+     * The first day of the period ending on `end`: six days before it, or
+     * the day after the same date a month before it.
+     */
+    pub fn start(self, end: CalendarDate) -> CalendarDate {
+        let start = match self {
+            Self::Week => end.checked_sub_days(Days::new(6)),
+            Self::Month => end
+                .checked_sub_months(Months::new(1))
+                .and_then(|date| date.succ_opt()),
+        };
+        start.unwrap_or(end)
+    }
+}
+
+/**
+ * How often an activity was done in the period, and how often it was meant
+ * to be.
+ */
+pub struct ActivityReport {
+    pub name: String,
+    pub done: usize,
+    pub goal: usize,
+}
+
+impl ActivityReport {
+    /**
+     * Completions towards the weekly target if the activity has one, spread
+     * over the days of the period that aren't frozen. Otherwise, days done
+     * out of the days it is scheduled on that aren't frozen.
+     */
+    fn new(
+        activity_type: &ActivityType,
+        activities: &ActivitiesStore,
+        start: CalendarDate,
+        end: CalendarDate,
+    ) -> Self {
+        let days = || {
+            start
+                .iter_days()
+                .take_while(move |date| *date <= end)
+                .filter(|date| !activities.is_frozen(*date))
+        };
+        let (done, goal) = match activity_type.weekly_target {
+            Some(target) => (
+                activities
+                    .activities_with_type_between(activity_type.id, start, end)
+                    .len(),
+                (target as usize * days().count()).div_ceil(7),
+            ),
+            None => (
+                activities.days_completed_between(activity_type.id, start, end),
+                days()
+                    .filter(|date| activity_type.scheduled_on(*date))
+                    .count(),
+            ),
+        };
+        Self {
+            name: activity_type.name.clone(),
+            done,
+            goal,
+        }
+    }
+}
+
+pub struct Report {
+    period: ReportPeriod,
+    start: CalendarDate,
+    end: CalendarDate,
+    // Sorted by name.
+    pub activities: Vec<ActivityReport>,
+    // The days on which the most and the fewest activities were done, with
+    // the number done, or None if there are no activities. Frozen days
+    // aren't counted, and ties go to the earliest day.
+    pub best_day: Option<(CalendarDate, usize)>,
+    pub worst_day: Option<(CalendarDate, usize)>,
+}
+
+impl Report {
+    /**
+     * Report on the period ending on `end`. Archived activities are only
+     * included if they were loaded.
+     */
+    pub fn new(
+        period: ReportPeriod,
+        end: CalendarDate,
+        activity_types: &ActivityTypesStore,
+        activities: &ActivitiesStore,
+    ) -> Self {
+        let start = period.start(end);
+        let mut types = activity_types.activity_types();
+        types.sort_by_key(|activity_type| activity_type.name.to_lowercase());
+        let reports = types
+            .iter()
+            .map(|activity_type| ActivityReport::new(activity_type, activities, start, end))
+            .collect();
+
+        let mut best_day: Option<(CalendarDate, usize)> = None;
+        let mut worst_day: Option<(CalendarDate, usize)> = None;
+        if !types.is_empty() {
+            for date in start.iter_days().take_while(|date| *date <= end) {
+                if activities.is_frozen(date) {
+                    continue;
+                }
+                let done = types
+                    .iter()
+                    .filter(|activity_type| activities.activity_count(date, activity_type.id) > 0)
+                    .count();
+                if best_day.is_none_or(|(_, best)| done > best) {
+                    best_day = Some((date, done));
+                }
+                if worst_day.is_none_or(|(_, worst)| done < worst) {
+                    worst_day = Some((date, done));
+                }
+            }
+        }
+
+        Self {
+            period,
+            start,
+            end,
+            activities: reports,
+            best_day,
+            worst_day,
+        }
+    }
+
+    /**
+     * Completions towards every activity's goal, not counting those past it,
+     * and the sum of the goals.
+     */
+    pub fn overall(&self) -> (usize, usize) {
+        self.activities.iter().fold((0, 0), |(done, goal), report| {
+            (done + report.done.min(report.goal), goal + report.goal)
+        })
+    }
+}
+
+/**
+ * A share as a rounded percentage (e.g. "71%"), or "n/a" for a share of
+ * nothing.
+ */
+fn format_percentage(done: usize, goal: usize) -> String {
+    match goal {
+        0 => String::from("n/a"),
+        goal => format!("{}%", (done * 100 + goal / 2) / goal),
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Daila report: {} {} to {}",
+            self.period.label(),
+            self.start.format("%Y-%m-%d"),
+            self.end.format("%Y-%m-%d")
+        )?;
+        for report in &self.activities {
+            writeln!(
+                f,
+                "{}: {}/{} ({})",
+                report.name,
+                report.done,
+                report.goal,
+                format_percentage(report.done, report.goal)
+            )?;
+        }
+        let total = self.activities.len();
+        for (label, day) in [("Best day", self.best_day), ("Worst day", self.worst_day)] {
+            match day {
+                Some((date, done)) => writeln!(
+                    f,
+                    "{}: {} ({} of {} activities)",
+                    label,
+                    date.format("%Y-%m-%d"),
+                    done,
+                    total
+                )?,
+                None => writeln!(f, "{}: none", label)?,
+            }
+        }
+        let (done, goal) = self.overall();
+        writeln!(
+            f,
+            "Overall: {}/{} ({})",
+            done,
+            goal,
+            format_percentage(done, goal)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+
+    use super::*;
+    use crate::activites::Activity;
+
+    fn date(day: u32) -> CalendarDate {
+        CalendarDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    /**
+     * The week of the example at the top of the module, from Monday the 4th
+     * to Sunday the 10th of March 2024: Meditate is done every weekday, and
+     * Read, scheduled on Mondays, Wednesdays and Fridays, on Wednesday and
+     * Friday.
+     */
+    fn example() -> (ActivityTypesStore, ActivitiesStore) {
+        let mut activity_types = ActivityTypesStore::default();
+        let meditate = activity_types.create_new_activity(String::from("Meditate"));
+        let read = activity_types.create_new_activity(String::from("Read"));
+        activity_types.update_schedule(read, vec![Weekday::Mon, Weekday::Wed, Weekday::Fri]);
+
+        let mut activities = ActivitiesStore::default();
+        for day in 4..=8 {
+            activities.add_activity(Activity::new(meditate, date(day)));
+        }
+        for day in [6, 8] {
+            activities.add_activity(Activity::new(read, date(day)));
+        }
+        (activity_types, activities)
+    }
+
+    #[test]
+    fn prints_each_line_as_documented() {
+        let (activity_types, activities) = example();
+        let report = Report::new(ReportPeriod::Week, date(10), &activity_types, &activities);
+        assert_eq!(
+            report.to_string(),
+            "Daila report: week 2024-03-04 to 2024-03-10\n\
+             Meditate: 5/7 (71%)\n\
+             Read: 2/3 (67%)\n\
+             Best day: 2024-03-06 (2 of 2 activities)\n\
+             Worst day: 2024-03-09 (0 of 2 activities)\n\
+             Overall: 7/10 (70%)\n"
+        );
+    }
+
+    #[test]
+    fn reports_on_the_month_ending_on_a_date() {
+        let (activity_types, activities) = example();
+        let report = Report::new(ReportPeriod::Month, date(10), &activity_types, &activities);
+        assert!(report
+            .to_string()
+            .starts_with("Daila report: month 2024-02-11 to 2024-03-10\n"));
+        assert_eq!(report.activities[0].goal, 29);
+        assert_eq!(report.activities[0].done, 5);
+    }
+
+    #[test]
+    fn reports_nothing_without_activity_types() {
+        let report = Report::new(
+            ReportPeriod::Week,
+            date(10),
+            &ActivityTypesStore::default(),
+            &ActivitiesStore::default(),
+        );
+        assert_eq!(
+            report.to_string(),
+            "Daila report: week 2024-03-04 to 2024-03-10\n\
+             Best day: none\n\
+             Worst day: none\n\
+             Overall: 0/0 (n/a)\n"
+        );
+    }
+
+    #[test]
+    fn reports_activity_types_never_done() {
+        let (activity_types, _) = example();
+        let report = Report::new(
+            ReportPeriod::Week,
+            date(10),
+            &activity_types,
+            &ActivitiesStore::default(),
+        );
+        assert_eq!(
+            report.to_string(),
+            "Daila report: week 2024-03-04 to 2024-03-10\n\
+             Meditate: 0/7 (0%)\n\
+             Read: 0/3 (0%)\n\
+             Best day: 2024-03-04 (0 of 2 activities)\n\
+             Worst day: 2024-03-04 (0 of 2 activities)\n\
+             Overall: 0/10 (0%)\n"
+        );
+    }
+
+    #[test]
+    fn counts_completions_towards_weekly_targets() {
+        let mut activity_types = ActivityTypesStore::default();
+        let run = activity_types.create_new_activity(String::from("Run"));
+        activity_types.update_weekly_target(run, Some(3));
+        let mut activities = ActivitiesStore::default();
+        for day in [4, 4, 5, 7] {
+            activities.add_activity(Activity::new(run, date(day)));
+        }
+
+        let report = Report::new(ReportPeriod::Week, date(10), &activity_types, &activities);
+        assert_eq!(report.activities[0].done, 4);
+        assert_eq!(report.activities[0].goal, 3);
+        // Completions past the goal don't make up for other activities.
+        assert_eq!(report.overall(), (3, 3));
+        assert!(report.to_string().contains("Run: 4/3 (133%)\n"));
+
+        // Over a month, the target is spread over its 29 days.
+        let report = Report::new(ReportPeriod::Month, date(10), &activity_types, &activities);
+        assert_eq!(report.activities[0].goal, 13);
+    }
+
+    #[test]
+    fn leaves_out_frozen_days() {
+        let (activity_types, mut activities) = example();
+        activities.freeze(date(9), date(10));
+        let report = Report::new(ReportPeriod::Week, date(10), &activity_types, &activities);
+        assert_eq!(
+            report.to_string(),
+            "Daila report: week 2024-03-04 to 2024-03-10\n\
+             Meditate: 5/5 (100%)\n\
+             Read: 2/3 (67%)\n\
+             Best day: 2024-03-06 (2 of 2 activities)\n\
+             Worst day: 2024-03-04 (1 of 2 activities)\n\
+             Overall: 7/8 (88%)\n"
+        );
+
+        // Frozen days lower weekly targets too.
+        let mut activity_types = ActivityTypesStore::default();
+        let run = activity_types.create_new_activity(String::from("Run"));
+        activity_types.update_weekly_target(run, Some(3));
+        activities.freeze(date(4), date(6));
+        // Two days of the week aren't frozen.
+        let report = Report::new(ReportPeriod::Week, date(10), &activity_types, &activities);
+        assert_eq!(report.activities[0].goal, 1);
+    }
+}
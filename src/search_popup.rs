@@ -0,0 +1,221 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, BorderType, Borders, StatefulWidget, Widget},
+};
+use std::cmp::Reverse;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    activites::ActivityId,
+    activity_selector::fuzzy_score,
+    heatmap::CalendarDate,
+    popup::Popup,
+    text_input::{TextInput, TextInputState},
+};
+
+// Width of the popup.
+const SEARCH_POPUP_WIDTH: u16 = 60;
+// Most results listed at once.
+const MAX_RESULTS: usize = 10;
+
+#[derive(Default)]
+pub struct SearchPopup {}
+
+pub enum SearchPopupAction {
+    SelectActivity(ActivityId),
+    GotoDate(CalendarDate),
+    Exit,
+}
+
+/**
+ * Something that can be searched for, and what is done when it is chosen.
+ */
+#[derive(Clone)]
+pub enum SearchCandidate {
+    Activity(ActivityId, String),
+    // A date, and a description of it (e.g. what was done on it).
+    Date(CalendarDate, String),
+}
+
+impl SearchCandidate {
+    /**
+     * The text matched against the search.
+     */
+    fn label(&self) -> &str {
+        match self {
+            SearchCandidate::Activity(_, name) => name,
+            SearchCandidate::Date(_, description) => description,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            SearchCandidate::Activity(..) => "activity",
+            SearchCandidate::Date(..) => "date",
+        }
+    }
+
+    fn action(&self) -> SearchPopupAction {
+        match self {
+            SearchCandidate::Activity(activity_id, _) => {
+                SearchPopupAction::SelectActivity(*activity_id)
+            }
+            SearchCandidate::Date(date, _) => SearchPopupAction::GotoDate(*date),
+        }
+    }
+}
+
+/**
+ * State for a popup searching activities and dates at once.
+ */
+pub struct SearchPopupState {
+    input: TextInputState,
+    candidates: Vec<SearchCandidate>,
+    // Indices of the candidates matching the search, best matches first.
+    results: Vec<usize>,
+    // Index in the results of the selected result.
+    selected: usize,
+}
+
+impl SearchPopupState {
+    pub fn new(candidates: Vec<SearchCandidate>) -> Self {
+        let mut state = Self {
+            input: TextInputState::default().placeholder("Search activities and dates"),
+            candidates,
+            results: vec![],
+            selected: 0,
+        };
+        state.update_results();
+        state
+    }
+
+    /**
+     * Match the candidates against the search, selecting the best match.
+     */
+    fn update_results(&mut self) {
+        let search = self.input.text();
+        let mut scored: Vec<(u32, usize)> = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(index, candidate)| Some((fuzzy_score(search, candidate.label())?, index)))
+            .collect();
+        // Stable, so equally good matches keep the order of the candidates.
+        scored.sort_by_key(|(score, _)| Reverse(*score));
+        self.results = scored.into_iter().map(|(_, index)| index).collect();
+        self.selected = 0;
+    }
+
+    fn selected_candidate(&self) -> Option<&SearchCandidate> {
+        let index = self.results.get(self.selected)?;
+        self.candidates.get(*index)
+    }
+}
+
+impl Popup<SearchPopupState> for SearchPopup {
+    type Action = SearchPopupAction;
+
+    fn handle_event(event: &Event, state: &mut SearchPopupState) -> Option<Self::Action> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        match key_event.code {
+            KeyCode::Esc => return Some(SearchPopupAction::Exit),
+            KeyCode::Enter => return state.selected_candidate().map(SearchCandidate::action),
+            KeyCode::Up => state.selected = state.selected.saturating_sub(1),
+            KeyCode::Down => {
+                state.selected = (state.selected + 1).min(state.results.len().saturating_sub(1))
+            }
+            _ => {
+                if state.input.handle_key(*key_event) {
+                    state.update_results();
+                }
+            }
+        }
+        None
+    }
+
+    fn desired_size(&self, _state: &SearchPopupState, _area: &Rect) -> (u16, u16) {
+        // Borders, the search with a blank line above and below it, and the
+        // results.
+        (SEARCH_POPUP_WIDTH, MAX_RESULTS as u16 + 5)
+    }
+}
+
+impl StatefulWidget for SearchPopup {
+    type State = SearchPopupState;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title("  Search  ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title_alignment(Alignment::Center)
+            .style(Style::default());
+        block.render(area, buffer);
+        if area.width < 6 || area.height < 5 {
+            return;
+        }
+
+        // Below the top border and a blank line.
+        let input_area = Rect::new(area.x + 2, area.y + 2, area.width - 4, 1);
+        buffer.set_style(input_area, Style::default().bg(Color::Black));
+        let text_area = Rect::new(input_area.x + 1, input_area.y, input_area.width - 2, 1);
+        TextInput::default()
+            .focused(true)
+            .render(text_area, buffer, &mut state.input);
+
+        // Below the search and a blank line, above the bottom border.
+        let results_area = Rect::new(
+            area.x + 2,
+            area.y + 4,
+            area.width - 4,
+            area.height.saturating_sub(5),
+        );
+        if state.results.is_empty() {
+            buffer.set_stringn(
+                results_area.x,
+                results_area.y,
+                "No matches",
+                results_area.width as usize,
+                Style::default().fg(Color::Gray),
+            );
+            return;
+        }
+        let visible = (results_area.height as usize).min(MAX_RESULTS);
+        // Scroll so that the selected result is listed.
+        let first = (state.selected + 1).saturating_sub(visible);
+        for (row, &index) in state.results.iter().skip(first).take(visible).enumerate() {
+            let candidate = &state.candidates[index];
+            let y = results_area.y + row as u16;
+            let kind = candidate.kind();
+            let width = results_area.width as usize;
+            // +1: Space between the label and its kind.
+            let label_width = width.saturating_sub(kind.width() + 1);
+            buffer.set_stringn(
+                results_area.x,
+                y,
+                candidate.label(),
+                label_width,
+                Style::default(),
+            );
+            if kind.width() < width {
+                buffer.set_string(
+                    results_area.right() - kind.width() as u16,
+                    y,
+                    kind,
+                    Style::default().fg(Color::DarkGray),
+                );
+            }
+            if first + row == state.selected {
+                buffer.set_style(
+                    Rect::new(results_area.x, y, results_area.width, 1),
+                    Style::default().add_modifier(Modifier::REVERSED),
+                );
+            }
+        }
+    }
+}
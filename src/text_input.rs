@@ -0,0 +1,382 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::StatefulWidget,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/**
+ * Editable text, and the byte index in it that characters are inserted at.
+ */
+#[derive(Default)]
+pub struct TextInputState {
+    text: String,
+    cursor: usize,
+    // Displayed in place of the text while there is none.
+    placeholder: String,
+    // Most graphemes the text can have, if limited.
+    max_length: Option<usize>,
+    // Column of the text at the left of the input, when the text is wider
+    // than the input.
+    scroll: usize,
+}
+
+impl TextInputState {
+    pub fn new(text: String) -> Self {
+        Self {
+            cursor: text.len(),
+            text,
+            ..Self::default()
+        }
+    }
+
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_owned();
+        self
+    }
+
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /**
+     * Insert a character at the cursor, moving the cursor past it. Does
+     * nothing if the text is already as long as it can be.
+     */
+    fn insert_char(&mut self, c: char) {
+        if let Some(max_length) = self.max_length {
+            if self.text.graphemes(true).count() >= max_length {
+                return;
+            }
+        }
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /**
+     * Length in bytes of the grapheme (user-perceived character, e.g. an
+     * emoji or a letter with combining accents) before the cursor.
+     */
+    fn grapheme_before_cursor_len(&self) -> usize {
+        self.text[..self.cursor]
+            .graphemes(true)
+            .next_back()
+            .map_or(0, str::len)
+    }
+
+    /**
+     * Length in bytes of the grapheme after the cursor.
+     */
+    fn grapheme_after_cursor_len(&self) -> usize {
+        self.text[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(0, str::len)
+    }
+
+    /**
+     * Edit the text, or move the cursor, for a key. Returns false if the key
+     * isn't for editing text.
+     */
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> bool {
+        match key_event.code {
+            // Control characters are shortcuts, not text.
+            KeyCode::Char(_)
+                if key_event
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                return false
+            }
+            KeyCode::Char(c) => self.insert_char(c),
+            KeyCode::Backspace => {
+                let len = self.grapheme_before_cursor_len();
+                self.cursor -= len;
+                self.text.replace_range(self.cursor..self.cursor + len, "");
+            }
+            KeyCode::Delete => {
+                let len = self.grapheme_after_cursor_len();
+                self.text.replace_range(self.cursor..self.cursor + len, "");
+            }
+            KeyCode::Left => self.cursor -= self.grapheme_before_cursor_len(),
+            KeyCode::Right => self.cursor += self.grapheme_after_cursor_len(),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.text.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    /**
+     * Column of the cursor, counting wide characters as two columns.
+     */
+    fn cursor_column(&self) -> usize {
+        self.text[..self.cursor].width()
+    }
+
+    /**
+     * Scroll so that the cursor is within an input `width` columns wide.
+     */
+    fn scroll_to_cursor(&mut self, width: usize) {
+        let column = self.cursor_column();
+        if column < self.scroll {
+            self.scroll = column;
+        } else if column >= self.scroll + width {
+            // +1: The cursor is drawn on the column after the text.
+            self.scroll = column + 1 - width;
+        }
+        // Don't leave empty columns at the right when there is text to fill
+        // them, e.g. after deleting from the end.
+        let text_width = self.text.width() + 1;
+        self.scroll = self.scroll.min(text_width.saturating_sub(width));
+    }
+}
+
+/**
+ * A single line of editable text, scrolled horizontally to keep the cursor
+ * in view.
+ */
+#[derive(Default)]
+pub struct TextInput {
+    // Draw the cursor.
+    focused: bool,
+}
+
+impl TextInput {
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+}
+
+impl StatefulWidget for TextInput {
+    type State = TextInputState;
+
+    fn render(self, area: Rect, buffer: &mut Buffer, state: &mut Self::State) {
+        let width = area.width as usize;
+        if width == 0 || area.height == 0 {
+            return;
+        }
+        if state.text.is_empty() {
+            state.scroll = 0;
+            buffer.set_stringn(
+                area.x,
+                area.y,
+                &state.placeholder,
+                width,
+                Style::default().fg(Color::DarkGray),
+            );
+        } else {
+            state.scroll_to_cursor(width);
+            // Graphemes cut by the left edge are left out, as are those cut
+            // by the right edge.
+            let mut column = 0;
+            for grapheme in state.text.graphemes(true) {
+                let grapheme_width = grapheme.width();
+                if column >= state.scroll && column + grapheme_width <= state.scroll + width {
+                    buffer.set_string(
+                        area.x + (column - state.scroll) as u16,
+                        area.y,
+                        grapheme,
+                        Style::default(),
+                    );
+                }
+                column += grapheme_width;
+            }
+        }
+
+        if self.focused {
+            let column = state.cursor_column() - state.scroll;
+            if column < width {
+                buffer
+                    .get_mut(area.x + column as u16, area.y)
+                    .set_style(Style::default().add_modifier(Modifier::REVERSED));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(state: &mut TextInputState, code: KeyCode) -> bool {
+        state.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_text(state: &mut TextInputState, text: &str) {
+        for c in text.chars() {
+            press(state, KeyCode::Char(c));
+        }
+    }
+
+    /**
+     * The symbol of each cell of a focused input `width` columns wide.
+     */
+    fn render(state: &mut TextInputState, width: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, 1);
+        let mut buffer = Buffer::empty(area);
+        TextInput::default()
+            .focused(true)
+            .render(area, &mut buffer, state);
+        buffer
+            .content
+            .iter()
+            .map(|cell| cell.symbol.clone())
+            .collect()
+    }
+
+    #[test]
+    fn backspaces_whole_emoji() {
+        let mut state = TextInputState::default();
+        // A thumbs up with a skin tone, and a family joined by zero width
+        // joiners, are each typed as several characters.
+        type_text(&mut state, "Run 👍🏽👨\u{200d}👩\u{200d}👧");
+        press(&mut state, KeyCode::Backspace);
+        assert_eq!(state.text(), "Run 👍🏽");
+        press(&mut state, KeyCode::Backspace);
+        assert_eq!(state.text(), "Run ");
+        assert_eq!(state.cursor, state.text().len());
+    }
+
+    #[test]
+    fn places_the_cursor_after_wide_characters() {
+        let mut state = TextInputState::default();
+        type_text(&mut state, "日本語");
+        assert_eq!(state.cursor_column(), 6);
+        press(&mut state, KeyCode::Left);
+        assert_eq!(state.cursor_column(), 4);
+        press(&mut state, KeyCode::Backspace);
+        assert_eq!(state.text(), "日語");
+        assert_eq!(state.cursor_column(), 2);
+    }
+
+    #[test]
+    fn edits_letters_with_combining_accents_whole() {
+        let mut state = TextInputState::default();
+        // "Café", with the accent typed separately.
+        type_text(&mut state, "Cafe\u{301}s");
+        press(&mut state, KeyCode::Left);
+        press(&mut state, KeyCode::Left);
+        assert_eq!(state.cursor_column(), 3);
+        press(&mut state, KeyCode::Delete);
+        assert_eq!(state.text(), "Cafs");
+        type_text(&mut state, "e\u{301}");
+        press(&mut state, KeyCode::Backspace);
+        assert_eq!(state.text(), "Cafs");
+        assert_eq!(state.cursor_column(), 3);
+    }
+
+    #[test]
+    fn inserts_and_deletes_in_the_middle() {
+        let mut state = TextInputState::new(String::from("Red"));
+        press(&mut state, KeyCode::Left);
+        type_text(&mut state, "a");
+        assert_eq!(state.text(), "Read");
+        press(&mut state, KeyCode::Left);
+        press(&mut state, KeyCode::Left);
+        press(&mut state, KeyCode::Delete);
+        assert_eq!(state.text(), "Rad");
+        press(&mut state, KeyCode::Backspace);
+        assert_eq!(state.text(), "ad");
+        assert_eq!(state.cursor, 0);
+        // Nothing to delete before the start.
+        press(&mut state, KeyCode::Backspace);
+        assert_eq!(state.text(), "ad");
+    }
+
+    #[test]
+    fn moves_to_the_start_and_end() {
+        let mut state = TextInputState::new(String::from("Read"));
+        press(&mut state, KeyCode::Home);
+        type_text(&mut state, "To ");
+        assert_eq!(state.text(), "To Read");
+        press(&mut state, KeyCode::End);
+        type_text(&mut state, "!");
+        assert_eq!(state.text(), "To Read!");
+    }
+
+    #[test]
+    fn stops_at_the_max_length() {
+        let mut state = TextInputState::default().max_length(4);
+        type_text(&mut state, "Reading");
+        assert_eq!(state.text(), "Read");
+        // Graphemes, rather than characters or bytes, are counted.
+        let mut state = TextInputState::default().max_length(2);
+        type_text(&mut state, "e\u{301}日本");
+        assert_eq!(state.text(), "e\u{301}日");
+    }
+
+    #[test]
+    fn ignores_control_shortcuts() {
+        let mut state = TextInputState::default();
+        let handled = state.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert!(!handled);
+        assert_eq!(state.text(), "");
+    }
+
+    #[test]
+    fn scrolls_to_keep_the_cursor_in_view() {
+        let mut state = TextInputState::default();
+        type_text(&mut state, "Practice the piano");
+        // The cursor is after the text, on the last column.
+        assert_eq!(render(&mut state, 8).concat(), "e piano ");
+        press(&mut state, KeyCode::Home);
+        assert_eq!(render(&mut state, 8).concat(), "Practice");
+        press(&mut state, KeyCode::End);
+        assert_eq!(render(&mut state, 8).concat(), "e piano ");
+    }
+
+    #[test]
+    fn draws_the_placeholder_without_text() {
+        let mut state = TextInputState::default().placeholder("Name");
+        assert_eq!(render(&mut state, 6).concat(), "Name  ");
+        type_text(&mut state, "R");
+        assert_eq!(render(&mut state, 6).concat(), "R     ");
+    }
+
+    #[test]
+    fn renders_each_character_in_its_own_cell() {
+        let mut state = TextInputState::new(String::from("Run"));
+        assert_eq!(render(&mut state, 5), ["R", "u", "n", " ", " "]);
+    }
+
+    #[test]
+    fn renders_multi_byte_graphemes_in_one_cell() {
+        // "é" as a letter and a combining accent, and a precomposed "ü".
+        let mut state = TextInputState::new(String::from("Cafe\u{301} Zü"));
+        assert_eq!(
+            render(&mut state, 8),
+            ["C", "a", "f", "e\u{301}", " ", "Z", "ü", " "]
+        );
+    }
+
+    #[test]
+    fn renders_wide_graphemes_across_two_cells() {
+        let mut state = TextInputState::new(String::from("日本"));
+        assert_eq!(render(&mut state, 6), ["日", " ", "本", " ", " ", " "]);
+    }
+
+    #[test]
+    fn scrolls_long_text_to_keep_the_cursor_visible() {
+        let mut state = TextInputState::new(String::from("Meditate"));
+        // The last four characters, and the cursor after them.
+        assert_eq!(render(&mut state, 5), ["t", "a", "t", "e", " "]);
+    }
+
+    #[test]
+    fn leaves_out_wide_graphemes_cut_by_the_edge() {
+        let mut state = TextInputState::new(String::from("a日本"));
+        state.handle_key(KeyEvent::from(KeyCode::Home));
+        // "本" would be cut by the right edge.
+        assert_eq!(render(&mut state, 4), ["a", "日", " ", " "]);
+    }
+}